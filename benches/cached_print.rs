@@ -0,0 +1,83 @@
+//! Compares a full `Graph::print` of a large graph with a `CachedPrinter`
+//! re-printing it unchanged and after a change to a single vertex. The three
+//! are timed in turn on every iteration and the median of each is reported,
+//! so they see the same machine load.
+//!
+//! Run with `cargo bench --bench cached_print`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use graphl_parser::ast::{GTensor, GVertex, Graph, Name, Vertex};
+use graphl_parser::printer::{CachedPrinter, PrintOptions};
+
+const ITERATIONS: u32 = 200;
+const DEPTH: u32 = 14;
+
+/// A balanced tensor of `2^depth` vertices, numbered from `first`.
+fn tensor(depth: u32, first: usize) -> Graph {
+    if depth == 0 {
+        return Graph::Vertex(GVertex {
+            graph: Box::new(Graph::Nil),
+            vertex: Vertex {
+                name: Name::VVar {
+                    value: format!("v{first}"),
+                },
+            },
+        });
+    }
+    let half = 1 << (depth - 1);
+    Graph::Tensor(GTensor {
+        graph_1: Box::new(tensor(depth - 1, first)),
+        graph_2: Box::new(tensor(depth - 1, first + half)),
+    })
+}
+
+/// Renames the last vertex of `graph`, the one edit between two prints.
+fn rename_last(mut graph: &mut Graph, value: String) {
+    loop {
+        match graph {
+            Graph::Tensor(tensor) => graph = &mut tensor.graph_2,
+            Graph::Vertex(vertex) => {
+                vertex.vertex.name = Name::VVar { value };
+                return;
+            }
+            _ => unreachable!("the benchmark graph holds only tensors and vertices"),
+        }
+    }
+}
+
+/// Times one call of `print`.
+fn time(print: impl FnOnce() -> String) -> Duration {
+    let start = Instant::now();
+    black_box(print());
+    start.elapsed()
+}
+
+fn median(mut samples: Vec<Duration>) -> Duration {
+    samples.sort();
+    samples[samples.len() / 2]
+}
+
+fn main() {
+    let opts = PrintOptions {
+        multiline: true,
+        ..PrintOptions::default()
+    };
+    let mut graph = tensor(DEPTH, 0);
+    let mut printer = CachedPrinter::new(opts.clone());
+    assert_eq!(printer.print(&graph), graph.print(&opts));
+
+    let (mut full, mut unchanged, mut edited) = (Vec::new(), Vec::new(), Vec::new());
+    for i in 0..ITERATIONS {
+        full.push(time(|| black_box(&graph).print(&opts)));
+        unchanged.push(time(|| printer.print(black_box(&graph))));
+        rename_last(&mut graph, format!("w{i}"));
+        edited.push(time(|| printer.print(black_box(&graph))));
+    }
+    assert_eq!(printer.print(&graph), graph.print(&opts));
+
+    for (label, samples) in [("full", full), ("unchanged", unchanged), ("edited", edited)] {
+        println!("{label:>10}: {:>12.1?} per print", median(samples));
+    }
+}
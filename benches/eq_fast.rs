@@ -0,0 +1,74 @@
+//! Compares `==` with `Fingerprinted::eq_fast` on large graphs that differ
+//! only in their last leaf, and reports the one-off cost of fingerprinting.
+//!
+//! Run with `cargo bench --bench eq_fast`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use graphl_parser::ast::{Fingerprinted, GTensor, GVertex, Graph, Name, Vertex};
+
+const ITERATIONS: u32 = 200;
+const DEPTH: u32 = 14;
+
+/// A balanced tensor of `2^depth` vertices, numbered from `first`.
+fn tensor(depth: u32, first: usize, last: &str) -> Graph {
+    if depth == 0 {
+        let name = if first == (1 << DEPTH) - 1 {
+            last.to_owned()
+        } else {
+            format!("v{first}")
+        };
+        return Graph::Vertex(GVertex {
+            graph: Box::new(Graph::Nil),
+            vertex: Vertex {
+                name: Name::VVar { value: name },
+            },
+        });
+    }
+    let half = 1 << (depth - 1);
+    Graph::Tensor(GTensor {
+        graph_1: Box::new(tensor(depth - 1, first, last)),
+        graph_2: Box::new(tensor(depth - 1, first + half, last)),
+    })
+}
+
+fn measure(mut eq: impl FnMut() -> bool) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert!(!black_box(eq()));
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let left = tensor(DEPTH, 0, "left");
+    let right = tensor(DEPTH, 0, "right");
+
+    let start = Instant::now();
+    let fingerprinted = (
+        Fingerprinted::new(left.clone()),
+        Fingerprinted::new(right.clone()),
+    );
+    let fingerprinting = start.elapsed() / 2;
+
+    let runs: [(&str, Duration); 2] = [
+        ("==", measure(|| black_box(&left) == black_box(&right))),
+        (
+            "eq_fast",
+            measure(|| black_box(&fingerprinted.0).eq_fast(black_box(&fingerprinted.1))),
+        ),
+    ];
+
+    for (label, elapsed) in runs {
+        println!(
+            "{label:>8}: {:>12.1} ns/compare",
+            elapsed.as_nanos() as f64 / f64::from(ITERATIONS)
+        );
+    }
+    println!(
+        "{:>8}: {:>12.1} ns/graph, once",
+        "new",
+        fingerprinting.as_nanos() as f64
+    );
+}
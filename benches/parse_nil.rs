@@ -0,0 +1,29 @@
+//! Compares the nil fast path of `parse_str` against a nil graph that has to
+//! go through the C parser.
+//!
+//! Run with `cargo bench --bench parse_nil`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use graphl_parser::parse_str;
+
+const ITERATIONS: u32 = 100_000;
+
+fn measure(code: &str) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(parse_str(black_box(code)).unwrap());
+    }
+    start.elapsed()
+}
+
+fn main() {
+    for (label, code) in [("fast path", "{0}"), ("c parser", "{{0}}")] {
+        let elapsed = measure(code);
+        println!(
+            "{label:>10}: {:>8.1} ns/parse ({code:?})",
+            elapsed.as_nanos() as f64 / f64::from(ITERATIONS)
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Compares repeated walks of one graph with a fresh stack per walk
+//! (`Walker::visit`) against a stack buffer kept across walks
+//! (`Walker::visit_mut`).
+//!
+//! Run with `cargo bench --bench walker_reuse`.
+
+use std::convert::Infallible;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use graphl_parser::ast::{GTensor, GVertex, Graph, Name, Vertex};
+use graphl_parser::{Visitor, Walker};
+
+const ITERATIONS: u32 = 200;
+const WIDTH: usize = 1 << 14;
+
+/// A left-nested tensor of `WIDTH` vertices, whose walk keeps every right
+/// operand on the stack at once.
+fn tensor() -> Graph {
+    (0..WIDTH)
+        .map(|i| {
+            Graph::Vertex(GVertex {
+                graph: Box::new(Graph::Nil),
+                vertex: Vertex {
+                    name: Name::VVar {
+                        value: format!("v{i}"),
+                    },
+                },
+            })
+        })
+        .reduce(|graph_1, graph_2| {
+            Graph::Tensor(GTensor {
+                graph_1: Box::new(graph_1),
+                graph_2: Box::new(graph_2),
+            })
+        })
+        .unwrap()
+}
+
+struct CountVertices;
+
+impl<'a> Visitor<'a, usize, Infallible> for CountVertices {
+    fn visit_vertex(&self, acc: usize, _vertex: &'a GVertex) -> Result<usize, Infallible> {
+        Ok(acc + 1)
+    }
+}
+
+fn measure(mut walk: impl FnMut() -> usize) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert_eq!(black_box(walk()), WIDTH);
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let graph = tensor();
+    let walker = Walker::new(&graph);
+    let mut reused = Walker::new(&graph);
+
+    let runs: [(&str, Duration); 2] = [
+        ("fresh", measure(|| walker.visit(0, CountVertices))),
+        ("reused", measure(|| reused.visit_mut(0, CountVertices))),
+    ];
+
+    for (label, elapsed) in runs {
+        println!(
+            "{label:>8}: {:>12.1} ns/walk",
+            elapsed.as_nanos() as f64 / f64::from(ITERATIONS)
+        );
+    }
+}
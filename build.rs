@@ -3,6 +3,8 @@ use std::path::PathBuf;
 const INCLUDE_DIR: &str = "parser";
 const WRAPPER_HEADER_FILE: &str = "parser/wrapper.h";
 const BINDINGS_FILE: &str = "bindings.rs";
+#[cfg(feature = "proto")]
+const PROTO_FILE: &str = "proto/graph.proto";
 
 fn main() {
     println!("cargo:rerun-if-changed={INCLUDE_DIR}");
@@ -10,6 +12,15 @@ fn main() {
     let target = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
     compile_in_parser(&target);
     generate_bindings(&target);
+    #[cfg(feature = "proto")]
+    generate_proto();
+}
+
+#[cfg(feature = "proto")]
+fn generate_proto() {
+    println!("cargo:rerun-if-changed={PROTO_FILE}");
+
+    prost_build::compile_protos(&[PROTO_FILE], &["proto"]).unwrap()
 }
 
 fn generate_bindings(target: &str) {
@@ -51,6 +62,7 @@ fn compile_in_parser(target: &str) {
         "parser/Absyn.c",
         "parser/Buffer.c",
         "parser/Lexer.c",
+        "parser/ParseError.c",
         "parser/Parser.c",
         "parser/Printer.c",
         "parser/Skeleton.c",
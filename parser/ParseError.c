@@ -0,0 +1,18 @@
+#include "ParseError.h"
+
+#ifdef __wasm__
+static ParseError last_error;
+#else
+static _Thread_local ParseError last_error;
+#endif
+
+void psSetError(int line, int column, const char *message)
+{
+  last_error.line = line;
+  last_error.column = column;
+  last_error.message = message;
+}
+
+void psClearError(void) { last_error = (ParseError){0}; }
+
+ParseError psLastError(void) { return last_error; }
@@ -0,0 +1,21 @@
+#pragma once
+
+/* Not generated by BNFC: the generated parser reports syntax errors to
+   `psSetError` through the change in `etc/parser.patch`, which `cargo make
+   bnfc` applies after regenerating it. */
+
+/* Where the last failed parse on the calling thread stopped. `line` and
+   `column` are the location bison passes to `yyerror`: the first line is 1,
+   the first column of the first line is 1 and that of every later line is 0.
+   Both are 0 if no error was reported since the last `psClearError`.
+   `message` is a static string, or null. */
+typedef struct ParseError
+{
+  int line;
+  int column;
+  const char *message;
+} ParseError;
+
+void psSetError(int line, int column, const char *message);
+void psClearError(void);
+ParseError psLastError(void);
@@ -82,6 +82,7 @@
 #include "panic.h"
 
 #include "Absyn.h"
+#include "ParseError.h"
 
 #define YYMAXDEPTH 10000000
 
@@ -185,10 +186,9 @@ typedef enum yysymbol_kind_t yysymbol_kind_t;
 /* Second part of user prologue.  */
 void yyerror(YYLTYPE *loc, yyscan_t scanner, YYSTYPE *result, const char *msg)
 {
-  (void)loc;
   (void)scanner;
   (void)result;
-  (void)msg;
+  psSetError(loc->first_line, loc->first_column, msg);
 }
 
 int yyparse(yyscan_t scanner, YYSTYPE *result);
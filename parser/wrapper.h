@@ -1,6 +1,7 @@
 #pragma once
 
 #include "Absyn.h"
+#include "ParseError.h"
 #include "Parser.h"
 #include "Printer.h"
 #include "Skeleton.h"
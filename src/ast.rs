@@ -7,6 +7,48 @@ use tsify::Tsify;
 use crate::bindings;
 use crate::guard::{Guard, Guarded, ResourceConsumer};
 
+mod builder;
+mod canonical;
+mod collect;
+mod compare;
+mod metrics;
+mod namespace;
+pub(crate) mod node;
+mod parts;
+mod paths;
+mod policy;
+mod process;
+mod rewrite;
+mod rule;
+mod scope;
+mod search;
+mod subst;
+mod tensor;
+#[cfg(any(test, feature = "testing"))]
+mod testing;
+mod transform;
+mod validate;
+
+pub use self::builder::GraphBuilder;
+pub use self::collect::Interface;
+pub use self::compare::Fingerprinted;
+pub use self::metrics::Metrics;
+pub use self::namespace::{Namespace, NamespaceError, VarRole};
+pub use self::node::NodeKind;
+pub use self::parts::FromGraph;
+pub use self::paths::ShapeTree;
+pub use self::process::ProcessTerm;
+pub use self::rewrite::Match;
+pub use self::rule::Rewrite;
+pub use self::scope::{EdgeConflict, VarDiff};
+#[cfg(test)]
+pub(crate) use self::testing::THREE_EDGE_GRAPH;
+#[cfg(any(test, feature = "testing"))]
+pub use self::testing::{ROUNDTRIP_CORPUS, RoundTripFailure, verify_roundtrip};
+pub use self::validate::Diagnostic;
+#[cfg(feature = "json")]
+pub use crate::json::diff_json_patch;
+
 #[derive(Debug, Clone, Serialize, Deserialize, thiserror::Error)]
 #[serde(tag = "type")]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
@@ -22,6 +64,44 @@ pub enum Error {
     InvalidVariant { context: String },
     #[error("invalid graphl")]
     InvalidGraphL,
+    #[error("parse error at line {line}, column {column}: {message}")]
+    ParseFailed {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("json error: {message}")]
+    Json { message: String },
+    #[error("unknown node type: {tag}")]
+    UnknownNodeType { tag: String },
+    #[error("protobuf error: {message}")]
+    Proto { message: String },
+    #[error("no node at path: {path:?}")]
+    InvalidPath { path: Vec<usize> },
+    #[error("graphs differ in shape at path: {path:?}")]
+    ShapeMismatch { path: Vec<usize> },
+    #[error("name rejected by the validator: {name}")]
+    InvalidName { name: String },
+    #[error("invalid token at byte offset {offset}")]
+    InvalidToken { offset: usize },
+    #[error("invalid graphl in statement {index} at line {line}, column {column}: {message}")]
+    InvalidStatement {
+        index: usize,
+        offset: usize,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    #[error("not supported by the rholang backend: {context}")]
+    Unsupported { context: String },
+    #[error("rule {rule} does not match the graph at {path:?}")]
+    NoMatch { rule: String, path: Vec<usize> },
+    #[error("no fixed point reached within {steps} rewrite steps")]
+    StepLimitExceeded { steps: usize },
+    #[error("variable {var} is already used by the graphs being connected")]
+    VarInUse { var: String },
+    #[error("io error: {message}")]
+    Io { message: String },
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
@@ -175,6 +255,13 @@ impl TryFrom<Vertex> for Guard<bindings::Vertex> {
     }
 }
 
+/// A name in vertex, edge, rule or context position.
+///
+/// Variables keep the token class they were parsed from: `VVar` holds an
+/// `LVar` (`x`) and `GVar` a `UVar` (`X`). These are the only identifier
+/// tokens of the grammar. BNFC's built-in `Ident` shows up in the generated
+/// printer and visitor skeleton, but no production uses it, so the parser
+/// never produces one and there is nothing to represent.
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(tag = "type")]
 #[cfg_attr(target_arch = "wasm32", derive(Tsify))]
@@ -614,6 +701,35 @@ fn test_curly_braces_are_correctly_inserted() {
 
     let printed_graphl = crate::ast_to_graphl(ast.clone()).unwrap();
     let printed_ast = crate::parse_to_ast(printed_graphl).unwrap();
+    let displayed_ast = crate::parse_to_ast(ast.to_string()).unwrap();
+
+    assert_eq!(ast, printed_ast);
+    assert_eq!(ast, displayed_ast)
+}
+
+#[test]
+fn test_variable_classes_survive_c_round_trip() {
+    let graphl = "<x> | <X> | n(let a = <a> in y | 0, let b = <B> in 0) * let G = <g> | 0 in 0";
+    let ast = crate::parse_to_ast(graphl.to_owned()).unwrap();
+
+    let c_ast: Guard<bindings::Graph> = ast.clone().try_into().unwrap();
+    let round_tripped: Graph = (*c_ast).try_into().unwrap();
+
+    assert_eq!(ast, round_tripped)
+}
+
+#[test]
+fn test_structurally_equal_graphs_hash_alike() {
+    use std::collections::HashSet;
+
+    let graphl = "<@{<a> | 0}> | 0 * e(let x = <b> in x | 0, let y = <@<c>> in 0)";
+    let mut graphs = HashSet::new();
+    graphs.insert(crate::parse_to_ast(graphl.to_owned()).unwrap());
+    graphs.insert(crate::parse_to_ast(graphl.to_owned()).unwrap());
+
+    assert_eq!(graphs.len(), 1);
+
+    graphs.insert(crate::parse_to_ast("<@{<b> | 0}> | 0".to_owned()).unwrap());
 
-    assert_eq!(ast, printed_ast)
+    assert_eq!(graphs.len(), 2)
 }
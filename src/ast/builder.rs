@@ -0,0 +1,206 @@
+//! A fluent way to assemble graphs in Rust code.
+//!
+//! [`GraphBuilder`] grows a graph from its end: start from [`nil`] or a
+//! small graph and prefix vertices, variables and bindings in front of it,
+//! so `GraphBuilder::nil().prefix_vertex("b").prefix_vertex("a")` is
+//! `<a> | <b> | 0`. Vertex names are given as their spelling and become
+//! [`Name::GVar`] when spelled as an uppercase variable, [`Name::VVar`]
+//! otherwise. Quoted graphs are made with [`Name::quote_graph`] and
+//! [`Vertex::from_quoted_graph`], or prefixed directly with
+//! [`prefix_quoted_vertex`](GraphBuilder::prefix_quoted_vertex). The free
+//! functions of [`crate::build`] cover the remaining constructs, and the
+//! builder converts to and from [`Graph`] to mix the two.
+//!
+//! [`nil`]: GraphBuilder::nil
+
+use crate::ast::namespace::vertex_name;
+use crate::ast::{Binding, GEdgeAnon, GTensor, GVar, GVertex, Graph, Name, Vertex};
+
+/// A graph under construction, see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphBuilder {
+    graph: Graph,
+}
+
+impl GraphBuilder {
+    /// `0`.
+    pub fn nil() -> Self {
+        Graph::Nil.into()
+    }
+
+    /// `<name> | 0`.
+    pub fn vertex(name: impl Into<String>) -> Self {
+        Self::nil().prefix_vertex(name)
+    }
+
+    /// `var | 0`.
+    pub fn var(var: impl Into<String>) -> Self {
+        Self::nil().prefix_var(var)
+    }
+
+    /// `let var = <vertex> in 0`.
+    pub fn nominate(var: impl Into<String>, vertex: impl Into<String>) -> Self {
+        Self::nil().prefix_nominate(var, vertex)
+    }
+
+    /// `(binding_1, binding_2)`, with bindings made by [`bind`](Self::bind).
+    pub fn edge_anon(binding_1: Binding, binding_2: Binding) -> Self {
+        Graph::EdgeAnon(GEdgeAnon {
+            binding_1,
+            binding_2,
+        })
+        .into()
+    }
+
+    /// `graph_1 * graph_2`.
+    pub fn tensor(graph_1: impl Into<Graph>, graph_2: impl Into<Graph>) -> Self {
+        Graph::Tensor(GTensor {
+            graph_1: Box::new(graph_1.into()),
+            graph_2: Box::new(graph_2.into()),
+        })
+        .into()
+    }
+
+    /// `<name> | self`.
+    pub fn prefix_vertex(self, name: impl Into<String>) -> Self {
+        Graph::Vertex(GVertex {
+            graph: Box::new(self.graph),
+            vertex: Vertex {
+                name: vertex_name(name.into()),
+            },
+        })
+        .into()
+    }
+
+    /// `<@{graph}> | self`.
+    pub fn prefix_quoted_vertex(self, graph: impl Into<Graph>) -> Self {
+        Graph::Vertex(GVertex {
+            graph: Box::new(self.graph),
+            vertex: Vertex::from_quoted_graph(graph.into()),
+        })
+        .into()
+    }
+
+    /// `var | self`.
+    pub fn prefix_var(self, var: impl Into<String>) -> Self {
+        Graph::Var(GVar {
+            graph: Box::new(self.graph),
+            var: var.into(),
+        })
+        .into()
+    }
+
+    /// `let var = <vertex> in self`, as a graph of its own.
+    pub fn prefix_nominate(self, var: impl Into<String>, vertex: impl Into<String>) -> Self {
+        Graph::Nominate(self.bind(var, vertex)).into()
+    }
+
+    /// `let var = <vertex> in self`, as one side of an edge.
+    pub fn bind(self, var: impl Into<String>, vertex: impl Into<String>) -> Binding {
+        Binding {
+            graph: Box::new(self.graph),
+            var: var.into(),
+            vertex: Vertex {
+                name: vertex_name(vertex.into()),
+            },
+        }
+    }
+
+    /// Returns the graph built so far.
+    pub fn build(self) -> Graph {
+        self.graph
+    }
+}
+
+impl Name {
+    /// `@{graph}`, the name of a quoted graph.
+    pub fn quote_graph(graph: Graph) -> Name {
+        Name::QuoteGraph {
+            value: Box::new(graph),
+        }
+    }
+}
+
+impl Vertex {
+    /// `<@{graph}>`, a vertex named by a quoted graph.
+    pub fn from_quoted_graph(graph: Graph) -> Vertex {
+        Vertex {
+            name: Name::quote_graph(graph),
+        }
+    }
+}
+
+impl From<Graph> for GraphBuilder {
+    fn from(graph: Graph) -> Self {
+        GraphBuilder { graph }
+    }
+}
+
+impl From<GraphBuilder> for Graph {
+    fn from(builder: GraphBuilder) -> Self {
+        builder.build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GraphBuilder;
+    use crate::ast::{GVertex, Graph, Name, Vertex};
+    use crate::printer::PrintOptions;
+    use crate::{ast_to_graphl, parse_to_ast};
+
+    #[test]
+    fn test_prefix_vertex_desugars_to_gvertex() {
+        let graph = GraphBuilder::nil().prefix_vertex("a").build();
+
+        assert_eq!(
+            graph,
+            Graph::Vertex(GVertex {
+                graph: Box::new(Graph::Nil),
+                vertex: Vertex {
+                    name: Name::VVar { value: "a".into() },
+                },
+            })
+        );
+        assert_eq!(GraphBuilder::vertex("a").build(), graph);
+    }
+
+    #[test]
+    fn test_quoted_graph_vertex_round_trips_through_printer() {
+        let quoted = GraphBuilder::tensor(GraphBuilder::vertex("b"), GraphBuilder::var("x"));
+        let graph = GraphBuilder::nil()
+            .prefix_quoted_vertex(quoted.clone())
+            .build();
+
+        assert_eq!(
+            graph,
+            Graph::Vertex(GVertex {
+                graph: Box::new(Graph::Nil),
+                vertex: Vertex::from_quoted_graph(quoted.build()),
+            })
+        );
+
+        let printed = graph.print(&PrintOptions::default());
+        assert_eq!(printed, "< @ < b > | 0 * x | 0 > | 0");
+        assert_eq!(parse_to_ast(printed).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_built_graph_round_trips_through_graphl() {
+        let graph = GraphBuilder::tensor(
+            GraphBuilder::edge_anon(
+                GraphBuilder::var("x").bind("a", "a"),
+                GraphBuilder::vertex("C").prefix_vertex("b").bind("b", "B"),
+            ),
+            GraphBuilder::tensor(
+                GraphBuilder::nominate("d", "d").prefix_var("y"),
+                GraphBuilder::nil(),
+            ),
+        )
+        .build();
+
+        assert_eq!(graph.validate_name_namespaces(), Ok(()));
+        let graphl = ast_to_graphl(graph.clone()).unwrap();
+        assert_eq!(parse_to_ast(graphl).unwrap(), graph);
+    }
+}
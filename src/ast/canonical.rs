@@ -0,0 +1,310 @@
+//! Canonical forms of graphs.
+//!
+//! [`Graph::canonical`] runs three passes in a single bottom-up traversal:
+//!
+//! 1. simplification: `0` operands of tensors are dropped;
+//! 2. canonical renaming: every binder (see the `scope` module) is renamed
+//!    after its scope depth, so the outermost binder is `x0` (or `X0` for an
+//!    uppercase variable), the binders directly inside it are `x1`, and so
+//!    on. Free variables keep their names, and the prefix is lengthened
+//!    (`xx0`, ...) if a free variable already has that shape;
+//! 3. commutative normalization: tensors are flattened and their operands
+//!    sorted, and the two bindings of an anonymous edge ordered, by their
+//!    printed form.
+//!
+//! Depth-based names do not depend on where a binder appears among its
+//! siblings, so renaming before sorting gives the same names whatever order
+//! the operands were written in.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::{
+    Binding,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    Vertex,
+};
+use crate::printer::{PrintOptions, Printer};
+
+/// The canonical names of the binders in scope.
+#[derive(Clone, Default)]
+struct Env {
+    renames: BTreeMap<String, String>,
+    depth: usize,
+}
+
+struct Canon {
+    lower: String,
+    upper: String,
+    /// Whether to run the simplification and commutative normalization
+    /// passes as well as the renaming.
+    commutative: bool,
+}
+
+impl Canon {
+    fn new(free: &BTreeSet<String>) -> Self {
+        Self {
+            lower: prefix('x', free),
+            upper: prefix('X', free),
+            commutative: true,
+        }
+    }
+
+    /// Renaming only, with binder names that no variable can have.
+    fn alpha() -> Self {
+        Self {
+            lower: "#x".into(),
+            upper: "#X".into(),
+            commutative: false,
+        }
+    }
+
+    /// Enters the scope of a binder for `var`, returning its canonical name
+    /// and the environment of the scope.
+    fn enter(&self, var: &str, env: &Env) -> (String, Env) {
+        let prefix = if var.starts_with(|c: char| c.is_ascii_uppercase()) {
+            &self.upper
+        } else {
+            &self.lower
+        };
+        let fresh = format!("{prefix}{}", env.depth);
+
+        let mut env = env.clone();
+        env.depth += 1;
+        env.renames.insert(var.to_owned(), fresh.clone());
+        (fresh, env)
+    }
+
+    fn var(&self, var: &str, env: &Env) -> String {
+        env.renames.get(var).map_or(var, String::as_str).to_owned()
+    }
+
+    fn graph(&self, graph: &Graph, env: &Env) -> Graph {
+        match graph {
+            Graph::Nil => Graph::Nil,
+            Graph::Vertex(vertex) => Graph::Vertex(GVertex {
+                vertex: self.vertex(&vertex.vertex, env),
+                graph: Box::new(self.graph(&vertex.graph, env)),
+            }),
+            Graph::Var(var) => Graph::Var(GVar {
+                graph: Box::new(self.graph(&var.graph, env)),
+                var: self.var(&var.var, env),
+            }),
+            Graph::Nominate(binding) => Graph::Nominate(self.binding(binding, env)),
+            Graph::EdgeAnon(edge) => {
+                let mut bindings = [
+                    self.binding(&edge.binding_1, env),
+                    self.binding(&edge.binding_2, env),
+                ];
+                if self.commutative {
+                    bindings.sort_by_cached_key(binding_key);
+                }
+                let [binding_1, binding_2] = bindings;
+                Graph::EdgeAnon(GEdgeAnon {
+                    binding_1,
+                    binding_2,
+                })
+            }
+            Graph::EdgeNamed(edge) => {
+                let (name, env) = self.named(&edge.name, env);
+                Graph::EdgeNamed(GEdgeNamed {
+                    name,
+                    binding_1: self.binding(&edge.binding_1, &env),
+                    binding_2: self.binding(&edge.binding_2, &env),
+                })
+            }
+            Graph::RuleAnon(rule) => Graph::RuleAnon(GRuleAnon {
+                graph_1: Box::new(self.graph(&rule.graph_1, env)),
+                graph_2: Box::new(self.graph(&rule.graph_2, env)),
+            }),
+            Graph::RuleNamed(rule) => {
+                let (name, env) = self.named(&rule.name, env);
+                Graph::RuleNamed(GRuleNamed {
+                    name,
+                    graph_1: Box::new(self.graph(&rule.graph_1, &env)),
+                    graph_2: Box::new(self.graph(&rule.graph_2, &env)),
+                })
+            }
+            Graph::Subgraph(binding) => {
+                let graph_1 = Box::new(self.graph(&binding.graph_1, env));
+                let (var, env) = self.enter(&binding.var, env);
+                Graph::Subgraph(GraphBinding {
+                    graph_1,
+                    graph_2: Box::new(self.graph(&binding.graph_2, &env)),
+                    var,
+                })
+            }
+            Graph::Tensor(tensor) if !self.commutative => Graph::Tensor(GTensor {
+                graph_1: Box::new(self.graph(&tensor.graph_1, env)),
+                graph_2: Box::new(self.graph(&tensor.graph_2, env)),
+            }),
+            Graph::Tensor(_) => {
+                let mut operands: Vec<_> = graph
+                    .flatten_tensors()
+                    .into_iter()
+                    .map(|operand| self.graph(operand, env))
+                    .filter(|operand| *operand != Graph::Nil)
+                    .collect();
+                operands.sort_by_cached_key(|operand| operand.print(&PrintOptions::default()));
+
+                operands
+                    .into_iter()
+                    .reduce(|graph_1, graph_2| {
+                        Graph::Tensor(GTensor {
+                            graph_1: Box::new(graph_1),
+                            graph_2: Box::new(graph_2),
+                        })
+                    })
+                    .unwrap_or(Graph::Nil)
+            }
+            Graph::Context(context) => Graph::Context(GContext {
+                graph: Box::new(self.graph(&context.graph, env)),
+                name: context.name.clone(),
+                string: context.string.clone(),
+            }),
+        }
+    }
+
+    fn binding(&self, binding: &Binding, env: &Env) -> Binding {
+        let vertex = self.vertex(&binding.vertex, env);
+        let (var, env) = self.enter(&binding.var, env);
+        Binding {
+            graph: Box::new(self.graph(&binding.graph, &env)),
+            var,
+            vertex,
+        }
+    }
+
+    /// Handles the name of an edge or rule, which binds plain variables and
+    /// is searched for references when quoted.
+    fn named(&self, name: &Name, env: &Env) -> (Name, Env) {
+        match name {
+            Name::VVar { value } => {
+                let (value, env) = self.enter(value, env);
+                (Name::VVar { value }, env)
+            }
+            Name::GVar { value } => {
+                let (value, env) = self.enter(value, env);
+                (Name::GVar { value }, env)
+            }
+            Name::Wildcard | Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => {
+                (self.name(name, env), env.clone())
+            }
+        }
+    }
+
+    fn vertex(&self, vertex: &Vertex, env: &Env) -> Vertex {
+        Vertex {
+            name: self.name(&vertex.name, env),
+        }
+    }
+
+    fn name(&self, name: &Name, env: &Env) -> Name {
+        match name {
+            Name::GVar { value } => Name::GVar {
+                value: self.var(value, env),
+            },
+            Name::QuoteGraph { value } => Name::QuoteGraph {
+                value: Box::new(self.graph(value, env)),
+            },
+            Name::QuoteVertex { value } => Name::QuoteVertex {
+                value: Box::new(self.vertex(value, env)),
+            },
+            Name::Wildcard | Name::VVar { .. } => name.clone(),
+        }
+    }
+}
+
+/// The shortest run of `base` such that no free variable is that run
+/// followed by digits.
+fn prefix(base: char, free: &BTreeSet<String>) -> String {
+    let mut prefix = base.to_string();
+    let clashes = |prefix: &str| {
+        free.iter().any(|var| {
+            var.strip_prefix(prefix)
+                .is_some_and(|rest| !rest.is_empty() && rest.bytes().all(|b| b.is_ascii_digit()))
+        })
+    };
+
+    while clashes(&prefix) {
+        prefix.push(base);
+    }
+    prefix
+}
+
+/// Renames the binders of `graph` after their scope depth, leaving
+/// everything else as is.
+///
+/// The new names are not valid GraphL variables, so they never clash with a
+/// free variable, and two graphs are alpha-equivalent exactly when their
+/// results are equal.
+pub(crate) fn alpha_normal(graph: &Graph) -> Graph {
+    Canon::alpha().graph(graph, &Env::default())
+}
+
+fn binding_key(binding: &Binding) -> String {
+    let opts = PrintOptions::default();
+    let mut printer = Printer::new(&opts);
+    printer.binding(binding);
+    printer.finish()
+}
+
+impl Graph {
+    /// Returns the canonical form of this graph.
+    ///
+    /// Graphs that differ only in the names of their binders, in the order
+    /// or nesting of tensor operands, in the order of the bindings of
+    /// anonymous edges, or in `0` operands of tensors have the same canonical
+    /// form, and so print to the same GraphL. The pipeline is described in
+    /// the module documentation; it reaches its fixed point in one pass, so
+    /// `g.canonical().canonical() == g.canonical()`.
+    pub fn canonical(&self) -> Graph {
+        Canon::new(&self.free_vars()).graph(self, &Env::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse_to_ast;
+    use crate::printer::PrintOptions;
+
+    #[test]
+    fn test_equivalent_graphs_share_canonical_form() {
+        let graph_1 = parse_to_ast(
+            "let a = <v> in { a | 0 * <w> | 0 * 0 } * (let c = <c> in 0, let d = <d> in c | 0)"
+                .into(),
+        )
+        .unwrap();
+        let graph_2 = parse_to_ast(
+            "(let e = <d> in c | 0, let f = <c> in 0) * let b = <v> in { <w> | 0 * b | 0 }".into(),
+        )
+        .unwrap();
+        let canonical = graph_1.canonical();
+
+        assert_eq!(canonical, graph_2.canonical());
+        assert_eq!(
+            canonical.print(&PrintOptions::default()),
+            "(let x0 = < c > in 0, let x0 = < d > in c | 0) * let x0 = < v > in {< w > | 0 * x0 | 0}"
+        );
+        assert_eq!(canonical.canonical(), canonical);
+    }
+
+    #[test]
+    fn test_canonical_names_avoid_free_variables() {
+        let graph = parse_to_ast("let a = <v> in { a | 0 * x0 | 0 }".into()).unwrap();
+
+        assert_eq!(
+            graph.canonical().print(&PrintOptions::default()),
+            "let xx0 = < v > in {x0 | 0 * xx0 | 0}"
+        );
+    }
+}
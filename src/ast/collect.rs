@@ -0,0 +1,633 @@
+//! Collectors that gather metadata from a graph using the [`Walker`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::Infallible;
+
+use crate::ast::node::Node;
+use crate::ast::scope::{ScopeEvent, walk_scopes};
+use crate::ast::{
+    Binding,
+    GContext,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    Vertex,
+};
+use crate::printer::{PrintOptions, Printer};
+use crate::visitor::Visitor;
+use crate::walker::Walker;
+
+/// What a graph needs from and exposes to its environment, see
+/// [`Graph::interface`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Interface {
+    /// The free variables, in order of first occurrence.
+    pub free_vars: Vec<String>,
+    /// The vertex names.
+    pub vertices: BTreeSet<String>,
+    /// The string and printed name of every context, in source order.
+    pub contexts: Vec<(String, String)>,
+}
+
+struct EdgeNames;
+
+impl<'a> Visitor<'a, BTreeSet<String>, Infallible> for EdgeNames {
+    fn visit_edge_named(
+        &self,
+        mut acc: BTreeSet<String>,
+        edge: &'a GEdgeNamed,
+    ) -> Result<BTreeSet<String>, Infallible> {
+        if let Name::VVar { value } | Name::GVar { value } = &edge.name {
+            acc.insert(value.clone());
+        }
+        Ok(acc)
+    }
+}
+
+/// Collects the `0` children of each node; the walker reaches every node
+/// but hands `visit_nil` no reference to return.
+struct Leaves;
+
+impl<'a> Leaves {
+    fn push(mut acc: Vec<&'a Graph>, children: [&'a Graph; 2]) -> Vec<&'a Graph> {
+        acc.extend(children.into_iter().filter(|child| **child == Graph::Nil));
+        acc
+    }
+
+    fn push_one(mut acc: Vec<&'a Graph>, child: &'a Graph) -> Vec<&'a Graph> {
+        if *child == Graph::Nil {
+            acc.push(child);
+        }
+        acc
+    }
+}
+
+impl<'a> Visitor<'a, Vec<&'a Graph>, Infallible> for Leaves {
+    fn visit_vertex(
+        &self,
+        acc: Vec<&'a Graph>,
+        vertex: &'a GVertex,
+    ) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push_one(acc, &vertex.graph))
+    }
+
+    fn visit_var(&self, acc: Vec<&'a Graph>, var: &'a GVar) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push_one(acc, &var.graph))
+    }
+
+    fn visit_nominate(
+        &self,
+        acc: Vec<&'a Graph>,
+        binding: &'a Binding,
+    ) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push_one(acc, &binding.graph))
+    }
+
+    fn visit_rule_anon(
+        &self,
+        acc: Vec<&'a Graph>,
+        rule: &'a GRuleAnon,
+    ) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push(acc, [&rule.graph_1, &rule.graph_2]))
+    }
+
+    fn visit_rule_named(
+        &self,
+        acc: Vec<&'a Graph>,
+        rule: &'a GRuleNamed,
+    ) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push(acc, [&rule.graph_1, &rule.graph_2]))
+    }
+
+    fn visit_subgraph(
+        &self,
+        acc: Vec<&'a Graph>,
+        subgraph: &'a GraphBinding,
+    ) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push(acc, [&subgraph.graph_1, &subgraph.graph_2]))
+    }
+
+    fn visit_tensor(
+        &self,
+        acc: Vec<&'a Graph>,
+        tensor: &'a GTensor,
+    ) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push(acc, [&tensor.graph_1, &tensor.graph_2]))
+    }
+
+    fn visit_context(
+        &self,
+        acc: Vec<&'a Graph>,
+        context: &'a GContext,
+    ) -> Result<Vec<&'a Graph>, Infallible> {
+        Ok(Self::push_one(acc, &context.graph))
+    }
+}
+
+impl Graph {
+    /// Returns the distinct names used on named edges (`n(..., ...)`).
+    ///
+    /// Only variable names are reported: edges named by a wildcard or by a
+    /// quoted graph or vertex have no textual name and are skipped. Named
+    /// rules are not included.
+    pub fn edge_names(&self) -> BTreeSet<String> {
+        Walker::new(self).visit(BTreeSet::new(), EdgeNames)
+    }
+
+    /// Returns the distinct vertex names (`<a>`, `<X>`) in ascending
+    /// (lexicographic) order.
+    ///
+    /// Vertices of bindings and vertices inside quoted names are included;
+    /// wildcard and quoted vertex names have no spelling and are skipped.
+    pub fn vertex_names(&self) -> BTreeSet<String> {
+        self.vertex_names_in_order().into_iter().collect()
+    }
+
+    /// Returns the same names as [`vertex_names`](Self::vertex_names), each
+    /// once, in the order of its first occurrence in the source.
+    pub fn vertex_names_in_order(&self) -> Vec<String> {
+        let mut seen = BTreeSet::new();
+
+        Node::Graph(self)
+            .preorder()
+            .filter_map(|node| match node {
+                Node::Vertex(vertex) => match &vertex.name {
+                    Name::VVar { value } | Name::GVar { value } => Some(value),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .filter(|name| seen.insert(*name))
+            .cloned()
+            .collect()
+    }
+
+    /// Summarizes the free variables, vertex names and contexts of this
+    /// graph in a single traversal.
+    ///
+    /// `free_vars` is [`free_vars_in_order`](Self::free_vars_in_order) and
+    /// `vertices` is [`vertex_names`](Self::vertex_names). Context names
+    /// are rendered as GraphL, so `context "s" for @<a> in 0` yields
+    /// `("s", "@ < a >")`.
+    pub fn interface(&self) -> Interface {
+        let mut interface = Interface::default();
+        let mut seen = BTreeSet::new();
+
+        walk_scopes(self, |event, scopes| match event {
+            ScopeEvent::Reference(var) => {
+                if !scopes.contains(&var) && seen.insert(var) {
+                    interface.free_vars.push(var.to_owned());
+                }
+            }
+            ScopeEvent::Vertex(name) => {
+                interface.vertices.insert(name.to_owned());
+            }
+            ScopeEvent::Context(context) => {
+                let opts = PrintOptions::default();
+                let mut printer = Printer::new(&opts);
+                printer.name(&context.name);
+                interface
+                    .contexts
+                    .push((context.string.clone(), printer.finish()));
+            }
+            ScopeEvent::Bind(..) => {}
+        });
+
+        interface
+    }
+
+    /// Counts the vertices named `name`.
+    ///
+    /// Every occurrence is counted: vertices in process position, vertices of
+    /// bindings and vertices inside quoted names alike. `name` is matched
+    /// against the spelling of both lowercase and uppercase names.
+    pub fn count_vertex(&self, name: &str) -> usize {
+        Node::Graph(self)
+            .preorder()
+            .filter(|node| match node {
+                Node::Vertex(vertex) => match &vertex.name {
+                    Name::VVar { value } | Name::GVar { value } => value == name,
+                    _ => false,
+                },
+                _ => false,
+            })
+            .count()
+    }
+
+    /// Returns the vertices whose continuation is `0`, i.e. the end states of
+    /// the process, in source order.
+    ///
+    /// Only vertices in process position (`<v> | 0`) are considered; the
+    /// vertex of a `let` binding has no continuation of its own. Vertices in
+    /// the processes of quoted names are included, so `<@{<a> | 0}> | 0`
+    /// lists both `@{<a> | 0}` and `a`. A quoted vertex (`@<a>`) has no
+    /// continuation and is not listed.
+    pub fn sink_vertices(&self) -> Vec<&Vertex> {
+        Node::Graph(self)
+            .preorder()
+            .filter_map(|node| match node {
+                Node::Graph(Graph::Vertex(vertex)) if *vertex.graph == Graph::Nil => {
+                    Some(&vertex.vertex)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the variables referenced with a `0` continuation (`x | 0`),
+    /// i.e. the final outputs of the dataflow, in source order.
+    ///
+    /// Each occurrence is reported, so a variable can appear more than once.
+    /// References inside quoted names are included, as in
+    /// [`sink_vertices`](Self::sink_vertices).
+    pub fn terminal_refs(&self) -> Vec<&str> {
+        Node::Graph(self)
+            .preorder()
+            .filter_map(|node| match node {
+                Node::Graph(Graph::Var(var)) if *var.graph == Graph::Nil => Some(var.var.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the leaves of the graph, in walking order.
+    ///
+    /// Every branch ends in a `0`: the continuation of a terminal vertex or
+    /// variable such as `<v> | 0` or `x | 0`, of a binding, or an operand of
+    /// a tensor, rule or `let X = ...`. Those `0` nodes are the leaves, so the
+    /// length of the result counts the endpoints of the graph. Edges are
+    /// walked through their bindings, and `0` nodes inside quoted names are
+    /// not included.
+    pub fn leaves(&self) -> Vec<&Graph> {
+        let roots = match self {
+            Graph::Nil => vec![self],
+            _ => Vec::new(),
+        };
+        Walker::new(self).visit(roots, Leaves)
+    }
+
+    /// Returns the paths of the contexts whose name matches nothing in their
+    /// scope, in pre-order.
+    ///
+    /// `context "s" for x in g` is fine as long as `x` is used somewhere in
+    /// `g`: as a vertex name, a variable, a binder, or the name of an edge or
+    /// rule. Other contexts do not count. Contexts for `_` or a quoted name
+    /// annotate no particular name and are never reported. Paths follow
+    /// [`children`](Self::children).
+    pub fn orphan_contexts(&self) -> Vec<Vec<usize>> {
+        self.subgraphs_with_paths()
+            .into_iter()
+            .filter_map(|(path, graph)| match graph {
+                Graph::Context(context) => match &context.name {
+                    Name::VVar { value } | Name::GVar { value } => {
+                        (!mentions(&context.graph, value)).then_some(path)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the vertices along the main continuation chain of this
+    /// graph, from the root.
+    ///
+    /// The chain follows the continuation of vertices (`<v> | g`), variables
+    /// (`x | g`), nominated bindings (`let x = <v> in g`, whose vertex `v` is
+    /// included) and contexts. It stops at the first `0` or at the first
+    /// node that branches: a tensor, edge, rule or graph binding.
+    pub fn spine(&self) -> Vec<&Vertex> {
+        let mut spine = Vec::new();
+        let mut graph = self;
+
+        loop {
+            graph = match graph {
+                Graph::Vertex(vertex) => {
+                    spine.push(&vertex.vertex);
+                    &vertex.graph
+                }
+                Graph::Var(var) => &var.graph,
+                Graph::Nominate(binding) => {
+                    spine.push(&binding.vertex);
+                    &binding.graph
+                }
+                Graph::Context(context) => &context.graph,
+                Graph::Nil
+                | Graph::EdgeAnon(_)
+                | Graph::EdgeNamed(_)
+                | Graph::RuleAnon(_)
+                | Graph::RuleNamed(_)
+                | Graph::Subgraph(_)
+                | Graph::Tensor(_) => return spine,
+            };
+        }
+    }
+
+    /// Returns the (in-degree, out-degree) of every vertex name, reading
+    /// edges as directed links between the vertices of their bindings.
+    ///
+    /// An edge `(let x = <a> in g, let y = <b> in h)`, named or not, points
+    /// from the vertex of its first binding to that of its second, so it
+    /// adds one to the out-degree of `a` and to the in-degree of `b`; an edge
+    /// between two bindings of the same vertex adds to both of its degrees.
+    /// Every name of [`vertex_names`](Self::vertex_names) is a key, with
+    /// `(0, 0)` if no edge touches it, and edges inside quoted names count.
+    /// Bindings of wildcard or quoted vertices have no name and leave their
+    /// edges out.
+    pub fn vertex_degrees(&self) -> BTreeMap<String, (usize, usize)> {
+        let mut degrees: BTreeMap<String, (usize, usize)> = self
+            .vertex_names()
+            .into_iter()
+            .map(|name| (name, (0, 0)))
+            .collect();
+
+        for (from, to) in links(self) {
+            degrees.entry(from.to_owned()).or_default().1 += 1;
+            degrees.entry(to.to_owned()).or_default().0 += 1;
+        }
+
+        degrees
+    }
+
+    /// Returns the [`vertex_names`](Self::vertex_names) in ascending order
+    /// and the adjacency matrix of the edges between them.
+    ///
+    /// `matrix[i][j]` is `true` when some edge points from the `i`th name to
+    /// the `j`th, reading edges as directed the way
+    /// [`vertex_degrees`](Self::vertex_degrees) does, so the matrix is not
+    /// symmetric in general. An edge between two bindings of the same vertex
+    /// is a self-loop and sets that vertex's entry on the diagonal. Repeated
+    /// edges are not counted. The matrix has a row and a column for every
+    /// name, so it is meant for graphs small enough for its square size.
+    pub fn to_adjacency_matrix(&self) -> (Vec<String>, Vec<Vec<bool>>) {
+        let names: Vec<String> = self.vertex_names().into_iter().collect();
+        let index = |name: &str| {
+            names
+                .binary_search_by(|probe| probe.as_str().cmp(name))
+                .expect("every linked vertex is a vertex name")
+        };
+        let mut matrix = vec![vec![false; names.len()]; names.len()];
+
+        for (from, to) in links(self) {
+            matrix[index(from)][index(to)] = true;
+        }
+
+        (names, matrix)
+    }
+}
+
+/// The vertex names every edge of `graph` links, from the vertex of its
+/// first binding to that of its second, in pre-order. Edges with a binding
+/// of a wildcard or quoted vertex are left out.
+fn links(graph: &Graph) -> impl Iterator<Item = (&str, &str)> {
+    fn name(binding: &Binding) -> Option<&str> {
+        match &binding.vertex.name {
+            Name::VVar { value } | Name::GVar { value } => Some(value),
+            _ => None,
+        }
+    }
+
+    Node::Graph(graph)
+        .preorder()
+        .filter_map(|node| match node {
+            Node::Graph(Graph::EdgeAnon(edge)) => Some((&edge.binding_1, &edge.binding_2)),
+            Node::Graph(Graph::EdgeNamed(edge)) => Some((&edge.binding_1, &edge.binding_2)),
+            _ => None,
+        })
+        .filter_map(|(from, to)| Some((name(from)?, name(to)?)))
+}
+
+/// Whether `name` is used in `graph` other than as the name of a context.
+fn mentions(graph: &Graph, name: &str) -> bool {
+    let is = |n: &Name| matches!(n, Name::VVar { value } | Name::GVar { value } if value == name);
+
+    Node::Graph(graph).preorder().any(|node| match node {
+        Node::Vertex(vertex) => is(&vertex.name),
+        Node::Binding(binding) => binding.var == name,
+        Node::Graph(Graph::Var(var)) => var.var == name,
+        Node::Graph(Graph::Subgraph(binding)) => binding.var == name,
+        Node::Graph(Graph::EdgeNamed(edge)) => is(&edge.name),
+        Node::Graph(Graph::RuleNamed(rule)) => is(&rule.name),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::Interface;
+    use crate::ast::{Graph, Name, THREE_EDGE_GRAPH, Vertex};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_edge_names() {
+        let graph = parse_to_ast(
+            "n(let a = <a> in {m(let b = <b> in 0, let c = <c> in 0)}, let d = <d> in {n(let e = <e> in 0, let f = <f> in 0)})"
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            graph.edge_names(),
+            BTreeSet::from(["m".to_owned(), "n".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_edge_names_skip_anonymous_and_quoted_edges() {
+        let graph = parse_to_ast(
+            "{(let a = <a> in 0, let b = <b> in 0) * @<q>(let c = <c> in 0, let d = <d> in 0)}"
+                .into(),
+        )
+        .unwrap();
+
+        assert!(graph.edge_names().is_empty());
+    }
+
+    #[test]
+    fn test_vertex_names_orderings() {
+        let graph = parse_to_ast(
+            "{ <z> | <@<y>> | 0 * (let a = <a> in <z> | 0, let b = <_> in <X> | 0) }".into(),
+        )
+        .unwrap();
+
+        assert_eq!(graph.vertex_names_in_order(), ["z", "y", "a", "X"]);
+        assert_eq!(
+            graph.vertex_names().into_iter().collect::<Vec<_>>(),
+            ["X", "a", "y", "z"]
+        );
+    }
+
+    #[test]
+    fn test_interface() {
+        let graph = parse_to_ast(
+            r#"{ context "db" for store in (let a = <a> in s | 0, let b = <b> in a | 0) * <Q> | x | 0 }"#
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            graph.interface(),
+            Interface {
+                free_vars: vec!["s".into(), "a".into(), "Q".into(), "x".into()],
+                vertices: BTreeSet::from(["Q".to_owned(), "a".to_owned(), "b".to_owned()]),
+                contexts: vec![("db".into(), "store".into())],
+            }
+        );
+
+        let graph = parse_to_ast(
+            r#"context "c" for @{let y = <v> in z | 0} in let x = <X> in <@<q>> | x | <X> | 0"#
+                .into(),
+        )
+        .unwrap();
+        let interface = graph.interface();
+
+        assert_eq!(interface.free_vars, graph.free_vars_in_order());
+        assert_eq!(interface.vertices, graph.vertex_names());
+        assert_eq!(interface.free_vars, vec!["X".to_owned()]);
+        assert_eq!(
+            interface.vertices,
+            BTreeSet::from(["X".to_owned(), "q".to_owned(), "v".to_owned()])
+        );
+        assert_eq!(interface.contexts.len(), 1);
+    }
+
+    #[test]
+    fn test_count_vertex() {
+        let graph = parse_to_ast(THREE_EDGE_GRAPH.into()).unwrap();
+
+        assert_eq!(graph.count_vertex("encryption"), 4);
+        assert_eq!(graph.count_vertex("store"), 2);
+        assert_eq!(graph.count_vertex("e1"), 0);
+
+        let quoted = parse_to_ast("<a> | <@<a>> | 0".into()).unwrap();
+        assert_eq!(quoted.count_vertex("a"), 2);
+    }
+
+    #[test]
+    fn test_vertex_degrees() {
+        let graph = parse_to_ast(
+            "{ (let x = <a> in 0, let y = <b> in 0) * e(let z = <b> in <c> | 0, let w = <a> in 0) } * (let v = <c> in 0, let u = <_> in 0)"
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            graph.vertex_degrees(),
+            BTreeMap::from([
+                ("a".to_owned(), (1, 1)),
+                ("b".to_owned(), (1, 1)),
+                ("c".to_owned(), (0, 0)),
+            ])
+        );
+        assert!(
+            parse_to_ast("0".into())
+                .unwrap()
+                .vertex_degrees()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_to_adjacency_matrix() {
+        let graph = parse_to_ast("(let x = <b> in 0, let y = <a> in 0)".into()).unwrap();
+
+        assert_eq!(
+            graph.to_adjacency_matrix(),
+            (
+                vec!["a".to_owned(), "b".to_owned()],
+                vec![vec![false, false], vec![true, false]],
+            )
+        );
+
+        let graph = parse_to_ast(
+            "e(let x = <a> in 0, let y = <a> in <b> | 0) * (let z = <a> in 0, let w = <a> in 0)"
+                .into(),
+        )
+        .unwrap();
+        assert_eq!(
+            graph.to_adjacency_matrix(),
+            (
+                vec!["a".to_owned(), "b".to_owned()],
+                vec![vec![true, false], vec![false, false]],
+            )
+        );
+    }
+
+    #[test]
+    fn test_sink_vertices() {
+        let vertex = |name: &str| Vertex {
+            name: Name::VVar { value: name.into() },
+        };
+
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+        assert_eq!(graph.sink_vertices(), [&vertex("a")]);
+
+        let graph = parse_to_ast("<a> | <b> | 0".into()).unwrap();
+        assert_eq!(graph.sink_vertices(), [&vertex("b")]);
+
+        // A quoted vertex has no continuation; a quoted process is searched.
+        let graph = parse_to_ast("<@<a>> | <@{<b> | 0}> | 0".into()).unwrap();
+        let quoted = Vertex {
+            name: Name::QuoteGraph {
+                value: Box::new(parse_to_ast("<b> | 0".into()).unwrap()),
+            },
+        };
+        assert_eq!(graph.sink_vertices(), [&quoted, &vertex("b")]);
+    }
+
+    #[test]
+    fn test_orphan_contexts() {
+        let graph = parse_to_ast(r#"context "m" for z in <a> | 0"#.into()).unwrap();
+        assert_eq!(graph.orphan_contexts(), [Vec::<usize>::new()]);
+
+        let graph = parse_to_ast(
+            r#"{ context "m" for a in <a> | 0 } * <b> | { context "n" for x in context "o" for x in 0 } * { context "p" for _ in 0 }"#
+                .into(),
+        )
+        .unwrap();
+        assert_eq!(graph.orphan_contexts(), [vec![0, 1, 0], vec![0, 1, 0, 0]]);
+    }
+
+    #[test]
+    fn test_leaves() {
+        let vertex = parse_to_ast("<v> | 0".into()).unwrap();
+        let tensor = parse_to_ast("<a> | 0 * <b> | 0".into()).unwrap();
+        let edge = parse_to_ast("(let a = <a> in 0, let b = <b> in x | 0)".into()).unwrap();
+
+        assert_eq!(vertex.leaves(), [&Graph::Nil]);
+        assert_eq!(tensor.leaves().len(), 2);
+        assert_eq!(edge.leaves().len(), 2);
+        assert_eq!(Graph::Nil.leaves().len(), 1);
+    }
+
+    #[test]
+    fn test_terminal_refs() {
+        let graph = parse_to_ast(THREE_EDGE_GRAPH.into()).unwrap();
+        assert_eq!(graph.terminal_refs(), ["e1"]);
+
+        let graph = parse_to_ast("x | y | 0 * <a> | 0".into()).unwrap();
+        assert_eq!(graph.terminal_refs(), ["y"]);
+    }
+
+    #[test]
+    fn test_spine() {
+        let vertex = |name: &str| Vertex {
+            name: Name::VVar { value: name.into() },
+        };
+
+        let graph = parse_to_ast("<a> | <b> | 0".into()).unwrap();
+        assert_eq!(graph.spine(), [&vertex("a"), &vertex("b")]);
+
+        let graph =
+            parse_to_ast("<a> | x | let y = <c> in <d> | { <e> | 0 * <f> | 0 }".into()).unwrap();
+        assert_eq!(graph.spine(), [&vertex("a"), &vertex("c"), &vertex("d")]);
+    }
+}
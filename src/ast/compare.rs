@@ -0,0 +1,507 @@
+//! Equality relations on graphs that are coarser than the derived
+//! [`PartialEq`].
+
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::Deref;
+
+use crate::ast::canonical::alpha_normal;
+use crate::ast::{Binding, Graph, Name};
+
+enum Pair<'a> {
+    Graph(&'a Graph, &'a Graph),
+    Binding(&'a Binding, &'a Binding),
+    Name(&'a Name, &'a Name),
+}
+
+/// Compares two graphs node by node like the derived [`PartialEq`], except
+/// that context strings are compared with `context_eq`.
+fn eq_with(left: &Graph, right: &Graph, context_eq: impl Fn(&str, &str) -> bool) -> bool {
+    let mut stack = vec![Pair::Graph(left, right)];
+
+    while let Some(pair) = stack.pop() {
+        match pair {
+            Pair::Graph(left, right) => match (left, right) {
+                (Graph::Nil, Graph::Nil) => {}
+                (Graph::Vertex(l), Graph::Vertex(r)) => {
+                    stack.push(Pair::Graph(&l.graph, &r.graph));
+                    stack.push(Pair::Name(&l.vertex.name, &r.vertex.name));
+                }
+                (Graph::Var(l), Graph::Var(r)) => {
+                    if l.var != r.var {
+                        return false;
+                    }
+                    stack.push(Pair::Graph(&l.graph, &r.graph));
+                }
+                (Graph::Nominate(l), Graph::Nominate(r)) => stack.push(Pair::Binding(l, r)),
+                (Graph::EdgeAnon(l), Graph::EdgeAnon(r)) => {
+                    stack.push(Pair::Binding(&l.binding_2, &r.binding_2));
+                    stack.push(Pair::Binding(&l.binding_1, &r.binding_1));
+                }
+                (Graph::EdgeNamed(l), Graph::EdgeNamed(r)) => {
+                    stack.push(Pair::Binding(&l.binding_2, &r.binding_2));
+                    stack.push(Pair::Binding(&l.binding_1, &r.binding_1));
+                    stack.push(Pair::Name(&l.name, &r.name));
+                }
+                (Graph::RuleAnon(l), Graph::RuleAnon(r)) => {
+                    stack.push(Pair::Graph(&l.graph_2, &r.graph_2));
+                    stack.push(Pair::Graph(&l.graph_1, &r.graph_1));
+                }
+                (Graph::RuleNamed(l), Graph::RuleNamed(r)) => {
+                    stack.push(Pair::Graph(&l.graph_2, &r.graph_2));
+                    stack.push(Pair::Graph(&l.graph_1, &r.graph_1));
+                    stack.push(Pair::Name(&l.name, &r.name));
+                }
+                (Graph::Subgraph(l), Graph::Subgraph(r)) => {
+                    if l.var != r.var {
+                        return false;
+                    }
+                    stack.push(Pair::Graph(&l.graph_2, &r.graph_2));
+                    stack.push(Pair::Graph(&l.graph_1, &r.graph_1));
+                }
+                (Graph::Tensor(l), Graph::Tensor(r)) => {
+                    stack.push(Pair::Graph(&l.graph_2, &r.graph_2));
+                    stack.push(Pair::Graph(&l.graph_1, &r.graph_1));
+                }
+                (Graph::Context(l), Graph::Context(r)) => {
+                    if !context_eq(&l.string, &r.string) {
+                        return false;
+                    }
+                    stack.push(Pair::Graph(&l.graph, &r.graph));
+                    stack.push(Pair::Name(&l.name, &r.name));
+                }
+                _ => return false,
+            },
+            Pair::Binding(left, right) => {
+                if left.var != right.var {
+                    return false;
+                }
+                stack.push(Pair::Graph(&left.graph, &right.graph));
+                stack.push(Pair::Name(&left.vertex.name, &right.vertex.name));
+            }
+            Pair::Name(left, right) => match (left, right) {
+                (Name::Wildcard, Name::Wildcard) => {}
+                (Name::VVar { value: l }, Name::VVar { value: r })
+                | (Name::GVar { value: l }, Name::GVar { value: r }) => {
+                    if l != r {
+                        return false;
+                    }
+                }
+                (Name::QuoteGraph { value: l }, Name::QuoteGraph { value: r }) => {
+                    stack.push(Pair::Graph(l, r))
+                }
+                (Name::QuoteVertex { value: l }, Name::QuoteVertex { value: r }) => {
+                    stack.push(Pair::Name(&l.name, &r.name))
+                }
+                _ => return false,
+            },
+        }
+    }
+
+    true
+}
+
+/// Compares two graphs up to the order of tensor operands and of the two
+/// bindings of anonymous edges.
+fn commutative_eq(left: &Graph, right: &Graph) -> bool {
+    match (left, right) {
+        (Graph::Tensor(_), Graph::Tensor(_)) => {
+            let mut unmatched = right.flatten_tensors();
+            let operands = left.flatten_tensors();
+            operands.len() == unmatched.len()
+                && operands.into_iter().all(|operand| {
+                    // Equivalence is transitive, so matching greedily is enough.
+                    match unmatched
+                        .iter()
+                        .position(|candidate| commutative_eq(operand, candidate))
+                    {
+                        Some(index) => {
+                            unmatched.swap_remove(index);
+                            true
+                        }
+                        None => false,
+                    }
+                })
+        }
+        (Graph::EdgeAnon(l), Graph::EdgeAnon(r)) => {
+            (binding_eq(&l.binding_1, &r.binding_1) && binding_eq(&l.binding_2, &r.binding_2))
+                || (binding_eq(&l.binding_1, &r.binding_2)
+                    && binding_eq(&l.binding_2, &r.binding_1))
+        }
+        (Graph::Nil, Graph::Nil) => true,
+        (Graph::Vertex(l), Graph::Vertex(r)) => {
+            name_eq(&l.vertex.name, &r.vertex.name) && commutative_eq(&l.graph, &r.graph)
+        }
+        (Graph::Var(l), Graph::Var(r)) => l.var == r.var && commutative_eq(&l.graph, &r.graph),
+        (Graph::Nominate(l), Graph::Nominate(r)) => binding_eq(l, r),
+        (Graph::EdgeNamed(l), Graph::EdgeNamed(r)) => {
+            name_eq(&l.name, &r.name)
+                && binding_eq(&l.binding_1, &r.binding_1)
+                && binding_eq(&l.binding_2, &r.binding_2)
+        }
+        (Graph::RuleAnon(l), Graph::RuleAnon(r)) => {
+            commutative_eq(&l.graph_1, &r.graph_1) && commutative_eq(&l.graph_2, &r.graph_2)
+        }
+        (Graph::RuleNamed(l), Graph::RuleNamed(r)) => {
+            name_eq(&l.name, &r.name)
+                && commutative_eq(&l.graph_1, &r.graph_1)
+                && commutative_eq(&l.graph_2, &r.graph_2)
+        }
+        (Graph::Subgraph(l), Graph::Subgraph(r)) => {
+            l.var == r.var
+                && commutative_eq(&l.graph_1, &r.graph_1)
+                && commutative_eq(&l.graph_2, &r.graph_2)
+        }
+        (Graph::Context(l), Graph::Context(r)) => {
+            l.string == r.string && name_eq(&l.name, &r.name) && commutative_eq(&l.graph, &r.graph)
+        }
+        _ => false,
+    }
+}
+
+fn binding_eq(left: &Binding, right: &Binding) -> bool {
+    left.var == right.var
+        && name_eq(&left.vertex.name, &right.vertex.name)
+        && commutative_eq(&left.graph, &right.graph)
+}
+
+fn name_eq(left: &Name, right: &Name) -> bool {
+    match (left, right) {
+        (Name::QuoteGraph { value: l }, Name::QuoteGraph { value: r }) => commutative_eq(l, r),
+        (Name::QuoteVertex { value: l }, Name::QuoteVertex { value: r }) => {
+            name_eq(&l.name, &r.name)
+        }
+        (left, right) => left == right,
+    }
+}
+
+/// Normalizes a `key=value` style context string: leading and trailing
+/// whitespace is removed, whitespace on either side of `=` is removed, and
+/// every other run of whitespace is replaced by a single space.
+fn normalize_context(string: &str) -> String {
+    let collapsed = string.split_whitespace().collect::<Vec<_>>().join(" ");
+    collapsed
+        .split('=')
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("=")
+}
+
+impl Graph {
+    /// Compares two graphs exactly, except that context strings are compared
+    /// after whitespace normalization.
+    ///
+    /// Each context string is trimmed, whitespace around `=` is dropped and
+    /// any other run of whitespace (spaces, tabs, newlines) is collapsed to a
+    /// single space. So `"foo = bar"`, `" foo=bar "` and `"foo=bar"` are all
+    /// equal, while `"foo bar"` and `"foobar"` are not. Names, variables and
+    /// structure are compared exactly as with `==`.
+    pub fn eq_normalized_contexts(&self, other: &Graph) -> bool {
+        eq_with(self, other, |left, right| {
+            left == right || normalize_context(left) == normalize_context(right)
+        })
+    }
+
+    /// Compares two graphs treating parallel composition as unordered.
+    ///
+    /// Tensors are compared as multisets of their flattened operands (see
+    /// [`flatten_tensors`](Self::flatten_tensors)), so both associativity
+    /// and commutativity are ignored, and the two bindings of an anonymous
+    /// edge may appear in either order. This applies at every depth,
+    /// including inside quoted names. Named edges, rules and everything else
+    /// are compared in order, and no graphs are cloned.
+    pub fn commutative_eq(&self, other: &Graph) -> bool {
+        commutative_eq(self, other)
+    }
+
+    /// Compares two graphs up to a consistent renaming of their binders.
+    ///
+    /// The variables bound by `let x = <v> in g`, `let X = g1 in g2` and
+    /// named edges and rules may be spelled differently, as long as every
+    /// reference resolves to the corresponding binder on both sides. Free
+    /// variables must have the same names. A plain vertex name such as `<a>`
+    /// is not a reference, so `let a = <a> in a | 0` and
+    /// `let b = <a> in b | 0` are alpha-equivalent but
+    /// `let a = <v> in <a> | 0` and `let b = <v> in <b> | 0` are not.
+    /// Everything else is compared as with `==`.
+    pub fn alpha_eq(&self, other: &Graph) -> bool {
+        alpha_normal(self) == alpha_normal(other)
+    }
+
+    /// Returns a 64-bit hash of the whole tree.
+    ///
+    /// Equal graphs have equal fingerprints. The value is derived from the
+    /// [`Hash`] implementation with a fixed-key hasher, so it is stable for a
+    /// given build of the crate but should not be persisted. It is computed
+    /// from scratch on every call; [`Fingerprinted`] keeps it with the graph.
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint(self)
+    }
+
+    /// Compares the [`fingerprint`](Self::fingerprint)s first and the graphs
+    /// only when the fingerprints agree, with the same result as `==`.
+    ///
+    /// Both fingerprints are computed on every call, which walks both graphs
+    /// in full, so this only pays off where `==` would too. To compare a graph
+    /// many times, keep it in a [`Fingerprinted`], whose
+    /// [`eq_fast`](Fingerprinted::eq_fast) this delegates to.
+    pub fn eq_fast(&self, other: &Graph) -> bool {
+        Fingerprinted::new(self).eq_fast(&Fingerprinted::new(other))
+    }
+
+    /// Returns the subgraphs that occur at more than one path, each with
+    /// the paths where it occurs, ordered by first occurrence.
+    ///
+    /// Occurrences are grouped up to the names of their own binders: the
+    /// [`fingerprint`](Self::fingerprint) is taken after renaming binders by
+    /// scope depth, so `let a = <v> in a | 0` and `let b = <v> in b | 0`
+    /// are the same subexpression, while free variables must match. The
+    /// graph returned for a group is its first occurrence. `0` is left out,
+    /// and a repeated subgraph is reported together with its repeated
+    /// subgraphs. Paths follow [`children`](Self::children) and are in
+    /// pre-order.
+    pub fn common_subexpressions(&self) -> Vec<(Graph, Vec<Vec<usize>>)> {
+        let mut groups: Vec<(Graph, Graph, Vec<Vec<usize>>)> = Vec::new();
+        let mut by_fingerprint: HashMap<u64, Vec<usize>> = HashMap::new();
+
+        for (path, graph) in self.subgraphs_with_paths() {
+            if *graph == Graph::Nil {
+                continue;
+            }
+            let normal = alpha_normal(graph);
+            let candidates = by_fingerprint.entry(normal.fingerprint()).or_default();
+
+            match candidates.iter().find(|&&index| groups[index].1 == normal) {
+                Some(&index) => groups[index].2.push(path),
+                None => {
+                    candidates.push(groups.len());
+                    groups.push((graph.clone(), normal, vec![path]));
+                }
+            }
+        }
+
+        groups
+            .into_iter()
+            .filter(|(_, _, paths)| paths.len() > 1)
+            .map(|(graph, _, paths)| (graph, paths))
+            .collect()
+    }
+}
+
+fn fingerprint(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A value kept together with its fingerprint, for values compared many
+/// times.
+///
+/// The fingerprint is computed once, by [`new`](Self::new), so
+/// [`eq_fast`](Self::eq_fast) rejects values with different fingerprints
+/// with a single `u64` comparison instead of walking both trees. For a
+/// [`Graph`] it is the same as [`Graph::fingerprint`]. The wrapped value can
+/// only be read, so the fingerprint cannot go stale.
+///
+/// # Examples
+///
+/// ```
+/// use graphl_parser::ast::Fingerprinted;
+/// use graphl_parser::parse_str;
+///
+/// let left = Fingerprinted::new(parse_str("<a> | 0 * <b> | 0").unwrap());
+/// let right = Fingerprinted::new(parse_str("<a> | 0 * <c> | 0").unwrap());
+///
+/// assert!(!left.eq_fast(&right));
+/// assert!(left.eq_fast(&left.clone()));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fingerprinted<T = Graph> {
+    value: T,
+    fingerprint: u64,
+}
+
+impl<T: Hash + Eq> Fingerprinted<T> {
+    /// Computes the fingerprint of `value` and keeps it with the value.
+    pub fn new(value: T) -> Self {
+        Self {
+            fingerprint: fingerprint(&value),
+            value,
+        }
+    }
+
+    /// The fingerprint computed by [`new`](Self::new).
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// The wrapped value.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Returns the wrapped value, dropping its fingerprint.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Compares the fingerprints first and the values only when the
+    /// fingerprints agree.
+    ///
+    /// The result is always the same as `==` on the wrapped values:
+    /// differing fingerprints prove the values differ, and a match, which
+    /// could be a collision, is confirmed with `==`. Values that differ are
+    /// almost always told apart without looking at them, see
+    /// `benches/eq_fast.rs`; equal values cost a full comparison.
+    pub fn eq_fast(&self, other: &Self) -> bool {
+        self.fingerprint == other.fingerprint && self.value == other.value
+    }
+}
+
+impl<T> Deref for Fingerprinted<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Hash + Eq> PartialEq for Fingerprinted<T> {
+    /// Same as [`eq_fast`](Self::eq_fast).
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_fast(other)
+    }
+}
+
+impl<T: Hash + Eq> Eq for Fingerprinted<T> {}
+
+impl<T> Hash for Fingerprinted<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.fingerprint);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Fingerprinted;
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_common_subexpressions() {
+        let graph = parse_to_ast(
+            "{ let a = <v> in { a | 0 * <w> | 0 } * <u> | let b = <v> in { b | 0 * <w> | 0 } } * x | 0"
+                .into(),
+        )
+        .unwrap();
+
+        let common = graph.common_subexpressions();
+
+        assert_eq!(
+            common,
+            [
+                (
+                    parse_to_ast("let a = <v> in { a | 0 * <w> | 0 }".into()).unwrap(),
+                    vec![vec![0, 0], vec![0, 1, 0]],
+                ),
+                (
+                    parse_to_ast("<w> | 0".into()).unwrap(),
+                    vec![vec![0, 0, 0, 1], vec![0, 1, 0, 0, 1]],
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_whitespace_variant_contexts_are_equal() {
+        let spaced = parse_to_ast("context \" foo =  bar\" for a in <a> | 0".into()).unwrap();
+        let compact = parse_to_ast("context \"foo=bar\" for a in <a> | 0".into()).unwrap();
+
+        assert_ne!(spaced, compact);
+        assert!(spaced.eq_normalized_contexts(&compact));
+    }
+
+    #[test]
+    fn test_commutative_eq() {
+        let graph = |code: &str| parse_to_ast(code.into()).unwrap();
+
+        assert!(graph("<a> | 0 * <b> | 0").commutative_eq(&graph("<b> | 0 * <a> | 0")));
+        assert!(!graph("<a> | 0 * <b> | 0").commutative_eq(&graph("<a> | 0 * <c> | 0")));
+        assert!(
+            graph("<x> | { <a> | 0 * { <b> | 0 * <c> | 0 } }")
+                .commutative_eq(&graph("<x> | { <c> | 0 * <a> | 0 * <b> | 0 }"))
+        );
+        assert!(
+            !graph("<a> | 0 * <a> | 0 * <b> | 0")
+                .commutative_eq(&graph("<a> | 0 * <b> | 0 * <b> | 0"))
+        );
+        assert!(
+            graph("(let a = <a> in 0, let b = <b> in 0)")
+                .commutative_eq(&graph("(let b = <b> in 0, let a = <a> in 0)"))
+        );
+        assert!(
+            !graph("n(let a = <a> in 0, let b = <b> in 0)")
+                .commutative_eq(&graph("n(let b = <b> in 0, let a = <a> in 0)"))
+        );
+    }
+
+    #[test]
+    fn test_alpha_eq() {
+        let graph = |code: &str| parse_to_ast(code.into()).unwrap();
+
+        assert!(graph("let a = <a> in a | 0").alpha_eq(&graph("let b = <a> in b | 0")));
+        assert!(!graph("let a = <v> in <a> | 0").alpha_eq(&graph("let b = <v> in <b> | 0")));
+        assert!(!graph("let a = <v> in x | 0").alpha_eq(&graph("let b = <v> in y | 0")));
+        assert!(
+            graph("let G = <a> | 0 in <G> | 0 * <F> | 0")
+                .alpha_eq(&graph("let H = <a> | 0 in <H> | 0 * <F> | 0"))
+        );
+        assert!(
+            !graph("let G = <a> | 0 in <F> | 0").alpha_eq(&graph("let H = <a> | 0 in <H> | 0"))
+        );
+        assert!(
+            graph("e(let a = <a> in e | 0, let b = <b> in { let c = <c> in b | c | 0 })").alpha_eq(
+                &graph("f(let x = <a> in f | 0, let y = <b> in { let b = <c> in y | b | 0 })")
+            )
+        );
+        assert!(
+            !graph("let a = <v> in { let b = <w> in a | 0 }")
+                .alpha_eq(&graph("let a = <v> in { let b = <w> in b | 0 }"))
+        );
+    }
+
+    #[test]
+    fn test_eq_fast_agrees_with_eq() {
+        let graph =
+            parse_to_ast("{ <a> | 0 * (let b = <b> in 0, let c = <c> in x | 0) }".into()).unwrap();
+        let other =
+            parse_to_ast("{ <a> | 0 * (let b = <b> in 0, let c = <c> in y | 0) }".into()).unwrap();
+        let fingerprinted = Fingerprinted::new(graph.clone());
+
+        assert_eq!(fingerprinted.fingerprint(), graph.fingerprint());
+        assert!(fingerprinted.eq_fast(&Fingerprinted::new(graph.clone())));
+        assert!(!fingerprinted.eq_fast(&Fingerprinted::new(other.clone())));
+
+        // A fingerprint collision is confirmed structurally.
+        let collision = Fingerprinted {
+            value: other,
+            fingerprint: graph.fingerprint(),
+        };
+        assert!(!fingerprinted.eq_fast(&collision));
+
+        assert!(graph.eq_fast(&graph.clone()));
+        assert!(!graph.eq_fast(&collision.value));
+    }
+
+    #[test]
+    fn test_normalized_contexts_still_compare_everything_else() {
+        let left = parse_to_ast("context \"foo bar\" for a in <a> | 0".into()).unwrap();
+
+        let joined = parse_to_ast("context \"foobar\" for a in <a> | 0".into()).unwrap();
+        let other_name = parse_to_ast("context \"foo  bar\" for b in <a> | 0".into()).unwrap();
+        let other_body = parse_to_ast("context \"foo  bar\" for a in <b> | 0".into()).unwrap();
+
+        assert!(!left.eq_normalized_contexts(&joined));
+        assert!(!left.eq_normalized_contexts(&other_name));
+        assert!(!left.eq_normalized_contexts(&other_body));
+    }
+}
@@ -0,0 +1,334 @@
+//! Size and shape statistics of a graph, gathered in one traversal.
+
+use std::collections::BTreeSet;
+
+use crate::ast::{Binding, Graph, Name};
+
+/// Statistics of a graph, see [`Graph::metrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Metrics {
+    /// The [`node_count`](Graph::node_count).
+    pub node_count: usize,
+    /// The [`depth`](Graph::depth).
+    pub depth: usize,
+    /// Occurrences of vertices, including those of bindings and those inside
+    /// quoted names.
+    pub vertex_count: usize,
+    /// The number of [`vertex_names`](Graph::vertex_names).
+    pub distinct_vertices: usize,
+    /// The number of [`free_vars`](Graph::free_vars).
+    pub free_var_count: usize,
+    /// Anonymous and named edges.
+    pub edge_count: usize,
+    /// Anonymous and named rules.
+    pub rule_count: usize,
+    /// `context` nodes.
+    pub context_count: usize,
+    /// The most binders in scope at any one point: `let x = ...`,
+    /// `let X = ...` and the names of named edges and rules.
+    pub max_binding_depth: usize,
+}
+
+/// A pending piece of the traversal.
+///
+/// `scope` is the number of binders in scope for the item, or `None` inside
+/// the name of a context, which is counted but not searched for references.
+/// `binder` is a variable bound for the item itself and everything beneath
+/// it. `depth` is `None` inside quoted names, which are not
+/// [`children`](Graph::children).
+enum Item<'a> {
+    Graph {
+        graph: &'a Graph,
+        depth: Option<usize>,
+        scope: Option<usize>,
+        binder: Option<&'a str>,
+    },
+    Binding {
+        binding: &'a Binding,
+        depth: Option<usize>,
+        scope: Option<usize>,
+        binder: Option<&'a str>,
+    },
+    Name {
+        name: &'a Name,
+        scope: Option<usize>,
+    },
+}
+
+#[derive(Default)]
+struct Census<'a> {
+    metrics: Metrics,
+    vertices: BTreeSet<&'a str>,
+    free: BTreeSet<&'a str>,
+    scopes: Vec<&'a str>,
+    stack: Vec<Item<'a>>,
+}
+
+impl<'a> Census<'a> {
+    /// Restores the binders in scope for an item and adds its own binder,
+    /// returning the new number in scope.
+    fn enter(&mut self, scope: Option<usize>, binder: Option<&'a str>) -> Option<usize> {
+        let scope = scope?;
+        self.scopes.truncate(scope);
+        if let Some(var) = binder {
+            self.scopes.push(var);
+            self.metrics.max_binding_depth = self.metrics.max_binding_depth.max(scope + 1);
+        }
+        Some(self.scopes.len())
+    }
+
+    fn reference(&mut self, var: &'a str, scope: Option<usize>) {
+        if scope.is_some() && !self.scopes.contains(&var) {
+            self.free.insert(var);
+        }
+    }
+
+    fn vertex(&mut self, name: &'a Name, scope: Option<usize>) {
+        self.metrics.node_count += 1;
+        self.metrics.vertex_count += 1;
+        if let Name::VVar { value } | Name::GVar { value } = name {
+            self.vertices.insert(value);
+        }
+        self.stack.push(Item::Name { name, scope });
+    }
+
+    fn graph(&self, graph: &'a Graph, depth: Option<usize>, scope: Option<usize>) -> Item<'a> {
+        Item::Graph {
+            graph,
+            depth: depth.map(|depth| depth + 1),
+            scope,
+            binder: None,
+        }
+    }
+
+    /// Handles the name of a named edge or rule, returning the variable it
+    /// binds for the rest of the node, if any. Quoted names bind nothing but
+    /// are searched for references.
+    fn named(&mut self, name: &'a Name, scope: Option<usize>) -> Option<&'a str> {
+        match name {
+            Name::VVar { value } | Name::GVar { value } => {
+                self.metrics.node_count += 1;
+                Some(value)
+            }
+            Name::Wildcard | Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => {
+                self.stack.push(Item::Name { name, scope });
+                None
+            }
+        }
+    }
+
+    fn visit_graph(&mut self, graph: &'a Graph, depth: Option<usize>, scope: Option<usize>) {
+        self.metrics.node_count += 1;
+        if let Some(depth) = depth {
+            self.metrics.depth = self.metrics.depth.max(depth);
+        }
+
+        match graph {
+            Graph::Nil => {}
+            Graph::Vertex(vertex) => {
+                self.stack.push(self.graph(&vertex.graph, depth, scope));
+                self.vertex(&vertex.vertex.name, scope);
+            }
+            Graph::Var(var) => {
+                self.reference(&var.var, scope);
+                self.stack.push(self.graph(&var.graph, depth, scope));
+            }
+            Graph::Nominate(binding) => self.stack.push(Item::Binding {
+                binding,
+                depth,
+                scope,
+                binder: None,
+            }),
+            Graph::EdgeAnon(edge) => {
+                self.metrics.edge_count += 1;
+                for binding in [&edge.binding_2, &edge.binding_1] {
+                    self.stack.push(Item::Binding {
+                        binding,
+                        depth,
+                        scope,
+                        binder: None,
+                    });
+                }
+            }
+            Graph::EdgeNamed(edge) => {
+                self.metrics.edge_count += 1;
+                let binder = self.named(&edge.name, scope);
+                for binding in [&edge.binding_2, &edge.binding_1] {
+                    self.stack.push(Item::Binding {
+                        binding,
+                        depth,
+                        scope,
+                        binder,
+                    });
+                }
+            }
+            Graph::RuleAnon(rule) => {
+                self.metrics.rule_count += 1;
+                self.stack.push(self.graph(&rule.graph_2, depth, scope));
+                self.stack.push(self.graph(&rule.graph_1, depth, scope));
+            }
+            Graph::RuleNamed(rule) => {
+                self.metrics.rule_count += 1;
+                let binder = self.named(&rule.name, scope);
+                for graph in [&rule.graph_2, &rule.graph_1] {
+                    self.stack.push(Item::Graph {
+                        graph,
+                        depth: depth.map(|depth| depth + 1),
+                        scope,
+                        binder,
+                    });
+                }
+            }
+            Graph::Subgraph(binding) => {
+                self.stack.push(Item::Graph {
+                    graph: &binding.graph_2,
+                    depth: depth.map(|depth| depth + 1),
+                    scope,
+                    binder: Some(&binding.var),
+                });
+                self.stack.push(self.graph(&binding.graph_1, depth, scope));
+            }
+            Graph::Tensor(tensor) => {
+                self.stack.push(self.graph(&tensor.graph_2, depth, scope));
+                self.stack.push(self.graph(&tensor.graph_1, depth, scope));
+            }
+            Graph::Context(context) => {
+                self.metrics.context_count += 1;
+                self.stack.push(self.graph(&context.graph, depth, scope));
+                self.stack.push(Item::Name {
+                    name: &context.name,
+                    scope: None,
+                });
+            }
+        }
+    }
+
+    fn visit_binding(&mut self, binding: &'a Binding, depth: Option<usize>, scope: Option<usize>) {
+        self.metrics.node_count += 1;
+        self.stack.push(Item::Graph {
+            graph: &binding.graph,
+            depth: depth.map(|depth| depth + 1),
+            scope,
+            binder: Some(&binding.var),
+        });
+        self.vertex(&binding.vertex.name, scope);
+    }
+
+    fn visit_name(&mut self, name: &'a Name, scope: Option<usize>) {
+        self.metrics.node_count += 1;
+        match name {
+            Name::Wildcard | Name::VVar { .. } => {}
+            Name::GVar { value } => self.reference(value, scope),
+            Name::QuoteGraph { value } => self.stack.push(Item::Graph {
+                graph: value,
+                depth: None,
+                scope,
+                binder: None,
+            }),
+            Name::QuoteVertex { value } => self.vertex(&value.name, scope),
+        }
+    }
+
+    fn run(mut self, graph: &'a Graph) -> Metrics {
+        self.stack.push(Item::Graph {
+            graph,
+            depth: Some(0),
+            scope: Some(0),
+            binder: None,
+        });
+
+        while let Some(item) = self.stack.pop() {
+            match item {
+                Item::Graph {
+                    graph,
+                    depth,
+                    scope,
+                    binder,
+                } => {
+                    let scope = self.enter(scope, binder);
+                    self.visit_graph(graph, depth, scope);
+                }
+                Item::Binding {
+                    binding,
+                    depth,
+                    scope,
+                    binder,
+                } => {
+                    let scope = self.enter(scope, binder);
+                    self.visit_binding(binding, depth, scope);
+                }
+                Item::Name { name, scope } => {
+                    let scope = self.enter(scope, None);
+                    self.visit_name(name, scope);
+                }
+            }
+        }
+
+        Metrics {
+            distinct_vertices: self.vertices.len(),
+            free_var_count: self.free.len(),
+            ..self.metrics
+        }
+    }
+}
+
+impl Graph {
+    /// Computes all [`Metrics`] of this graph in a single traversal.
+    ///
+    /// Each field agrees with the method it names, so this is a cheaper way
+    /// to get several of them at once.
+    pub fn metrics(&self) -> Metrics {
+        Census::default().run(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Metrics;
+    use crate::ast::THREE_EDGE_GRAPH;
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_metrics_of_three_edge_graph() {
+        let graph = parse_to_ast(THREE_EDGE_GRAPH.into()).unwrap();
+        let metrics = graph.metrics();
+
+        assert_eq!(
+            metrics,
+            Metrics {
+                node_count: 35,
+                depth: 4,
+                vertex_count: 9,
+                distinct_vertices: 3,
+                free_var_count: 1,
+                edge_count: 3,
+                rule_count: 0,
+                context_count: 0,
+                max_binding_depth: 3,
+            }
+        );
+        assert_eq!(metrics.node_count, graph.node_count());
+        assert_eq!(metrics.free_var_count, graph.free_vars().len());
+    }
+
+    #[test]
+    fn test_metrics_resolve_scopes_like_free_vars() {
+        for code in [
+            "0",
+            "e(let a = <a> in e | 0, let b = <@{<B> | x | 0}> in b | 0) * f | 0",
+            "let G = <G> | 0 in <G> | { context \"c\" for @{y | 0} in R [= <R> | 0 z | 0] }",
+        ] {
+            let graph = parse_to_ast(code.into()).unwrap();
+            let metrics = graph.metrics();
+
+            assert_eq!(metrics.node_count, graph.node_count(), "{code}");
+            assert_eq!(metrics.depth, graph.depth(), "{code}");
+            assert_eq!(metrics.free_var_count, graph.free_vars().len(), "{code}");
+            assert_eq!(
+                metrics.distinct_vertices,
+                graph.vertex_names().len(),
+                "{code}"
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! The two namespaces of variables.
+//!
+//! The lexer tells lowercase variables (`LVar`) from uppercase ones (`UVar`)
+//! by their spelling, and the grammar accepts only one of the two at each
+//! position. The AST stores every variable as a plain `String`, so a
+//! hand-built or transformed graph can hold a variable of the wrong class,
+//! which the C printer writes out as GraphL that no longer parses.
+
+use crate::ast::node::Node;
+use crate::ast::{Graph, Name};
+
+const KEYWORDS: [&str; 4] = ["context", "for", "in", "let"];
+
+/// Matches the `LVar` token: `x`, `x'`, `'x`, `_x1`.
+fn is_lvar(s: &str) -> bool {
+    is_var(
+        s,
+        |c| c.is_ascii_lowercase() || c == '\'',
+        |c| c.is_ascii_lowercase(),
+    ) && !KEYWORDS.contains(&s)
+}
+
+/// Matches the `UVar` token: `X`, `Xy'`, `_X1`.
+fn is_uvar(s: &str) -> bool {
+    is_var(s, |c| c.is_ascii_uppercase(), |c| c.is_ascii_uppercase())
+}
+
+/// Makes the vertex name spelled `value`: a [`Name::GVar`] if it is an
+/// uppercase variable and a [`Name::VVar`] otherwise.
+pub(crate) fn vertex_name(value: String) -> Name {
+    if is_uvar(&value) {
+        Name::GVar { value }
+    } else {
+        Name::VVar { value }
+    }
+}
+
+fn is_var(s: &str, first: impl Fn(char) -> bool, after_underscore: impl Fn(char) -> bool) -> bool {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some('_') => {
+            let rest = chars.as_str();
+            !rest.is_empty()
+                && rest
+                    .chars()
+                    .all(|c| after_underscore(c) || c.is_ascii_digit() || c == '_' || c == '\'')
+        }
+        Some(c) if first(c) => chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '\''),
+        _ => false,
+    }
+}
+
+/// The token class of a variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Namespace {
+    /// `LVar`: `x`, `x'`, `_x1`, but not a keyword.
+    Lower,
+    /// `UVar`: `X`, `Xy'`, `_X1`.
+    Upper,
+}
+
+impl Namespace {
+    fn description(self) -> &'static str {
+        match self {
+            Namespace::Lower => "a lowercase",
+            Namespace::Upper => "an uppercase",
+        }
+    }
+}
+
+/// A position that holds a variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VarRole {
+    /// `x` in `x | g`.
+    Process,
+    /// `x` in `let x = <v> in g`.
+    Binding,
+    /// `X` in `let X = g1 in g2`.
+    Subgraph,
+    /// A [`Name::VVar`].
+    VertexName,
+    /// A [`Name::GVar`].
+    GraphName,
+}
+
+impl VarRole {
+    /// Returns the namespace the grammar requires at this position.
+    pub fn namespace(self) -> Namespace {
+        match self {
+            VarRole::Process | VarRole::Binding | VarRole::VertexName => Namespace::Lower,
+            VarRole::Subgraph | VarRole::GraphName => Namespace::Upper,
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            VarRole::Process => "process variable",
+            VarRole::Binding => "binding variable",
+            VarRole::Subgraph => "subgraph variable",
+            VarRole::VertexName => "vertex variable name",
+            VarRole::GraphName => "graph variable name",
+        }
+    }
+}
+
+/// A variable outside the namespace of its position, reported by
+/// [`Graph::validate_name_namespaces`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "{} `{var}` is not {} variable",
+    .role.description(),
+    .role.namespace().description()
+)]
+pub struct NamespaceError {
+    /// Where the variable occurs.
+    pub role: VarRole,
+    /// The variable as spelled in the graph.
+    pub var: String,
+}
+
+/// Checks the variable held directly by `node`, if any.
+pub(crate) fn check(node: Node<'_>) -> Option<NamespaceError> {
+    let (role, var) = match node {
+        Node::Graph(Graph::Var(var)) => (VarRole::Process, &var.var),
+        Node::Graph(Graph::Subgraph(binding)) => (VarRole::Subgraph, &binding.var),
+        Node::Binding(binding) => (VarRole::Binding, &binding.var),
+        Node::Name(Name::VVar { value }) => (VarRole::VertexName, value),
+        Node::Name(Name::GVar { value }) => (VarRole::GraphName, value),
+        _ => return None,
+    };
+
+    let valid = match role.namespace() {
+        Namespace::Lower => is_lvar(var),
+        Namespace::Upper => is_uvar(var),
+    };
+    (!valid).then(|| NamespaceError {
+        role,
+        var: var.clone(),
+    })
+}
+
+impl Graph {
+    /// Checks that every variable belongs to the namespace the grammar
+    /// expects at its position.
+    ///
+    /// Process variables, `let` binding variables and [`Name::VVar`] must be
+    /// lowercase variables that are not keywords; `let X = g1 in g2`
+    /// variables and [`Name::GVar`] must be uppercase. Names inside quoted
+    /// graphs and vertices are checked too.
+    ///
+    /// # Errors
+    ///
+    /// Returns every misplaced variable, in source order.
+    pub fn validate_name_namespaces(&self) -> Result<(), Vec<NamespaceError>> {
+        let errors: Vec<_> = Node::Graph(self).preorder().filter_map(check).collect();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NamespaceError, VarRole};
+    use crate::ast::{GVertex, Graph, Name, Vertex};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_misplaced_gvar_is_reported() {
+        let graph = parse_to_ast("let G = <a> | 0 in <X> | x | 0".into()).unwrap();
+        assert_eq!(graph.validate_name_namespaces(), Ok(()));
+
+        let graph = Graph::Vertex(GVertex {
+            graph: Box::new(graph),
+            vertex: Vertex {
+                name: Name::GVar { value: "a".into() },
+            },
+        });
+        let errors = graph.validate_name_namespaces().unwrap_err();
+
+        assert_eq!(
+            errors,
+            [NamespaceError {
+                role: VarRole::GraphName,
+                var: "a".into(),
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "graph variable name `a` is not an uppercase variable"
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! A uniform view over the four AST node types.
+
+use serde::Serialize;
+
+use crate::ast::{Binding, Graph, Name, Vertex};
+
+/// The constructor of a [`Graph`] node, without its payload.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize)]
+pub enum NodeKind {
+    Nil,
+    Vertex,
+    Var,
+    Nominate,
+    EdgeAnon,
+    EdgeNamed,
+    RuleAnon,
+    RuleNamed,
+    Subgraph,
+    Tensor,
+    Context,
+}
+
+/// A borrowed reference to any node of the AST.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Node<'a> {
+    Graph(&'a Graph),
+    Binding(&'a Binding),
+    Vertex(&'a Vertex),
+    Name(&'a Name),
+}
+
+impl<'a> Node<'a> {
+    /// Iterates over this node and everything beneath it, parents first.
+    pub(crate) fn preorder(self) -> Preorder<'a> {
+        Preorder { stack: vec![self] }
+    }
+
+    /// Returns the direct children of this node in source order.
+    pub(crate) fn children(self) -> Vec<Node<'a>> {
+        match self {
+            Node::Graph(graph) => match graph {
+                Graph::Nil => vec![],
+                Graph::Vertex(vertex) => {
+                    vec![Node::Vertex(&vertex.vertex), Node::Graph(&vertex.graph)]
+                }
+                Graph::Var(var) => vec![Node::Graph(&var.graph)],
+                Graph::Nominate(binding) => vec![Node::Binding(binding)],
+                Graph::EdgeAnon(edge) => {
+                    vec![
+                        Node::Binding(&edge.binding_1),
+                        Node::Binding(&edge.binding_2),
+                    ]
+                }
+                Graph::EdgeNamed(edge) => vec![
+                    Node::Name(&edge.name),
+                    Node::Binding(&edge.binding_1),
+                    Node::Binding(&edge.binding_2),
+                ],
+                Graph::RuleAnon(rule) => {
+                    vec![Node::Graph(&rule.graph_1), Node::Graph(&rule.graph_2)]
+                }
+                Graph::RuleNamed(rule) => vec![
+                    Node::Name(&rule.name),
+                    Node::Graph(&rule.graph_1),
+                    Node::Graph(&rule.graph_2),
+                ],
+                Graph::Subgraph(binding) => {
+                    vec![Node::Graph(&binding.graph_1), Node::Graph(&binding.graph_2)]
+                }
+                Graph::Tensor(tensor) => {
+                    vec![Node::Graph(&tensor.graph_1), Node::Graph(&tensor.graph_2)]
+                }
+                Graph::Context(context) => {
+                    vec![Node::Name(&context.name), Node::Graph(&context.graph)]
+                }
+            },
+            Node::Binding(binding) => {
+                vec![Node::Vertex(&binding.vertex), Node::Graph(&binding.graph)]
+            }
+            Node::Vertex(vertex) => vec![Node::Name(&vertex.name)],
+            Node::Name(name) => match name {
+                Name::QuoteGraph { value } => vec![Node::Graph(value)],
+                Name::QuoteVertex { value } => vec![Node::Vertex(value)],
+                Name::Wildcard | Name::VVar { .. } | Name::GVar { .. } => vec![],
+            },
+        }
+    }
+}
+
+/// Iterator over a node and its descendants in depth-first, left-to-right
+/// pre-order, created by [`Node::preorder`].
+pub(crate) struct Preorder<'a> {
+    stack: Vec<Node<'a>>,
+}
+
+impl<'a> Iterator for Preorder<'a> {
+    type Item = Node<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().into_iter().rev());
+        Some(node)
+    }
+}
+
+impl Graph {
+    /// Returns the constructor of this node.
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Graph::Nil => NodeKind::Nil,
+            Graph::Vertex(_) => NodeKind::Vertex,
+            Graph::Var(_) => NodeKind::Var,
+            Graph::Nominate(_) => NodeKind::Nominate,
+            Graph::EdgeAnon(_) => NodeKind::EdgeAnon,
+            Graph::EdgeNamed(_) => NodeKind::EdgeNamed,
+            Graph::RuleAnon(_) => NodeKind::RuleAnon,
+            Graph::RuleNamed(_) => NodeKind::RuleNamed,
+            Graph::Subgraph(_) => NodeKind::Subgraph,
+            Graph::Tensor(_) => NodeKind::Tensor,
+            Graph::Context(_) => NodeKind::Context,
+        }
+    }
+
+    /// Counts the [`Graph`], [`Binding`], [`Vertex`] and [`Name`] nodes
+    /// reachable from this graph, including those inside quoted names.
+    ///
+    /// Every graph constructor, this graph included, is one node. A vertex
+    /// `<a>` is two: the [`Vertex`] and its [`Name`], so `<a> | 0` has four
+    /// nodes. Edge and rule names and the names of contexts count as a
+    /// [`Name`] each. Variables, which are plain strings, and the string of a
+    /// context are not nodes. Braces only group and add nothing, so `{0}` has
+    /// a single node.
+    ///
+    /// The nodes are visited with an explicit stack, so arbitrarily deep
+    /// graphs can be counted.
+    pub fn node_count(&self) -> usize {
+        Node::Graph(self).preorder().count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ast::THREE_EDGE_GRAPH;
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_node_count() {
+        let count = |code: &str| parse_to_ast(code.into()).unwrap().node_count();
+
+        assert_eq!(count("{0}"), 1);
+        assert_eq!(count("<a> | 0"), 4);
+        assert_eq!(count("E(let a = <a> in 0, let b = <@{0}> in 0)"), 11);
+        assert_eq!(count(THREE_EDGE_GRAPH), 35);
+    }
+}
@@ -0,0 +1,175 @@
+//! Consuming accessors that take a graph apart into its owned pieces.
+//!
+//! Each `into_*` method returns `None` when the graph is a different variant,
+//! and otherwise unboxes the subgraphs so callers need neither a `match` nor
+//! `*Box` dereferencing. Pieces are returned in source order.
+//!
+//! [`FromGraph`] narrows a graph to the node struct of one variant instead.
+
+use crate::ast::{
+    Binding,
+    Error,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    Vertex,
+};
+
+/// A node struct that a [`Graph`] of the matching variant unwraps to, used
+/// by [`parse_as`](crate::parse_as).
+pub trait FromGraph: Sized {
+    /// Unwraps `graph` if it is the variant holding `Self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidVariant`] naming the expected type and the
+    /// kind of graph found.
+    fn from_graph(graph: Graph) -> Result<Self, Error>;
+}
+
+macro_rules! impl_from_graph {
+    ($($variant:ident => $node:ty),* $(,)?) => {
+        $(
+            impl FromGraph for $node {
+                fn from_graph(graph: Graph) -> Result<Self, Error> {
+                    match graph {
+                        Graph::$variant(node) => Ok(node),
+                        graph => Err(Error::InvalidVariant {
+                            context: format!(
+                                "expected {}, found {:?}",
+                                stringify!($node),
+                                graph.kind()
+                            ),
+                        }),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_graph! {
+    Vertex => GVertex,
+    Var => GVar,
+    Nominate => Binding,
+    EdgeAnon => GEdgeAnon,
+    EdgeNamed => GEdgeNamed,
+    RuleAnon => GRuleAnon,
+    RuleNamed => GRuleNamed,
+    Subgraph => GraphBinding,
+    Tensor => GTensor,
+    Context => GContext,
+}
+
+impl Graph {
+    /// Splits `<v> | g` into `(v, g)`.
+    pub fn into_vertex(self) -> Option<(Vertex, Graph)> {
+        match self {
+            Graph::Vertex(vertex) => Some((vertex.vertex, *vertex.graph)),
+            _ => None,
+        }
+    }
+
+    /// Splits `x | g` into `(x, g)`.
+    pub fn into_var(self) -> Option<(String, Graph)> {
+        match self {
+            Graph::Var(var) => Some((var.var, *var.graph)),
+            _ => None,
+        }
+    }
+
+    /// Returns the binding of a nominated `let x = <v> in g`.
+    pub fn into_nominate(self) -> Option<Binding> {
+        match self {
+            Graph::Nominate(binding) => Some(binding),
+            _ => None,
+        }
+    }
+
+    /// Splits `(b1, b2)` into `(b1, b2)`.
+    pub fn into_edge_anon(self) -> Option<(Binding, Binding)> {
+        match self {
+            Graph::EdgeAnon(edge) => Some((edge.binding_1, edge.binding_2)),
+            _ => None,
+        }
+    }
+
+    /// Splits `n(b1, b2)` into `(n, b1, b2)`.
+    pub fn into_edge_named(self) -> Option<(Name, Binding, Binding)> {
+        match self {
+            Graph::EdgeNamed(edge) => Some((edge.name, edge.binding_1, edge.binding_2)),
+            _ => None,
+        }
+    }
+
+    /// Splits `[= g1 g2]` into `(g1, g2)`.
+    pub fn into_rule_anon(self) -> Option<(Graph, Graph)> {
+        match self {
+            Graph::RuleAnon(rule) => Some((*rule.graph_1, *rule.graph_2)),
+            _ => None,
+        }
+    }
+
+    /// Splits `n [= g1 g2]` into `(n, g1, g2)`.
+    pub fn into_rule_named(self) -> Option<(Name, Graph, Graph)> {
+        match self {
+            Graph::RuleNamed(rule) => Some((rule.name, *rule.graph_1, *rule.graph_2)),
+            _ => None,
+        }
+    }
+
+    /// Splits `let X = g1 in g2` into `(X, g1, g2)`.
+    pub fn into_subgraph(self) -> Option<(String, Graph, Graph)> {
+        match self {
+            Graph::Subgraph(binding) => Some((binding.var, *binding.graph_1, *binding.graph_2)),
+            _ => None,
+        }
+    }
+
+    /// Splits `g1 * g2` into `(g1, g2)`.
+    pub fn into_tensor(self) -> Option<(Graph, Graph)> {
+        match self {
+            Graph::Tensor(tensor) => Some((*tensor.graph_1, *tensor.graph_2)),
+            _ => None,
+        }
+    }
+
+    /// Splits `context "s" for n in g` into `(s, n, g)`.
+    pub fn into_context(self) -> Option<(String, Name, Graph)> {
+        match self {
+            Graph::Context(context) => Some((context.string, context.name, *context.graph)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_into_tensor() {
+        let graph = parse_to_ast("<a> | 0 * { <b> | 0 * <c> | 0 }".into()).unwrap();
+
+        let (left, right) = graph.into_tensor().unwrap();
+
+        assert_eq!(left, parse_to_ast("<a> | 0".into()).unwrap());
+        assert_eq!(right, parse_to_ast("<b> | 0 * <c> | 0".into()).unwrap());
+    }
+
+    #[test]
+    fn test_into_other_variant_is_none() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+
+        assert!(graph.clone().into_tensor().is_none());
+        assert!(graph.into_vertex().is_some());
+    }
+}
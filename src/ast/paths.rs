@@ -0,0 +1,536 @@
+//! Navigation by child-index paths.
+//!
+//! A path is a sequence of indices into [`Graph::children`], starting at the
+//! root: `[]` is the root itself and `[1, 0]` is the first child of its
+//! second child.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::ast::{Error, Graph, NodeKind};
+use crate::printer::{PrintOptions, Printer};
+
+/// The structure of a graph with every name, variable and string left out,
+/// see [`Graph::shape`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct ShapeTree {
+    pub kind: NodeKind,
+    /// One tree per entry of [`Graph::children`], in the same order.
+    pub children: Vec<ShapeTree>,
+}
+
+impl Graph {
+    /// Returns the graphs directly beneath this one, in source order.
+    ///
+    /// Bindings are looked through, so the children of an edge are the
+    /// continuations of its two bindings. Graphs quoted inside names are not
+    /// children.
+    pub fn children(&self) -> Vec<&Graph> {
+        match self {
+            Graph::Nil => vec![],
+            Graph::Vertex(vertex) => vec![&vertex.graph],
+            Graph::Var(var) => vec![&var.graph],
+            Graph::Nominate(binding) => vec![&binding.graph],
+            Graph::EdgeAnon(edge) => vec![&edge.binding_1.graph, &edge.binding_2.graph],
+            Graph::EdgeNamed(edge) => vec![&edge.binding_1.graph, &edge.binding_2.graph],
+            Graph::RuleAnon(rule) => vec![&rule.graph_1, &rule.graph_2],
+            Graph::RuleNamed(rule) => vec![&rule.graph_1, &rule.graph_2],
+            Graph::Subgraph(binding) => vec![&binding.graph_1, &binding.graph_2],
+            Graph::Tensor(tensor) => vec![&tensor.graph_1, &tensor.graph_2],
+            Graph::Context(context) => vec![&context.graph],
+        }
+    }
+
+    /// Mutable counterpart of [`children`](Self::children), in the same order.
+    pub fn children_mut(&mut self) -> Vec<&mut Graph> {
+        match self {
+            Graph::Nil => vec![],
+            Graph::Vertex(vertex) => vec![&mut vertex.graph],
+            Graph::Var(var) => vec![&mut var.graph],
+            Graph::Nominate(binding) => vec![&mut binding.graph],
+            Graph::EdgeAnon(edge) => vec![&mut edge.binding_1.graph, &mut edge.binding_2.graph],
+            Graph::EdgeNamed(edge) => vec![&mut edge.binding_1.graph, &mut edge.binding_2.graph],
+            Graph::RuleAnon(rule) => vec![&mut rule.graph_1, &mut rule.graph_2],
+            Graph::RuleNamed(rule) => vec![&mut rule.graph_1, &mut rule.graph_2],
+            Graph::Subgraph(binding) => vec![&mut binding.graph_1, &mut binding.graph_2],
+            Graph::Tensor(tensor) => vec![&mut tensor.graph_1, &mut tensor.graph_2],
+            Graph::Context(context) => vec![&mut context.graph],
+        }
+    }
+
+    /// Numbers every graph node in pre-order, following [`children`]: the
+    /// root is `0`, a node comes before its children, and children are
+    /// numbered left to right. The index of each entry equals its number.
+    ///
+    /// [`children`]: Self::children
+    pub fn number_nodes(&self) -> Vec<(usize, &Graph)> {
+        let mut numbered = Vec::new();
+        let mut stack = vec![self];
+
+        while let Some(graph) = stack.pop() {
+            numbered.push((numbered.len(), graph));
+            stack.extend(graph.children().into_iter().rev());
+        }
+
+        numbered
+    }
+
+    /// Returns the [`number_nodes`](Self::number_nodes) number of `node`.
+    ///
+    /// `node` is looked up by identity, so it must be a reference into this
+    /// graph; a structurally equal graph stored elsewhere is not found.
+    pub fn node_number(&self, node: &Graph) -> Option<usize> {
+        self.number_nodes()
+            .into_iter()
+            .find_map(|(number, graph)| std::ptr::eq(graph, node).then_some(number))
+    }
+
+    /// Returns the paths, in pre-order, of the `0` operands of tensors.
+    ///
+    /// `g * 0` and `0 * g` are both just `g`, so these nil nodes can be
+    /// removed without changing the meaning of the graph. Nil continuations
+    /// are not reported: `<a> | 0` cannot be written without its `0`.
+    pub fn dead_nils(&self) -> Vec<Vec<usize>> {
+        let mut dead = Vec::new();
+        let mut stack = vec![(self, Vec::new(), false)];
+
+        while let Some((graph, path, operand)) = stack.pop() {
+            if operand && *graph == Graph::Nil {
+                dead.push(path);
+                continue;
+            }
+            let tensor = matches!(graph, Graph::Tensor(_));
+            for (index, child) in graph.children().into_iter().enumerate().rev() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                stack.push((child, child_path, tensor));
+            }
+        }
+
+        dead
+    }
+
+    /// Returns, for every leaf in pre-order, the kinds of the nodes from the
+    /// root down to it, both included.
+    ///
+    /// Following [`children`](Self::children), every path of a graph ends in
+    /// [`Graph::Nil`]: a vertex `<a> | g` or variable `x | g` always has a
+    /// continuation, which is `0` when the flow stops there.
+    pub fn root_to_leaf_paths(&self) -> Vec<Vec<NodeKind>> {
+        let mut paths = Vec::new();
+        let mut stack = vec![(self, vec![self.kind()])];
+
+        while let Some((graph, kinds)) = stack.pop() {
+            let children = graph.children();
+            if children.is_empty() {
+                paths.push(kinds);
+                continue;
+            }
+            for child in children.into_iter().rev() {
+                let mut child_kinds = kinds.clone();
+                child_kinds.push(child.kind());
+                stack.push((child, child_kinds));
+            }
+        }
+
+        paths
+    }
+
+    /// Returns the length of the longest path from this graph to a node.
+    ///
+    /// Each step into a [`child`](Self::children), including the continuation
+    /// of a binding, adds one, so `0` has depth 0 and `<a> | 0` depth 1.
+    /// Graphs inside quoted names are not children and add nothing. The
+    /// walk uses an explicit stack, so it is safe on untrusted input of any
+    /// depth.
+    pub fn depth(&self) -> usize {
+        let mut max = 0;
+        let mut stack = vec![(self, 0)];
+
+        while let Some((graph, depth)) = stack.pop() {
+            max = max.max(depth);
+            stack.extend(graph.children().into_iter().map(|child| (child, depth + 1)));
+        }
+
+        max
+    }
+
+    /// Returns a copy of this graph cut off at `max_depth`, for previewing
+    /// large graphs.
+    ///
+    /// Every node whose path is `max_depth` long is replaced by `0`, so the
+    /// result has no path longer than `max_depth`. `0` is used as the
+    /// truncation sentinel because it keeps the preview valid GraphL; a
+    /// truncated node cannot be told apart from a `0` that was there before.
+    /// Binders survive the cut, but references below it are dropped.
+    pub fn prune_to_depth(&self, max_depth: usize) -> Graph {
+        let mut pruned = self.clone();
+        let mut stack = vec![(&mut pruned, 0)];
+
+        while let Some((graph, depth)) = stack.pop() {
+            if depth == max_depth {
+                *graph = Graph::Nil;
+                continue;
+            }
+            stack.extend(
+                graph
+                    .children_mut()
+                    .into_iter()
+                    .map(|child| (child, depth + 1)),
+            );
+        }
+
+        pruned
+    }
+
+    /// Returns every subgraph reachable by a path, with that path, in
+    /// pre-order.
+    pub(crate) fn subgraphs_with_paths(&self) -> Vec<(Vec<usize>, &Graph)> {
+        let mut subgraphs = Vec::new();
+        let mut stack = vec![(Vec::new(), self)];
+
+        while let Some((path, graph)) = stack.pop() {
+            for (index, child) in graph.children().into_iter().enumerate().rev() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                stack.push((child_path, child));
+            }
+            subgraphs.push((path, graph));
+        }
+
+        subgraphs
+    }
+
+    /// Returns the label-free shape of this graph: the kind of every node,
+    /// arranged as [`children`](Self::children) arranges them.
+    ///
+    /// Two graphs have the same shape exactly when [`zip`](Self::zip)
+    /// succeeds on them. Quoted graphs inside names are not part of the
+    /// shape.
+    pub fn shape(&self) -> ShapeTree {
+        let mut preorder = Vec::new();
+        let mut stack = vec![self];
+        while let Some(graph) = stack.pop() {
+            let children = graph.children();
+            preorder.push((graph.kind(), children.len()));
+            stack.extend(children.into_iter().rev());
+        }
+
+        // Every subtree follows its node in pre-order, so building from the
+        // end finds the children of a node on top of `trees`, first child
+        // first.
+        let mut trees: Vec<ShapeTree> = Vec::new();
+        for (kind, count) in preorder.into_iter().rev() {
+            let children = trees.drain(trees.len() - count..).rev().collect();
+            trees.push(ShapeTree { kind, children });
+        }
+
+        trees.pop().expect("the root is built last")
+    }
+
+    /// Returns the number of [`children`](Self::children) of every node,
+    /// keyed by its path.
+    ///
+    /// Tensors, edges, rules and `let X = ...` have two children, vertices,
+    /// variables, bindings and contexts one, and `0` none.
+    pub fn fanout(&self) -> BTreeMap<Vec<usize>, usize> {
+        self.subgraphs_with_paths()
+            .into_iter()
+            .map(|(path, graph)| (path, graph.children().len()))
+            .collect()
+    }
+
+    /// Walks this graph and `other` in lockstep, returning the pairs of
+    /// corresponding nodes in pre-order.
+    ///
+    /// Nodes correspond when they are at the same path, following
+    /// [`children`](Self::children). The shapes agree as long as every pair
+    /// has the same [`NodeKind`]; names, variables and strings are not
+    /// compared, so alpha-equivalent graphs always zip.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::ShapeMismatch`] with the first path, in pre-order,
+    /// at which the two nodes have different kinds.
+    pub fn zip<'a>(&'a self, other: &'a Graph) -> Result<Vec<(&'a Graph, &'a Graph)>, Error> {
+        let mut pairs = Vec::new();
+        let mut stack = vec![(Vec::new(), self, other)];
+
+        while let Some((path, left, right)) = stack.pop() {
+            if left.kind() != right.kind() {
+                return Err(Error::ShapeMismatch { path });
+            }
+            let children = left.children().into_iter().zip(right.children());
+            for (index, (left, right)) in children.enumerate().rev() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                stack.push((child_path, left, right));
+            }
+            pairs.push((left, right));
+        }
+
+        Ok(pairs)
+    }
+
+    /// Returns the node reached from this graph by following `path`, each
+    /// index selecting one of the [`children`](Self::children) of the node
+    /// before it. The empty path leads to this graph itself.
+    ///
+    /// Returns `None` if an index is out of range.
+    pub fn subgraph_at(&self, path: &[usize]) -> Option<&Graph> {
+        path.iter()
+            .try_fold(self, |graph, &index| graph.children().get(index).copied())
+    }
+
+    pub(crate) fn node_at_mut(&mut self, path: &[usize]) -> Option<&mut Graph> {
+        path.iter().try_fold(self, |graph, &index| {
+            graph.children_mut().into_iter().nth(index)
+        })
+    }
+
+    /// Renders only the subgraph at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPath`] if `path` does not lead to a node.
+    pub fn print_at(&self, path: &[usize], opts: &PrintOptions) -> Result<String, Error> {
+        let graph = self.subgraph_at(path).ok_or_else(|| Error::InvalidPath {
+            path: path.to_vec(),
+        })?;
+
+        let mut printer = Printer::new(opts);
+        printer.graph(graph, 0);
+        Ok(printer.finish())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ast::{Error, Graph, GraphBuilder, NodeKind, ShapeTree, THREE_EDGE_GRAPH};
+    use crate::parse_to_ast;
+    use crate::printer::PrintOptions;
+
+    #[test]
+    fn test_dead_nils() {
+        let graph = parse_to_ast("<a> | 0 * 0".into()).unwrap();
+        assert_eq!(graph.dead_nils(), [vec![1]]);
+
+        let nested = parse_to_ast("<a> | { 0 * { <b> | 0 * 0 } }".into()).unwrap();
+        assert_eq!(nested.dead_nils(), [vec![0, 0], vec![0, 1, 1]]);
+    }
+
+    #[test]
+    fn test_root_to_leaf_paths() {
+        let graph = parse_to_ast("<a> | 0 * x | <b> | 0".into()).unwrap();
+
+        assert_eq!(
+            graph.root_to_leaf_paths(),
+            [
+                vec![NodeKind::Tensor, NodeKind::Vertex, NodeKind::Nil],
+                vec![
+                    NodeKind::Tensor,
+                    NodeKind::Var,
+                    NodeKind::Vertex,
+                    NodeKind::Nil
+                ],
+            ]
+        );
+        assert_eq!(Graph::Nil.root_to_leaf_paths(), [vec![NodeKind::Nil]]);
+    }
+
+    #[test]
+    fn test_prune_to_depth() {
+        let graph = parse_to_ast(THREE_EDGE_GRAPH.into()).unwrap();
+        let expected = parse_to_ast(
+            "(
+               let n2 = <notification> in {
+                 (let e2 = <encryption> in 0, let n1 = <notification> in 0)
+               },
+               let e3 = <encryption> in e1 | 0
+             )"
+            .into(),
+        )
+        .unwrap();
+
+        assert_eq!(graph.prune_to_depth(2), expected);
+        assert_eq!(graph.prune_to_depth(0), Graph::Nil);
+        assert_eq!(graph.prune_to_depth(10), graph);
+    }
+
+    #[test]
+    fn test_depth() {
+        let depth = |code: &str| parse_to_ast(code.into()).unwrap().depth();
+
+        assert_eq!(depth("0"), 0);
+        assert_eq!(depth("<a> | 0"), 1);
+        assert_eq!(depth("<@{<b> | <c> | 0}> | 0"), 1);
+        assert_eq!(depth(THREE_EDGE_GRAPH), 4);
+
+        let chain = (0..10_000).fold(GraphBuilder::nil(), |graph, _| graph.prefix_vertex("a"));
+        assert_eq!(chain.build().depth(), 10_000);
+    }
+
+    #[test]
+    fn test_shape() {
+        let shape = parse_to_ast("<a> | 0 * (let x = <b> in x | 0, let y = <c> in 0)".into())
+            .unwrap()
+            .shape();
+        let leaf = |kind| ShapeTree {
+            kind,
+            children: vec![],
+        };
+
+        assert_eq!(
+            shape,
+            ShapeTree {
+                kind: NodeKind::Tensor,
+                children: vec![
+                    ShapeTree {
+                        kind: NodeKind::Vertex,
+                        children: vec![leaf(NodeKind::Nil)],
+                    },
+                    ShapeTree {
+                        kind: NodeKind::EdgeAnon,
+                        children: vec![
+                            ShapeTree {
+                                kind: NodeKind::Var,
+                                children: vec![leaf(NodeKind::Nil)],
+                            },
+                            leaf(NodeKind::Nil),
+                        ],
+                    },
+                ],
+            }
+        );
+
+        let chain = (0..10_000).fold(GraphBuilder::nil(), |graph, _| graph.prefix_vertex("a"));
+        let mut tree = &chain.build().shape();
+        let mut depth = 0;
+        while let [child] = tree.children.as_slice() {
+            assert_eq!(tree.kind, NodeKind::Vertex);
+            tree = child;
+            depth += 1;
+        }
+        assert_eq!((depth, tree.kind), (10_000, NodeKind::Nil));
+    }
+
+    #[test]
+    fn test_fanout() {
+        let graph = parse_to_ast("<a> | 0 * x | 0".into()).unwrap();
+        let fanout = graph.fanout();
+
+        assert_eq!(fanout.len(), 5);
+        assert_eq!(fanout[&vec![]], 2);
+        assert_eq!(fanout[&vec![0]], 1);
+        assert_eq!(fanout[&vec![1, 0]], 0);
+    }
+
+    #[test]
+    fn test_zip() {
+        let graph_1 =
+            parse_to_ast("(let a = <a> in a | 0, let b = <b> in <c> | 0)".into()).unwrap();
+        let graph_2 =
+            parse_to_ast("(let x = <a> in x | 0, let y = <b> in <d> | 0)".into()).unwrap();
+
+        let pairs = graph_1.zip(&graph_2).unwrap();
+
+        assert_eq!(pairs.len(), graph_1.number_nodes().len());
+        assert!(std::ptr::eq(pairs[0].0, &graph_1) && std::ptr::eq(pairs[0].1, &graph_2));
+        assert!(
+            pairs
+                .iter()
+                .all(|(left, right)| left.kind() == right.kind())
+        );
+
+        let graph_3 = parse_to_ast("(let x = <a> in x | 0, let y = <b> in z | 0)".into()).unwrap();
+        assert!(matches!(
+            graph_1.zip(&graph_3),
+            Err(Error::ShapeMismatch { path }) if path == [1]
+        ));
+    }
+
+    #[test]
+    fn test_number_nodes_pre_order() {
+        let graph =
+            parse_to_ast("{ <a> | x | 0 * (let b = <b> in 0, let c = <c> in 0) }".into()).unwrap();
+
+        let numbered = graph.number_nodes();
+        let kinds: Vec<_> = numbered.iter().map(|(_, graph)| graph.kind()).collect();
+
+        assert!(std::ptr::eq(numbered[0].1, &graph));
+        assert!(
+            numbered
+                .iter()
+                .enumerate()
+                .all(|(i, (number, _))| i == *number)
+        );
+        assert_eq!(
+            kinds,
+            [
+                NodeKind::Tensor,
+                NodeKind::Vertex,
+                NodeKind::Var,
+                NodeKind::Nil,
+                NodeKind::EdgeAnon,
+                NodeKind::Nil,
+                NodeKind::Nil,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_node_number_reverse_lookup() {
+        let graph = parse_to_ast("{ <a> | 0 * <b> | 0 }".into()).unwrap();
+        let copy = graph.clone();
+
+        for (number, node) in graph.number_nodes() {
+            assert_eq!(graph.node_number(node), Some(number));
+        }
+        assert_eq!(graph.node_number(&copy), None);
+    }
+
+    #[test]
+    fn test_subgraph_at_vertex_continuation() {
+        let graph = parse_to_ast("{ <a> | 0 * x | <b> | 0 }".into()).unwrap();
+
+        assert_eq!(graph.subgraph_at(&[]), Some(&graph));
+        assert_eq!(graph.subgraph_at(&[1, 0, 0]), Some(&Graph::Nil));
+        assert!(matches!(graph.subgraph_at(&[1, 0]), Some(Graph::Vertex(_))));
+        assert_eq!(graph.subgraph_at(&[1, 0, 0, 0]), None);
+        assert_eq!(graph.subgraph_at(&[2]), None);
+    }
+
+    #[test]
+    fn test_print_at_vertex_continuation() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+
+        assert_eq!(graph.print_at(&[0], &PrintOptions::default()).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_print_at_nested_path() {
+        let graph = parse_to_ast(
+            "(let a = <a> in <a> | 0, let b = <b> in { <b> | 0 * <c> | x | 0 })".into(),
+        )
+        .unwrap();
+        let opts = PrintOptions::default();
+
+        assert_eq!(graph.print_at(&[], &opts).unwrap(), graph.print(&opts));
+        assert_eq!(
+            graph.print_at(&[1], &opts).unwrap(),
+            "< b > | 0 * < c > | x | 0"
+        );
+        assert_eq!(graph.print_at(&[1, 1, 0], &opts).unwrap(), "x | 0");
+    }
+
+    #[test]
+    fn test_print_at_invalid_path() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+
+        assert!(matches!(
+            graph.print_at(&[0, 0], &PrintOptions::default()),
+            Err(Error::InvalidPath { path }) if path == [0, 0]
+        ));
+    }
+}
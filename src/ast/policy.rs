@@ -0,0 +1,122 @@
+//! Naming policies defined by the embedder.
+//!
+//! The grammar decides which spellings are variables at all; a policy can
+//! narrow that further, e.g. to snake_case channel names for Rholang.
+
+use crate::ast::{
+    Binding,
+    Error,
+    GEdgeNamed,
+    GRuleNamed,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+};
+use crate::visitor::Visitor;
+use crate::walker::Walker;
+
+struct NameCheck<F>(F);
+
+impl<F: Fn(&str) -> bool> NameCheck<F> {
+    fn check(&self, name: &str) -> Result<(), Error> {
+        if (self.0)(name) {
+            Ok(())
+        } else {
+            Err(Error::InvalidName {
+                name: name.to_owned(),
+            })
+        }
+    }
+
+    fn check_name(&self, name: &Name) -> Result<(), Error> {
+        match name {
+            Name::VVar { value } | Name::GVar { value } => self.check(value),
+            Name::Wildcard | Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => Ok(()),
+        }
+    }
+}
+
+impl<'a, F: Fn(&str) -> bool> Visitor<'a, (), Error> for NameCheck<F> {
+    fn visit_vertex(&self, _: (), vertex: &'a GVertex) -> Result<(), Error> {
+        self.check_name(&vertex.vertex.name)
+    }
+
+    fn visit_var(&self, _: (), var: &'a GVar) -> Result<(), Error> {
+        self.check(&var.var)
+    }
+
+    fn visit_nominate(&self, _: (), binding: &'a Binding) -> Result<(), Error> {
+        self.check(&binding.var)?;
+        self.check_name(&binding.vertex.name)
+    }
+
+    fn visit_edge_named(&self, _: (), edge: &'a GEdgeNamed) -> Result<(), Error> {
+        self.check_name(&edge.name)
+    }
+
+    fn visit_rule_named(&self, _: (), rule: &'a GRuleNamed) -> Result<(), Error> {
+        self.check_name(&rule.name)
+    }
+
+    fn visit_subgraph(&self, _: (), subgraph: &'a GraphBinding) -> Result<(), Error> {
+        self.check(&subgraph.var)
+    }
+}
+
+impl Graph {
+    /// Checks every vertex name and variable against `valid`.
+    ///
+    /// The names checked are the variables of `x | g`, `let x = ...` and
+    /// `let X = ...`, and the plain variable names of vertices, named edges
+    /// and named rules. Names inside quoted graphs and vertices and the names
+    /// of contexts are not checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidName`] for the first name, in walking order,
+    /// that `valid` rejects.
+    pub fn validate_names(&self, valid: impl Fn(&str) -> bool) -> Result<(), Error> {
+        Walker::new(self).try_visit((), NameCheck(valid))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ast::{Error, GVertex, Graph, Name, Vertex};
+    use crate::{parse_str, parse_with_validator};
+
+    fn no_whitespace(name: &str) -> bool {
+        !name.contains(char::is_whitespace)
+    }
+
+    #[test]
+    fn test_name_with_space_is_rejected() {
+        let graph = Graph::Vertex(GVertex {
+            graph: Box::new(parse_str("let a = <a> in 0").unwrap()),
+            vertex: Vertex {
+                name: Name::VVar {
+                    value: "my chan".into(),
+                },
+            },
+        });
+
+        assert!(matches!(
+            graph.validate_names(no_whitespace),
+            Err(Error::InvalidName { name }) if name == "my chan"
+        ));
+        assert!(parse_with_validator("<my_chan> | x | 0", no_whitespace).is_ok());
+    }
+
+    #[test]
+    fn test_parse_with_validator_enforces_policy() {
+        let snake_case = |name: &str| name.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+
+        assert!(parse_with_validator("let chan = <out_chan> in 0", snake_case).is_ok());
+        assert!(matches!(
+            parse_with_validator("(let a = <a> in 0, let b = <outChan> in 0)", snake_case),
+            Err(Error::InvalidName { name }) if name == "outChan"
+        ));
+    }
+}
@@ -0,0 +1,266 @@
+//! The process-algebra reading of a graph.
+//!
+//! A vertex `<a> | g` or variable `x | g` performs an action and continues
+//! as `g`, and a tensor runs its operands in parallel. [`ProcessTerm`] spells
+//! these out as sequential and parallel composition, normalized so that
+//! terms equal up to associativity and commutativity of `*` and the unit `0`
+//! are equal.
+
+use crate::ast::{Binding, Graph, Name};
+use crate::printer::{PrintOptions, Printer};
+
+/// A graph as a process term, see [`Graph::to_process_term`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProcessTerm {
+    /// At least two terms in parallel, none of them `Par` or `Nil`, sorted.
+    Par(Vec<ProcessTerm>),
+    /// The first term followed by the second, which is never `Nil`.
+    Seq(Box<ProcessTerm>, Box<ProcessTerm>),
+    /// A single step, labelled with a vertex name or variable.
+    Action(String),
+    /// The inactive process.
+    Nil,
+}
+
+impl ProcessTerm {
+    fn seq(first: ProcessTerm, then: ProcessTerm) -> ProcessTerm {
+        match then {
+            ProcessTerm::Nil => first,
+            then => ProcessTerm::Seq(Box::new(first), Box::new(then)),
+        }
+    }
+
+    fn par(terms: impl IntoIterator<Item = ProcessTerm>) -> ProcessTerm {
+        let mut operands = Vec::new();
+        for term in terms {
+            match term {
+                ProcessTerm::Par(nested) => operands.extend(nested),
+                ProcessTerm::Nil => {}
+                term => operands.push(term),
+            }
+        }
+        operands.sort();
+
+        match operands.len() {
+            0 => ProcessTerm::Nil,
+            1 => operands.pop().expect("one operand"),
+            _ => ProcessTerm::Par(operands),
+        }
+    }
+
+    /// The most actions of this term that can be enabled at the same time.
+    fn width(&self) -> usize {
+        enum Step<'t> {
+            Visit(&'t ProcessTerm),
+            Sum(usize),
+            Max,
+        }
+
+        let mut widths = Vec::new();
+        let mut stack = vec![Step::Visit(self)];
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Visit(ProcessTerm::Par(terms)) => {
+                    stack.push(Step::Sum(terms.len()));
+                    stack.extend(terms.iter().map(Step::Visit));
+                }
+                Step::Visit(ProcessTerm::Seq(first, then)) => {
+                    stack.extend([Step::Max, Step::Visit(then), Step::Visit(first)]);
+                }
+                Step::Visit(ProcessTerm::Action(_)) => widths.push(1),
+                Step::Visit(ProcessTerm::Nil) => widths.push(0),
+                Step::Sum(count) => {
+                    let sum = widths.drain(widths.len() - count..).sum();
+                    widths.push(sum);
+                }
+                Step::Max => {
+                    let then = widths.pop().expect("the width of a continuation");
+                    let first = widths.pop().expect("the width of a first step");
+                    widths.push(first.max(then));
+                }
+            }
+        }
+
+        widths.pop().expect("the width of the whole term")
+    }
+}
+
+fn label(name: &Name) -> String {
+    let opts = PrintOptions::default();
+    let mut printer = Printer::new(&opts);
+    printer.name(name);
+    printer.finish()
+}
+
+/// A pending piece of work of [`Graph::to_process_term`].
+enum Step<'g> {
+    /// Translate a graph, pushing its term.
+    Visit(&'g Graph),
+    /// Replace the term on top with the action followed by it.
+    Seq(String),
+    /// Replace that many terms on top with their parallel composition.
+    Par(usize),
+}
+
+impl<'g> Step<'g> {
+    /// The steps of a binding `let x = <v> in g`, which acts as `<v> | g`,
+    /// in the order they are pushed.
+    fn binding(binding: &'g Binding) -> [Step<'g>; 2] {
+        [
+            Step::Seq(label(&binding.vertex.name)),
+            Step::Visit(&binding.graph),
+        ]
+    }
+}
+
+impl Graph {
+    /// Returns the process-algebra view of this graph.
+    ///
+    /// A vertex or variable becomes an [`Action`](ProcessTerm::Action) labelled
+    /// with the printed vertex name or the variable, sequenced before its
+    /// continuation, and tensors become [`Par`](ProcessTerm::Par). The other
+    /// constructs are read through:
+    ///
+    /// * a binding `let x = <v> in g` acts as `<v> | g`;
+    /// * an edge runs its two bindings in parallel;
+    /// * `let X = g1 in g2` is `g2`, and a context is its scope;
+    /// * rules describe rewrites rather than processes, and become `Nil`.
+    pub fn to_process_term(&self) -> ProcessTerm {
+        let mut terms = Vec::new();
+        let mut stack = vec![Step::Visit(self)];
+
+        while let Some(step) = stack.pop() {
+            match step {
+                Step::Visit(graph) => match graph {
+                    Graph::Nil | Graph::RuleAnon(_) | Graph::RuleNamed(_) => {
+                        terms.push(ProcessTerm::Nil)
+                    }
+                    Graph::Vertex(vertex) => stack.extend([
+                        Step::Seq(label(&vertex.vertex.name)),
+                        Step::Visit(&vertex.graph),
+                    ]),
+                    Graph::Var(var) => {
+                        stack.extend([Step::Seq(var.var.clone()), Step::Visit(&var.graph)])
+                    }
+                    Graph::Nominate(nominate) => stack.extend(Step::binding(nominate)),
+                    Graph::EdgeAnon(edge) => {
+                        stack.push(Step::Par(2));
+                        stack.extend(Step::binding(&edge.binding_2));
+                        stack.extend(Step::binding(&edge.binding_1));
+                    }
+                    Graph::EdgeNamed(edge) => {
+                        stack.push(Step::Par(2));
+                        stack.extend(Step::binding(&edge.binding_2));
+                        stack.extend(Step::binding(&edge.binding_1));
+                    }
+                    Graph::Subgraph(subgraph) => stack.push(Step::Visit(&subgraph.graph_2)),
+                    Graph::Tensor(_) => {
+                        let operands = graph.flatten_tensors();
+                        stack.push(Step::Par(operands.len()));
+                        stack.extend(operands.into_iter().rev().map(Step::Visit));
+                    }
+                    Graph::Context(context) => stack.push(Step::Visit(&context.graph)),
+                },
+                Step::Seq(action) => {
+                    let then = terms.pop().expect("the term of a continuation");
+                    terms.push(ProcessTerm::seq(ProcessTerm::Action(action), then));
+                }
+                Step::Par(count) => {
+                    let operands: Vec<_> = terms.drain(terms.len() - count..).collect();
+                    terms.push(ProcessTerm::par(operands));
+                }
+            }
+        }
+
+        terms.pop().expect("the term of the whole graph")
+    }
+
+    /// Returns the widest parallel composition of this graph: the most
+    /// branches that can be active at once, which bounds the concurrency of
+    /// the process it describes.
+    ///
+    /// Nested tensors are flattened, so each operand of `a * { b * c }`
+    /// counts and the width is 3. The branches of an edge run in parallel, a
+    /// sequence `<a> | g` is as wide as the wider of `<a>` and `g`, and `0`
+    /// has width 0. Constructs are read as in
+    /// [`to_process_term`](Self::to_process_term), so rules do not count.
+    pub fn parallel_width(&self) -> usize {
+        self.to_process_term().width()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ProcessTerm;
+    use crate::ast::GraphBuilder;
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_tensor_of_vertices_is_par_of_actions() {
+        let graph = parse_to_ast("<b> | 0 * <a> | 0".into()).unwrap();
+
+        assert_eq!(
+            graph.to_process_term(),
+            ProcessTerm::Par(vec![
+                ProcessTerm::Action("a".into()),
+                ProcessTerm::Action("b".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parallel_width_flattens_tensors() {
+        let width = |code: &str| parse_to_ast(code.into()).unwrap().parallel_width();
+
+        assert_eq!(width("<a> | 0 * { <b> | 0 * <c> | 0 }"), 3);
+        assert_eq!(width("0"), 0);
+        assert_eq!(width("<a> | x | 0"), 1);
+        assert_eq!(
+            width("<a> | { (let b = <b> in 0, let c = <c> in { x | 0 * y | 0 }) * 0 }"),
+            3
+        );
+    }
+
+    #[test]
+    fn test_process_term_normalizes_tensors() {
+        let graph_1 = parse_to_ast("{ <a> | x | 0 * 0 } * { <b> | 0 * <c> | 0 }".into()).unwrap();
+        let graph_2 = parse_to_ast("<c> | 0 * { <b> | 0 * <a> | x | 0 }".into()).unwrap();
+
+        let term = graph_1.to_process_term();
+
+        assert_eq!(term, graph_2.to_process_term());
+        assert_eq!(
+            term,
+            ProcessTerm::Par(vec![
+                ProcessTerm::Seq(
+                    Box::new(ProcessTerm::Action("a".into())),
+                    Box::new(ProcessTerm::Action("x".into())),
+                ),
+                ProcessTerm::Action("b".into()),
+                ProcessTerm::Action("c".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_process_term_of_deep_chain() {
+        let chain = (0..10_000)
+            .fold(GraphBuilder::nil(), |graph, index| {
+                graph.prefix_vertex(format!("v{index}"))
+            })
+            .build();
+
+        let mut term = &chain.to_process_term();
+        let mut length = 1;
+        while let ProcessTerm::Seq(first, then) = term {
+            assert_eq!(
+                **first,
+                ProcessTerm::Action(format!("v{}", 10_000 - length))
+            );
+            term = then;
+            length += 1;
+        }
+        assert_eq!((length, term), (10_000, &ProcessTerm::Action("v0".into())));
+        assert_eq!(chain.parallel_width(), 1);
+    }
+}
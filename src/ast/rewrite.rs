@@ -0,0 +1,360 @@
+//! Matching patterns and applying rules.
+//!
+//! A pattern, such as the left-hand side of a rule, is an ordinary graph.
+//! Its pattern variables are the process variables that are free in the
+//! pattern and continue with `0`, `x | 0`: each matches any subgraph, and
+//! repeated occurrences of one variable must match equal subgraphs. Names,
+//! including graph variables such as `<X>`, are never pattern variables.
+//!
+//! Every other node matches a node of the same kind with the same vertex
+//! names, strings and free variables. Variables bound inside the pattern
+//! match variables bound at the same place in the subject, so binders may be
+//! named differently. A pattern variable never captures a subgraph that
+//! refers to a binder of the matched region, since the right-hand side of a
+//! rule could not refer to that binder.
+
+use std::collections::BTreeMap;
+
+use crate::ast::{Binding, Error, GRuleNamed, GVar, Graph, Name, Vertex};
+use crate::printer::{PrintOptions, Printer};
+
+/// A place where a pattern matches, see [`Graph::match_pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// The path of the matching subgraph.
+    pub path: Vec<usize>,
+    /// The subgraph captured by each pattern variable.
+    pub bindings: BTreeMap<String, Graph>,
+}
+
+struct Matcher<'p, 's> {
+    /// Corresponding pattern and subject binders in scope, innermost last.
+    scopes: Vec<(&'p str, &'s str)>,
+    bindings: BTreeMap<String, Graph>,
+}
+
+impl<'p, 's> Matcher<'p, 's> {
+    /// Whether `p` and `s` refer to corresponding binders, or are both free
+    /// and spelled the same.
+    fn same_var(&self, p: &str, s: &str) -> bool {
+        let pattern = self.scopes.iter().rposition(|&(bound, _)| bound == p);
+        let subject = self.scopes.iter().rposition(|&(_, bound)| bound == s);
+        pattern == subject && (pattern.is_some() || p == s)
+    }
+
+    fn is_pattern_var(&self, var: &GVar) -> bool {
+        *var.graph == Graph::Nil && !self.scopes.iter().any(|&(bound, _)| bound == var.var)
+    }
+
+    fn capture(&mut self, var: &str, subject: &Graph) -> bool {
+        let free = subject.free_vars();
+        if self.scopes.iter().any(|&(_, bound)| free.contains(bound)) {
+            return false;
+        }
+
+        match self.bindings.get(var) {
+            Some(bound) => bound == subject,
+            None => {
+                self.bindings.insert(var.to_owned(), subject.clone());
+                true
+            }
+        }
+    }
+
+    fn scoped(&mut self, p: &'p str, s: &'s str, f: impl FnOnce(&mut Self) -> bool) -> bool {
+        self.scopes.push((p, s));
+        let matched = f(self);
+        self.scopes.pop();
+        matched
+    }
+
+    fn graph(&mut self, pattern: &'p Graph, subject: &'s Graph) -> bool {
+        match (pattern, subject) {
+            (Graph::Var(var), _) if self.is_pattern_var(var) => self.capture(&var.var, subject),
+            (Graph::Nil, Graph::Nil) => true,
+            (Graph::Vertex(p), Graph::Vertex(s)) => {
+                self.vertex(&p.vertex, &s.vertex) && self.graph(&p.graph, &s.graph)
+            }
+            (Graph::Var(p), Graph::Var(s)) => {
+                self.same_var(&p.var, &s.var) && self.graph(&p.graph, &s.graph)
+            }
+            (Graph::Nominate(p), Graph::Nominate(s)) => self.binding(p, s),
+            (Graph::EdgeAnon(p), Graph::EdgeAnon(s)) => {
+                self.binding(&p.binding_1, &s.binding_1) && self.binding(&p.binding_2, &s.binding_2)
+            }
+            (Graph::EdgeNamed(p), Graph::EdgeNamed(s)) => self.named(&p.name, &s.name, |m| {
+                m.binding(&p.binding_1, &s.binding_1) && m.binding(&p.binding_2, &s.binding_2)
+            }),
+            (Graph::RuleAnon(p), Graph::RuleAnon(s)) => {
+                self.graph(&p.graph_1, &s.graph_1) && self.graph(&p.graph_2, &s.graph_2)
+            }
+            (Graph::RuleNamed(p), Graph::RuleNamed(s)) => self.named(&p.name, &s.name, |m| {
+                m.graph(&p.graph_1, &s.graph_1) && m.graph(&p.graph_2, &s.graph_2)
+            }),
+            (Graph::Subgraph(p), Graph::Subgraph(s)) => {
+                self.graph(&p.graph_1, &s.graph_1)
+                    && self.scoped(&p.var, &s.var, |m| m.graph(&p.graph_2, &s.graph_2))
+            }
+            (Graph::Tensor(p), Graph::Tensor(s)) => {
+                self.graph(&p.graph_1, &s.graph_1) && self.graph(&p.graph_2, &s.graph_2)
+            }
+            (Graph::Context(p), Graph::Context(s)) => {
+                p.string == s.string
+                    && self.name(&p.name, &s.name)
+                    && self.graph(&p.graph, &s.graph)
+            }
+            _ => false,
+        }
+    }
+
+    fn binding(&mut self, p: &'p Binding, s: &'s Binding) -> bool {
+        self.vertex(&p.vertex, &s.vertex)
+            && self.scoped(&p.var, &s.var, |m| m.graph(&p.graph, &s.graph))
+    }
+
+    /// Matches the names of two edges or rules, which bind plain variables
+    /// in `body`.
+    fn named(&mut self, p: &'p Name, s: &'s Name, body: impl FnOnce(&mut Self) -> bool) -> bool {
+        match (p, s) {
+            (Name::VVar { value: p }, Name::VVar { value: s })
+            | (Name::GVar { value: p }, Name::GVar { value: s }) => self.scoped(p, s, body),
+            _ => self.name(p, s) && body(self),
+        }
+    }
+
+    fn vertex(&mut self, p: &'p Vertex, s: &'s Vertex) -> bool {
+        self.name(&p.name, &s.name)
+    }
+
+    fn name(&mut self, p: &'p Name, s: &'s Name) -> bool {
+        match (p, s) {
+            (Name::Wildcard, Name::Wildcard) => true,
+            (Name::VVar { value: p }, Name::VVar { value: s }) => p == s,
+            (Name::GVar { value: p }, Name::GVar { value: s }) => self.same_var(p, s),
+            (Name::QuoteGraph { value: p }, Name::QuoteGraph { value: s }) => self.graph(p, s),
+            (Name::QuoteVertex { value: p }, Name::QuoteVertex { value: s }) => self.vertex(p, s),
+            _ => false,
+        }
+    }
+}
+
+/// Matches `subject` against `pattern`, returning the subgraph captured by
+/// each pattern variable.
+pub(crate) fn match_graph(pattern: &Graph, subject: &Graph) -> Option<BTreeMap<String, Graph>> {
+    let mut matcher = Matcher {
+        scopes: Vec::new(),
+        bindings: BTreeMap::new(),
+    };
+    matcher.graph(pattern, subject).then_some(matcher.bindings)
+}
+
+impl Graph {
+    /// Returns every subgraph that `pattern` matches, in pre-order, with the
+    /// subgraphs its pattern variables capture there.
+    ///
+    /// Pattern variables and the matching rules are described in the
+    /// `rewrite` module; in short, each free `x | 0` of `pattern` is a
+    /// wildcard. Candidates are the subgraphs reachable by a path (see
+    /// [`children`](Self::children)), so graphs quoted in names are not
+    /// searched. Matches may overlap: a pattern that is a single pattern
+    /// variable matches every subgraph.
+    pub fn match_pattern(&self, pattern: &Graph) -> Vec<Match> {
+        self.subgraphs_with_paths()
+            .into_iter()
+            .filter_map(|(path, graph)| {
+                match_graph(pattern, graph).map(|bindings| Match { path, bindings })
+            })
+            .collect()
+    }
+
+    /// Performs one rewrite step: replaces the subgraph at `target` with the
+    /// right-hand side of `rule`, if it matches the left-hand side.
+    ///
+    /// `target` is a path as described in the `paths` module. Matching
+    /// follows the rules of the `rewrite` module, and the subgraphs captured
+    /// by pattern variables are substituted into the right-hand side with
+    /// [`subst_many`](Self::subst_many). The rule's name plays no part in
+    /// matching.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPath`] if `target` does not lead to a node and
+    /// [`Error::NoMatch`] if the left-hand side does not match it.
+    pub fn apply_rule(&self, rule: &GRuleNamed, target: &[usize]) -> Result<Graph, Error> {
+        let invalid_path = || Error::InvalidPath {
+            path: target.to_vec(),
+        };
+        let subject = self.subgraph_at(target).ok_or_else(invalid_path)?;
+
+        let Some(bindings) = match_graph(&rule.graph_1, subject) else {
+            let opts = PrintOptions::default();
+            let mut printer = Printer::new(&opts);
+            printer.name(&rule.name);
+            return Err(Error::NoMatch {
+                rule: printer.finish(),
+                path: target.to_vec(),
+            });
+        };
+
+        let mut rewritten = self.clone();
+        *rewritten.node_at_mut(target).ok_or_else(invalid_path)? =
+            rule.graph_2.subst_many(&bindings);
+        Ok(rewritten)
+    }
+
+    /// Rewrites this graph with `rules` until none of them applies.
+    ///
+    /// Each step takes the first rule, in the order given, whose left-hand
+    /// side matches anywhere in the graph, and rewrites the first match in
+    /// pre-order as [`apply_rule`](Self::apply_rule) would. Returns the final
+    /// graph and the number of steps taken; a graph no rule applies to is
+    /// returned unchanged after `0` steps.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StepLimitExceeded`] if a rule still applies after
+    /// `max_steps` steps, which is how rule systems that never terminate are
+    /// cut off.
+    pub fn rewrite_to_fixpoint(
+        &self,
+        rules: &[GRuleNamed],
+        max_steps: usize,
+    ) -> Result<(Graph, usize), Error> {
+        let mut graph = self.clone();
+
+        for steps in 0..=max_steps {
+            let step = rules.iter().find_map(|rule| {
+                graph
+                    .subgraphs_with_paths()
+                    .into_iter()
+                    .find_map(|(path, subject)| {
+                        match_graph(&rule.graph_1, subject).map(|bindings| (rule, path, bindings))
+                    })
+            });
+            let Some((rule, path, bindings)) = step else {
+                return Ok((graph, steps));
+            };
+            if steps == max_steps {
+                break;
+            }
+
+            let replacement = rule.graph_2.subst_many(&bindings);
+            *graph
+                .node_at_mut(&path)
+                .expect("the path of a match leads to a node") = replacement;
+        }
+
+        Err(Error::StepLimitExceeded { steps: max_steps })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::Match;
+    use crate::ast::{Error, GRuleNamed, Graph, THREE_EDGE_GRAPH};
+    use crate::{parse_as, parse_to_ast};
+
+    fn rule(code: &str) -> GRuleNamed {
+        parse_as(code).unwrap()
+    }
+
+    #[test]
+    fn test_apply_rule_at_root() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+
+        assert_eq!(
+            graph
+                .apply_rule(&rule("r [= <a> | 0 <b> | 0]"), &[])
+                .unwrap(),
+            parse_to_ast("<b> | 0".into()).unwrap()
+        );
+        assert!(matches!(
+            graph.apply_rule(&rule("r [= <c> | 0 <b> | 0]"), &[]),
+            Err(Error::NoMatch { rule, path }) if rule == "r" && path.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_apply_rule_substitutes_pattern_variables() {
+        let graph = parse_to_ast("<c> | 0 * <a> | <d> | 0".into()).unwrap();
+
+        assert_eq!(
+            graph
+                .apply_rule(&rule("r [= <a> | x | 0 <b> | x | 0]"), &[1])
+                .unwrap(),
+            parse_to_ast("<c> | 0 * <b> | <d> | 0".into()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_apply_rule_matches_binders_up_to_renaming() {
+        let graph = parse_to_ast("let y = <a> in y | 0".into()).unwrap();
+        let bound = rule("r [= let x = <a> in x | 0 <b> | 0]");
+        let captures = rule("r [= let x = <a> in g | 0 g | 0]");
+
+        assert_eq!(
+            graph.apply_rule(&bound, &[]).unwrap(),
+            parse_to_ast("<b> | 0".into()).unwrap()
+        );
+        assert!(matches!(
+            graph.apply_rule(&captures, &[]),
+            Err(Error::NoMatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_match_pattern_in_three_edge_sample() {
+        let graph = parse_to_ast(THREE_EDGE_GRAPH.into()).unwrap();
+        let pattern =
+            parse_to_ast("(let a = <encryption> in x | 0, let b = <store> in <store> | 0)".into())
+                .unwrap();
+
+        assert_eq!(
+            graph.match_pattern(&pattern),
+            [Match {
+                path: vec![0, 0],
+                bindings: BTreeMap::from([(
+                    "x".to_owned(),
+                    parse_to_ast("<encryption> | 0".into()).unwrap()
+                )]),
+            }]
+        );
+
+        let pattern = parse_to_ast("<encryption> | x | 0".into()).unwrap();
+        assert_eq!(
+            graph.match_pattern(&pattern),
+            [Match {
+                path: vec![0, 0, 0],
+                bindings: BTreeMap::from([("x".to_owned(), Graph::Nil)]),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_to_fixpoint() {
+        let graph = parse_to_ast("<a> | 0 * <b> | 0".into()).unwrap();
+        let rules = [rule("a [= <a> | 0 <b> | 0]"), rule("b [= <b> | 0 <c> | 0]")];
+
+        let (rewritten, steps) = graph.rewrite_to_fixpoint(&rules, 10).unwrap();
+
+        assert_eq!(rewritten, parse_to_ast("<c> | 0 * <c> | 0".into()).unwrap());
+        assert_eq!(steps, 3);
+        assert_eq!(
+            rewritten.rewrite_to_fixpoint(&rules, 0).unwrap(),
+            (rewritten, 0)
+        );
+    }
+
+    #[test]
+    fn test_rewrite_to_fixpoint_stops_looping_rules() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+        let rules = [rule("a [= <a> | 0 <b> | 0]"), rule("b [= <b> | 0 <a> | 0]")];
+
+        assert!(matches!(
+            graph.rewrite_to_fixpoint(&rules, 10),
+            Err(Error::StepLimitExceeded { steps: 10 })
+        ));
+    }
+}
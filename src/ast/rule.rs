@@ -0,0 +1,81 @@
+//! A uniform view of anonymous and named rules.
+
+use crate::ast::{GRuleAnon, GRuleNamed, Graph, Name};
+
+/// The two sides of a rule `[= lhs rhs]`, with the rule's name if it has
+/// one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rewrite<'a> {
+    /// The variable naming the rule. Rules named by a wildcard or a quoted
+    /// graph or vertex have no textual name and report `None`, like
+    /// anonymous ones.
+    pub name: Option<&'a str>,
+    /// The pattern the rule matches, its first graph (`graph_1`).
+    pub lhs: &'a Graph,
+    /// What a match is replaced with, the rule's second graph (`graph_2`).
+    pub rhs: &'a Graph,
+}
+
+impl GRuleAnon {
+    /// Returns this rule as a [`Rewrite`] without a name.
+    pub fn as_rewrite(&self) -> Rewrite<'_> {
+        Rewrite {
+            name: None,
+            lhs: &self.graph_1,
+            rhs: &self.graph_2,
+        }
+    }
+}
+
+impl GRuleNamed {
+    /// Returns this rule as a [`Rewrite`].
+    pub fn as_rewrite(&self) -> Rewrite<'_> {
+        let name = match &self.name {
+            Name::VVar { value } | Name::GVar { value } => Some(value.as_str()),
+            Name::Wildcard | Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => None,
+        };
+
+        Rewrite {
+            name,
+            lhs: &self.graph_1,
+            rhs: &self.graph_2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Rewrite;
+    use crate::ast::Graph;
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_as_rewrite() {
+        let lhs = parse_to_ast("<a> | 0".into()).unwrap();
+        let rhs = parse_to_ast("<b> | 0".into()).unwrap();
+
+        let Graph::RuleAnon(anon) = parse_to_ast("[= <a> | 0 <b> | 0]".into()).unwrap() else {
+            panic!("expected an anonymous rule");
+        };
+        assert_eq!(
+            anon.as_rewrite(),
+            Rewrite {
+                name: None,
+                lhs: &lhs,
+                rhs: &rhs,
+            }
+        );
+
+        let Graph::RuleNamed(named) = parse_to_ast("r [= <a> | 0 <b> | 0]".into()).unwrap() else {
+            panic!("expected a named rule");
+        };
+        assert_eq!(
+            named.as_rewrite(),
+            Rewrite {
+                name: Some("r"),
+                lhs: &lhs,
+                rhs: &rhs,
+            }
+        );
+    }
+}
@@ -0,0 +1,626 @@
+//! Variable scoping over the AST.
+//!
+//! GraphL scopes are lexical:
+//!
+//! * `let x = <v> in g` ([`Binding`]) binds `x` in its continuation `g`;
+//! * `let X = g1 in g2` ([`GraphBinding`]) binds `X` in `g2` only;
+//! * a named edge or rule whose name is a plain variable binds that name in
+//!   both of its bindings or graphs.
+//!
+//! A reference is either a variable in process position (`x | g`) or a graph
+//! variable used as a vertex name (`<X> | g`). Quoted graphs and vertices are
+//! searched for references as well.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::{Binding, GContext, Graph, GraphBinding, Name};
+
+/// How the free variables of two graphs compare, see [`Graph::var_diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VarDiff {
+    /// Free in the receiver only.
+    pub only_in_self: BTreeSet<String>,
+    /// Free in the other graph only.
+    pub only_in_other: BTreeSet<String>,
+    /// Free in both.
+    pub common: BTreeSet<String>,
+}
+
+/// An edge whose two bindings introduce the same variable, reported by
+/// [`Graph::check_edge_binding_conflicts`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("both bindings of the edge at {path:?} bind `{var}`")]
+pub struct EdgeConflict {
+    /// The path of the edge, see the `paths` module.
+    pub path: Vec<usize>,
+    /// The variable bound twice.
+    pub var: String,
+}
+
+/// A scoping event reported by [`walk_scopes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScopeEvent<'a> {
+    /// A binder of the given kind for the given variable is entered.
+    Bind(&'a str, Binder),
+    /// The given variable is referenced.
+    Reference(&'a str),
+    /// A vertex with the given name is found. A graph variable used as a
+    /// vertex name is reported as a reference right after.
+    Vertex(&'a str),
+    /// A context is entered.
+    Context(&'a GContext),
+}
+
+/// What introduced a variable reported by [`ScopeEvent::Bind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Binder {
+    /// A `let x = <v> in g` or `let X = g1 in g2` binding.
+    Let,
+    /// The name of a named edge or rule.
+    Name,
+}
+
+enum Step<'a> {
+    Graph(&'a Graph),
+    Binding(&'a Binding),
+    Name(&'a Name),
+    Enter(&'a str, Binder),
+    Leave,
+    /// The quoted name of a context follows, see [`walk_scopes`].
+    Hide,
+    Show,
+}
+
+/// Walks `graph` depth-first, left to right, reporting every binder and
+/// reference together with the stack of variables in scope at that point
+/// (outermost first, not including the binder being reported).
+///
+/// Vertices and contexts are reported as well. Quoted names of contexts are
+/// searched for vertices only: binders and references inside them are not
+/// reported and leave the scopes alone.
+pub(crate) fn walk_scopes<'a>(graph: &'a Graph, mut f: impl FnMut(ScopeEvent<'a>, &[&'a str])) {
+    let mut scopes: Vec<&'a str> = Vec::new();
+    let mut stack = vec![Step::Graph(graph)];
+    // How many context names enclose the current step.
+    let mut hidden = 0;
+
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Graph(graph) => match graph {
+                Graph::Nil => {}
+                Graph::Vertex(vertex) => {
+                    stack.push(Step::Graph(&vertex.graph));
+                    stack.push(Step::Name(&vertex.vertex.name));
+                }
+                Graph::Var(var) => {
+                    if hidden == 0 {
+                        f(ScopeEvent::Reference(&var.var), &scopes);
+                    }
+                    stack.push(Step::Graph(&var.graph));
+                }
+                Graph::Nominate(binding) => stack.push(Step::Binding(binding)),
+                Graph::EdgeAnon(edge) => {
+                    stack.push(Step::Binding(&edge.binding_2));
+                    stack.push(Step::Binding(&edge.binding_1));
+                }
+                Graph::EdgeNamed(edge) => {
+                    let bound = push_named_scope(&mut stack, &edge.name);
+                    stack.push(Step::Binding(&edge.binding_2));
+                    stack.push(Step::Binding(&edge.binding_1));
+                    if let Some(var) = bound {
+                        stack.push(Step::Enter(var, Binder::Name));
+                    }
+                }
+                Graph::RuleAnon(rule) => {
+                    stack.push(Step::Graph(&rule.graph_2));
+                    stack.push(Step::Graph(&rule.graph_1));
+                }
+                Graph::RuleNamed(rule) => {
+                    let bound = push_named_scope(&mut stack, &rule.name);
+                    stack.push(Step::Graph(&rule.graph_2));
+                    stack.push(Step::Graph(&rule.graph_1));
+                    if let Some(var) = bound {
+                        stack.push(Step::Enter(var, Binder::Name));
+                    }
+                }
+                Graph::Subgraph(GraphBinding {
+                    graph_1,
+                    graph_2,
+                    var,
+                }) => {
+                    stack.push(Step::Leave);
+                    stack.push(Step::Graph(graph_2));
+                    stack.push(Step::Enter(var, Binder::Let));
+                    stack.push(Step::Graph(graph_1));
+                }
+                Graph::Tensor(tensor) => {
+                    stack.push(Step::Graph(&tensor.graph_2));
+                    stack.push(Step::Graph(&tensor.graph_1));
+                }
+                Graph::Context(context) => {
+                    f(ScopeEvent::Context(context), &scopes);
+                    stack.push(Step::Graph(&context.graph));
+                    if let Name::QuoteGraph { .. } | Name::QuoteVertex { .. } = &context.name {
+                        stack.push(Step::Show);
+                        stack.push(Step::Name(&context.name));
+                        stack.push(Step::Hide);
+                    }
+                }
+            },
+            Step::Binding(binding) => {
+                stack.push(Step::Leave);
+                stack.push(Step::Graph(&binding.graph));
+                stack.push(Step::Enter(&binding.var, Binder::Let));
+                stack.push(Step::Name(&binding.vertex.name));
+            }
+            Step::Name(name) => match name {
+                Name::Wildcard => {}
+                Name::VVar { value } => f(ScopeEvent::Vertex(value), &scopes),
+                Name::GVar { value } => {
+                    f(ScopeEvent::Vertex(value), &scopes);
+                    if hidden == 0 {
+                        f(ScopeEvent::Reference(value), &scopes);
+                    }
+                }
+                Name::QuoteGraph { value } => stack.push(Step::Graph(value)),
+                Name::QuoteVertex { value } => stack.push(Step::Name(&value.name)),
+            },
+            // An `Enter` and its `Leave` are inside the same context names.
+            Step::Enter(var, binder) if hidden == 0 => {
+                f(ScopeEvent::Bind(var, binder), &scopes);
+                scopes.push(var);
+            }
+            Step::Leave if hidden == 0 => {
+                scopes.pop();
+            }
+            Step::Enter(..) | Step::Leave => {}
+            Step::Hide => hidden += 1,
+            Step::Show => hidden -= 1,
+        }
+    }
+}
+
+/// Pushes the scope exit for a named edge or rule and returns the variable it
+/// binds, if any. Quoted names bind nothing but are searched for references.
+fn push_named_scope<'a>(stack: &mut Vec<Step<'a>>, name: &'a Name) -> Option<&'a str> {
+    match name {
+        Name::VVar { value } | Name::GVar { value } => {
+            stack.push(Step::Leave);
+            Some(value)
+        }
+        Name::Wildcard => None,
+        Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => {
+            stack.push(Step::Name(name));
+            None
+        }
+    }
+}
+
+/// Returns every binder of `graph`, in order, with the number of references
+/// resolving to it. Names of edges and rules are resolved but not returned.
+fn let_usages(graph: &Graph) -> Vec<(&str, usize)> {
+    let mut binders: Vec<(&str, Binder, usize)> = Vec::new();
+    // Index into `binders` of each variable currently in scope.
+    let mut in_scope: Vec<usize> = Vec::new();
+
+    walk_scopes(graph, |event, scopes| {
+        in_scope.truncate(scopes.len());
+        match event {
+            ScopeEvent::Bind(var, binder) => {
+                in_scope.push(binders.len());
+                binders.push((var, binder, 0));
+            }
+            ScopeEvent::Reference(var) => {
+                if let Some(position) = scopes.iter().rposition(|&bound| bound == var) {
+                    binders[in_scope[position]].2 += 1;
+                }
+            }
+            ScopeEvent::Vertex(_) | ScopeEvent::Context(_) => {}
+        }
+    });
+
+    binders
+        .into_iter()
+        .filter(|&(_, binder, _)| binder == Binder::Let)
+        .map(|(var, _, uses)| (var, uses))
+        .collect()
+}
+
+impl Graph {
+    /// Returns the variables referenced outside the scope of any binder, in
+    /// ascending (lexicographic) order.
+    ///
+    /// Use [`free_vars_in_order`](Self::free_vars_in_order) to get them in
+    /// the order they first appear in the source instead.
+    pub fn free_vars(&self) -> BTreeSet<String> {
+        self.free_vars_in_order().into_iter().collect()
+    }
+
+    /// Compares the [`free_vars`](Self::free_vars) of this graph with those
+    /// of `other`.
+    ///
+    /// A transformation that keeps the interface of a graph gives an empty
+    /// `only_in_self` and `only_in_other`.
+    pub fn var_diff(&self, other: &Graph) -> VarDiff {
+        let mine = self.free_vars();
+        let theirs = other.free_vars();
+
+        VarDiff {
+            only_in_self: mine.difference(&theirs).cloned().collect(),
+            only_in_other: theirs.difference(&mine).cloned().collect(),
+            common: mine.intersection(&theirs).cloned().collect(),
+        }
+    }
+
+    /// Checks that the two bindings of every edge bind different variables.
+    ///
+    /// In `(let a = <x> in g1, let a = <y> in g2)` the two vertices share a
+    /// name that a reference in either branch cannot tell apart, which is
+    /// almost always a mistake. Only edges reachable by a path are checked,
+    /// so those in quoted names are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns every conflicting edge, in pre-order.
+    pub fn check_edge_binding_conflicts(&self) -> Result<(), Vec<EdgeConflict>> {
+        let conflicts: Vec<_> = self
+            .subgraphs_with_paths()
+            .into_iter()
+            .filter_map(|(path, graph)| {
+                let (binding_1, binding_2) = match graph {
+                    Graph::EdgeAnon(edge) => (&edge.binding_1, &edge.binding_2),
+                    Graph::EdgeNamed(edge) => (&edge.binding_1, &edge.binding_2),
+                    _ => return None,
+                };
+                (binding_1.var == binding_2.var).then(|| EdgeConflict {
+                    path,
+                    var: binding_1.var.clone(),
+                })
+            })
+            .collect();
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(conflicts)
+        }
+    }
+
+    /// Returns the same variables as [`free_vars`](Self::free_vars), each once,
+    /// in the order of its first free occurrence during a depth-first,
+    /// left-to-right traversal.
+    pub fn free_vars_in_order(&self) -> Vec<String> {
+        let mut seen = BTreeSet::new();
+        let mut free = Vec::new();
+
+        walk_scopes(self, |event, scopes| {
+            if let ScopeEvent::Reference(var) = event
+                && !scopes.contains(&var)
+                && seen.insert(var)
+            {
+                free.push(var.to_owned());
+            }
+        });
+
+        free
+    }
+
+    /// Returns whether every reference is bound, i.e. the graph has no
+    /// [`free_vars`](Self::free_vars) and can be used on its own.
+    ///
+    /// Unlike `free_vars().is_empty()`, this collects nothing.
+    pub fn is_ground(&self) -> bool {
+        let mut ground = true;
+        walk_scopes(self, |event, scopes| {
+            if let ScopeEvent::Reference(var) = event
+                && !scopes.contains(&var)
+            {
+                ground = false;
+            }
+        });
+        ground
+    }
+
+    /// Returns the binding-dependency graph: for every bound variable, the
+    /// bound variables referenced from within its scope.
+    ///
+    /// A reference belongs to the innermost enclosing binder and depends on
+    /// the binder it resolves to, so `let a = <a> in { let b = <b> in a | 0 }`
+    /// records `b -> a`. References are resolved lexically, like in
+    /// [`free_vars`](Self::free_vars): a binder in a sibling branch, such as
+    /// the other binding of an edge, is not in scope, and references that
+    /// resolve to no binder are dropped. Dependencies are keyed by name, so
+    /// shadowing bindings share an entry.
+    pub fn binding_dependencies(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut dependencies: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+        walk_scopes(self, |event, scopes| match event {
+            ScopeEvent::Bind(var, _) => {
+                dependencies.entry(var.to_owned()).or_default();
+            }
+            ScopeEvent::Reference(var) => {
+                if let Some(owner) = scopes.last()
+                    && scopes.contains(&var)
+                {
+                    dependencies
+                        .entry((*owner).to_owned())
+                        .or_default()
+                        .insert(var.to_owned());
+                }
+            }
+            ScopeEvent::Vertex(_) | ScopeEvent::Context(_) => {}
+        });
+
+        dependencies
+    }
+
+    /// Returns, for every variable bound by a `let`, how many references
+    /// resolve to it.
+    ///
+    /// A reference resolves to the innermost binder of its name, which may also
+    /// be the name of an enclosing named edge or rule; such references are not
+    /// counted. Unused bindings are reported with a count of 0, and the counts
+    /// of several bindings of the same name are added up.
+    pub fn usage_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for (var, uses) in let_usages(self) {
+            *counts.entry(var.to_owned()).or_default() += uses;
+        }
+        counts
+    }
+
+    /// Returns whether every `let` binding is referenced exactly once, so
+    /// that no bound vertex or graph is duplicated or discarded.
+    ///
+    /// Shadowing bindings are checked separately, unlike in
+    /// [`usage_counts`](Self::usage_counts).
+    pub fn is_linear(&self) -> bool {
+        let_usages(self).into_iter().all(|(_, uses)| uses == 1)
+    }
+
+    /// Returns the number of variables on the longest chain of
+    /// [`binding_dependencies`](Self::binding_dependencies), i.e. the
+    /// sequential depth of the bindings.
+    ///
+    /// A graph without bindings has length 0 and a binding that references
+    /// nothing has length 1. Cycles are cut at the first repeated variable.
+    pub fn reference_chain_length(&self) -> usize {
+        let dependencies = self.binding_dependencies();
+        let adjacency: BTreeMap<&str, Vec<&str>> = dependencies
+            .iter()
+            .map(|(var, deps)| (var.as_str(), deps.iter().map(String::as_str).collect()))
+            .collect();
+        let mut lengths: BTreeMap<&str, usize> = BTreeMap::new();
+
+        for &root in adjacency.keys() {
+            if lengths.contains_key(root) {
+                continue;
+            }
+
+            let mut on_path = BTreeSet::from([root]);
+            let mut path = vec![(root, 0)];
+
+            while let Some((var, next)) = path.last_mut() {
+                let var = *var;
+
+                if let Some(&dep) = adjacency[var].get(*next) {
+                    *next += 1;
+                    if !lengths.contains_key(dep) && on_path.insert(dep) {
+                        path.push((dep, 0));
+                    }
+                    continue;
+                }
+
+                let longest = adjacency[var]
+                    .iter()
+                    .filter_map(|dep| lengths.get(dep))
+                    .max()
+                    .copied()
+                    .unwrap_or(0);
+                lengths.insert(var, longest + 1);
+                on_path.remove(var);
+                path.pop();
+            }
+        }
+
+        lengths.into_values().max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeMap, BTreeSet};
+
+    use super::{EdgeConflict, VarDiff};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_check_edge_binding_conflicts() {
+        let graph = parse_to_ast(
+            "{ (let a = <x> in 0, let b = <y> in 0) * <c> | e(let a = <x> in 0, let a = <y> in 0) }"
+                .into(),
+        )
+        .unwrap();
+        let errors = graph.check_edge_binding_conflicts().unwrap_err();
+
+        assert_eq!(
+            errors,
+            [EdgeConflict {
+                path: vec![1, 0],
+                var: "a".into(),
+            }]
+        );
+        assert_eq!(
+            errors[0].to_string(),
+            "both bindings of the edge at [1, 0] bind `a`"
+        );
+
+        let distinct = parse_to_ast("(let a = <x> in 0, let b = <x> in 0)".into()).unwrap();
+        assert_eq!(distinct.check_edge_binding_conflicts(), Ok(()));
+    }
+
+    #[test]
+    fn test_var_diff_after_renaming_free_var() {
+        let graph = parse_to_ast("let a = <a> in { a | 0 * x | 0 * y | 0 }".into()).unwrap();
+        let renamed = parse_to_ast("let a = <a> in { a | 0 * z | 0 * y | 0 }".into()).unwrap();
+
+        assert_eq!(
+            graph.var_diff(&renamed),
+            VarDiff {
+                only_in_self: BTreeSet::from(["x".into()]),
+                only_in_other: BTreeSet::from(["z".into()]),
+                common: BTreeSet::from(["y".into()]),
+            }
+        );
+        assert_eq!(
+            graph.var_diff(&graph.canonical()),
+            VarDiff {
+                common: graph.free_vars(),
+                ..VarDiff::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_free_vars_orderings() {
+        let graph = parse_to_ast("{ b | 0 * let c = <c> in { c | a | b | 0 } }".into()).unwrap();
+
+        assert_eq!(graph.free_vars_in_order(), ["b", "a"]);
+        assert_eq!(
+            graph.free_vars().into_iter().collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_free_vars_respect_scopes_and_shadowing() {
+        let free = |code: &str| parse_to_ast(code.into()).unwrap().free_vars();
+
+        // `e1` is bound in the left branch only, so its use on the right
+        // escapes.
+        assert_eq!(
+            free("(let e1 = <encryption> in e1 | 0, let e3 = <encryption> in e1 | 0)"),
+            BTreeSet::from(["e1".to_owned()])
+        );
+        assert_eq!(
+            free("let e1 = <a> in { e1 | 0 * (let e1 = <b> in e1 | 0, let s = <s> in 0) }"),
+            BTreeSet::new()
+        );
+        assert_eq!(
+            free(
+                "e1(let a = <a> in e1 | 0, let b = <b> in 0) * { let G = <a> | 0 in <G> | <H> | e1 | 0 }"
+            ),
+            BTreeSet::from(["H".to_owned(), "e1".to_owned()])
+        );
+        assert_eq!(
+            free("E1 [= <E1> | e1 | 0 0]"),
+            BTreeSet::from(["e1".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_binding_dependencies() {
+        let graph = parse_to_ast(
+            "let c = <c> in { let b = <b> in { c | 0 * let a = <a> in b | x | 0 } }".into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            graph.binding_dependencies(),
+            BTreeMap::from([
+                ("a".to_owned(), BTreeSet::from(["b".to_owned()])),
+                ("b".to_owned(), BTreeSet::from(["c".to_owned()])),
+                ("c".to_owned(), BTreeSet::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_binding_dependencies_ignore_sibling_branches() {
+        let graph = parse_to_ast(
+            "{ (let a = <a> in b | 0, let b = <b> in c | 0) * let c = <c> in a | 0 }".into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            graph.binding_dependencies(),
+            BTreeMap::from([
+                ("a".to_owned(), BTreeSet::new()),
+                ("b".to_owned(), BTreeSet::new()),
+                ("c".to_owned(), BTreeSet::new()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_is_ground() {
+        let bound = parse_to_ast("let a = <a> in a | 0".into()).unwrap();
+        let free = parse_to_ast("e1 | 0".into()).unwrap();
+
+        assert!(bound.is_ground());
+        assert!(!free.is_ground());
+        assert!(
+            !parse_to_ast("<a> | 0 * let b = <b> in e1 | 0".into())
+                .unwrap()
+                .is_ground()
+        );
+    }
+
+    #[test]
+    fn test_linearity() {
+        let twice = parse_to_ast("let a = <a> in a | a | 0".into()).unwrap();
+        let once = parse_to_ast("let a = <a> in a | 0".into()).unwrap();
+
+        assert!(!twice.is_linear());
+        assert_eq!(twice.usage_counts(), BTreeMap::from([("a".to_owned(), 2)]));
+        assert!(once.is_linear());
+        assert_eq!(once.usage_counts(), BTreeMap::from([("a".to_owned(), 1)]));
+    }
+
+    #[test]
+    fn test_usage_counts_resolve_shadowing() {
+        let graph = parse_to_ast(
+            "let a = <a> in { a (let b = <b> in a | 0, let a = <c> in a | b | 0) }".into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            graph.usage_counts(),
+            BTreeMap::from([("a".to_owned(), 1), ("b".to_owned(), 0)])
+        );
+        assert!(!graph.is_linear());
+    }
+
+    #[test]
+    fn test_three_deep_reference_chain() {
+        let graph = parse_to_ast(
+            "let c = <c> in { let b = <b> in { c | 0 * let a = <a> in b | 0 } }".into(),
+        )
+        .unwrap();
+
+        assert_eq!(graph.reference_chain_length(), 3);
+
+        let diamond = parse_to_ast(
+            "let b = <b> in { let c = <c> in { b | 0 * let a = <a> in b | c | 0 } }".into(),
+        )
+        .unwrap();
+        assert_eq!(diamond.reference_chain_length(), 3);
+    }
+
+    #[test]
+    fn test_reference_chain_without_bindings_and_with_cycle() {
+        assert_eq!(
+            parse_to_ast("<a> | 0".into())
+                .unwrap()
+                .reference_chain_length(),
+            0
+        );
+
+        // The inner `a` shadows the outer one, which `b` depends on.
+        let cyclic = parse_to_ast(
+            "let a = <a> in { let b = <b> in { a | 0 * let a = <v> in b | 0 } }".into(),
+        )
+        .unwrap();
+        assert_eq!(cyclic.reference_chain_length(), 2);
+    }
+}
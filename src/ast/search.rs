@@ -0,0 +1,80 @@
+//! Searching for nodes.
+
+use crate::ast::Graph;
+
+impl Graph {
+    /// Returns the first node, in depth-first pre-order over
+    /// [`children`](Self::children), for which `pred` holds.
+    ///
+    /// The search stops as soon as a match is found: `pred` is called at
+    /// most once per node, and never on nodes after the match in pre-order,
+    /// including the match's own descendants.
+    pub fn walk_until(&self, pred: impl Fn(&Graph) -> bool) -> Option<&Graph> {
+        let mut stack = vec![self];
+
+        while let Some(graph) = stack.pop() {
+            if pred(graph) {
+                return Some(graph);
+            }
+            stack.extend(graph.children().into_iter().rev());
+        }
+
+        None
+    }
+
+    /// Like [`walk_until`](Self::walk_until), but returns the match mutably
+    /// so it can be edited in place.
+    pub fn walk_until_mut(&mut self, pred: impl Fn(&Graph) -> bool) -> Option<&mut Graph> {
+        let mut stack = vec![self];
+
+        while let Some(graph) = stack.pop() {
+            if pred(graph) {
+                return Some(graph);
+            }
+            stack.extend(graph.children_mut().into_iter().rev());
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+
+    use crate::ast::{Graph, NodeKind};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_walk_until_stops_at_first_tensor() {
+        let graph = parse_to_ast(
+            "(let a = <a> in { <b> | 0 * <c> | 0 }, let d = <d> in { <e> | 0 * <f> | 0 })".into(),
+        )
+        .unwrap();
+        let visited = RefCell::new(Vec::new());
+
+        let found = graph.walk_until(|graph| {
+            visited.borrow_mut().push(graph.kind());
+            matches!(graph, Graph::Tensor(_))
+        });
+
+        assert_eq!(found, Some(graph.children()[0]));
+        assert_eq!(*visited.borrow(), [NodeKind::EdgeAnon, NodeKind::Tensor]);
+    }
+
+    #[test]
+    fn test_walk_until_mut_edits_match() {
+        let mut graph = parse_to_ast("<a> | x | 0".into()).unwrap();
+
+        *graph
+            .walk_until_mut(|graph| matches!(graph, Graph::Var(_)))
+            .unwrap() = Graph::Nil;
+
+        assert_eq!(graph, parse_to_ast("<a> | 0".into()).unwrap());
+        assert!(
+            graph
+                .walk_until(|graph| matches!(graph, Graph::Var(_)))
+                .is_none()
+        );
+    }
+}
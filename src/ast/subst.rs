@@ -0,0 +1,365 @@
+//! Capture-avoiding substitution of graphs for variables, and renaming
+//! binders apart.
+//!
+//! Binders and references follow the scoping rules described in the `scope`
+//! module. A variable in process position, `x | g`, stands for a graph that
+//! runs alongside its continuation, so substituting `h` for `x` yields
+//! `h * g`, or just `h` when `g` is `0`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::ast::node::Node;
+use crate::ast::{
+    Binding,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    Vertex,
+};
+
+/// The substitutions and renamings in effect at some point of the graph.
+#[derive(Clone)]
+struct Env<'s> {
+    subs: BTreeMap<&'s str, &'s Graph>,
+    renames: BTreeMap<String, String>,
+}
+
+struct Subst<'s> {
+    /// The free variables of each replacement.
+    free: BTreeMap<&'s str, BTreeSet<String>>,
+    /// Every name used so far, which fresh names must differ from.
+    taken: BTreeSet<String>,
+    /// When every binder is to get a distinct name, the names no further
+    /// binder may keep: the free variables and the binders seen so far.
+    distinct: Option<BTreeSet<String>>,
+}
+
+impl<'s> Subst<'s> {
+    /// Enters the scope of a binder for `var`, returning the (possibly
+    /// renamed) variable and the environment of the scope.
+    fn enter(&mut self, var: &str, env: &Env<'s>) -> (String, Env<'s>) {
+        let mut env = env.clone();
+        env.subs.remove(var);
+        env.renames.remove(var);
+
+        let captures = env.subs.keys().any(|key| self.free[key].contains(var));
+        let repeated = self
+            .distinct
+            .as_mut()
+            .is_some_and(|seen| !seen.insert(var.to_owned()));
+        if !captures && !repeated {
+            return (var.to_owned(), env);
+        }
+
+        let mut fresh = format!("{var}'");
+        while self.taken.contains(&fresh) {
+            fresh.push('\'');
+        }
+        self.taken.insert(fresh.clone());
+        env.renames.insert(var.to_owned(), fresh.clone());
+        (fresh, env)
+    }
+
+    fn graph(&mut self, graph: &Graph, env: &Env<'s>) -> Graph {
+        match graph {
+            Graph::Nil => Graph::Nil,
+            Graph::Vertex(vertex) => Graph::Vertex(GVertex {
+                vertex: self.vertex(&vertex.vertex, env),
+                graph: Box::new(self.graph(&vertex.graph, env)),
+            }),
+            Graph::Var(var) => {
+                let rest = self.graph(&var.graph, env);
+                if let Some(renamed) = env.renames.get(&var.var) {
+                    return Graph::Var(GVar {
+                        graph: Box::new(rest),
+                        var: renamed.clone(),
+                    });
+                }
+                match env.subs.get(var.var.as_str()) {
+                    Some(&replacement) if rest == Graph::Nil => replacement.clone(),
+                    Some(&replacement) => Graph::Tensor(GTensor {
+                        graph_1: Box::new(replacement.clone()),
+                        graph_2: Box::new(rest),
+                    }),
+                    None => Graph::Var(GVar {
+                        graph: Box::new(rest),
+                        var: var.var.clone(),
+                    }),
+                }
+            }
+            Graph::Nominate(binding) => Graph::Nominate(self.binding(binding, env)),
+            Graph::EdgeAnon(edge) => Graph::EdgeAnon(GEdgeAnon {
+                binding_1: self.binding(&edge.binding_1, env),
+                binding_2: self.binding(&edge.binding_2, env),
+            }),
+            Graph::EdgeNamed(edge) => {
+                let (name, env) = self.named(&edge.name, env);
+                Graph::EdgeNamed(GEdgeNamed {
+                    name,
+                    binding_1: self.binding(&edge.binding_1, &env),
+                    binding_2: self.binding(&edge.binding_2, &env),
+                })
+            }
+            Graph::RuleAnon(rule) => Graph::RuleAnon(GRuleAnon {
+                graph_1: Box::new(self.graph(&rule.graph_1, env)),
+                graph_2: Box::new(self.graph(&rule.graph_2, env)),
+            }),
+            Graph::RuleNamed(rule) => {
+                let (name, env) = self.named(&rule.name, env);
+                Graph::RuleNamed(GRuleNamed {
+                    name,
+                    graph_1: Box::new(self.graph(&rule.graph_1, &env)),
+                    graph_2: Box::new(self.graph(&rule.graph_2, &env)),
+                })
+            }
+            Graph::Subgraph(binding) => {
+                let graph_1 = Box::new(self.graph(&binding.graph_1, env));
+                let (var, env) = self.enter(&binding.var, env);
+                Graph::Subgraph(GraphBinding {
+                    graph_1,
+                    graph_2: Box::new(self.graph(&binding.graph_2, &env)),
+                    var,
+                })
+            }
+            Graph::Tensor(tensor) => Graph::Tensor(GTensor {
+                graph_1: Box::new(self.graph(&tensor.graph_1, env)),
+                graph_2: Box::new(self.graph(&tensor.graph_2, env)),
+            }),
+            Graph::Context(context) => Graph::Context(GContext {
+                graph: Box::new(self.graph(&context.graph, env)),
+                name: context.name.clone(),
+                string: context.string.clone(),
+            }),
+        }
+    }
+
+    fn binding(&mut self, binding: &Binding, env: &Env<'s>) -> Binding {
+        let vertex = self.vertex(&binding.vertex, env);
+        let (var, env) = self.enter(&binding.var, env);
+        Binding {
+            graph: Box::new(self.graph(&binding.graph, &env)),
+            var,
+            vertex,
+        }
+    }
+
+    /// Handles the name of an edge or rule, which binds plain variables and
+    /// is searched for references when quoted.
+    fn named(&mut self, name: &Name, env: &Env<'s>) -> (Name, Env<'s>) {
+        match name {
+            Name::VVar { value } => {
+                let (value, env) = self.enter(value, env);
+                (Name::VVar { value }, env)
+            }
+            Name::GVar { value } => {
+                let (value, env) = self.enter(value, env);
+                (Name::GVar { value }, env)
+            }
+            Name::Wildcard | Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => {
+                (self.name(name, env), env.clone())
+            }
+        }
+    }
+
+    fn vertex(&mut self, vertex: &Vertex, env: &Env<'s>) -> Vertex {
+        Vertex {
+            name: self.name(&vertex.name, env),
+        }
+    }
+
+    fn name(&mut self, name: &Name, env: &Env<'s>) -> Name {
+        match name {
+            Name::GVar { value } => Name::GVar {
+                value: env.renames.get(value).unwrap_or(value).clone(),
+            },
+            Name::QuoteGraph { value } => Name::QuoteGraph {
+                value: Box::new(self.graph(value, env)),
+            },
+            Name::QuoteVertex { value } => Name::QuoteVertex {
+                value: Box::new(self.vertex(value, env)),
+            },
+            Name::Wildcard | Name::VVar { .. } => name.clone(),
+        }
+    }
+}
+
+/// Every variable and name spelled anywhere in `graph`.
+fn spellings(graph: &Graph) -> impl Iterator<Item = String> {
+    Node::Graph(graph).preorder().filter_map(|node| match node {
+        Node::Graph(Graph::Var(var)) => Some(var.var.clone()),
+        Node::Graph(Graph::Subgraph(binding)) => Some(binding.var.clone()),
+        Node::Binding(binding) => Some(binding.var.clone()),
+        Node::Name(Name::VVar { value } | Name::GVar { value }) => Some(value.clone()),
+        _ => None,
+    })
+}
+
+impl Graph {
+    /// Simultaneously replaces every free occurrence of each variable in
+    /// `subs` with its graph.
+    ///
+    /// All replacements are made against the original graph, so the
+    /// replacements themselves are never substituted into: swapping `a` and
+    /// `b` works. Occurrences shadowed by an inner binder are left alone, and
+    /// a binder that would capture a free variable of a replacement is
+    /// renamed by appending primes (`x'`) to it and its references.
+    ///
+    /// Only variables in process position are substituted; graph variables
+    /// used as vertex names (`<X>`) are not.
+    pub fn subst_many(&self, subs: &BTreeMap<String, Graph>) -> Graph {
+        let free = subs
+            .iter()
+            .map(|(var, replacement)| (var.as_str(), replacement.free_vars()))
+            .collect();
+        let taken = spellings(self)
+            .chain(subs.values().flat_map(spellings))
+            .collect();
+        let env = Env {
+            subs: subs
+                .iter()
+                .map(|(var, graph)| (var.as_str(), graph))
+                .collect(),
+            renames: BTreeMap::new(),
+        };
+
+        Subst {
+            free,
+            taken,
+            distinct: None,
+        }
+        .graph(self, &env)
+    }
+
+    /// Replaces every free occurrence of the variable `var` with
+    /// `replacement`, renaming binders that would capture its free variables.
+    ///
+    /// This is [`subst_many`](Self::subst_many) with a single substitution.
+    pub fn substitute(&self, var: &str, replacement: &Graph) -> Graph {
+        self.subst_many(&BTreeMap::from([(var.to_owned(), replacement.clone())]))
+    }
+
+    /// Renames binders so that no two bind the same name, removing all
+    /// shadowing.
+    ///
+    /// The first binder of each name keeps it unless a free variable is
+    /// spelled the same; every later one is renamed by appending primes
+    /// (`a'`, `a''`), as in [`subst_many`](Self::subst_many), to a name used
+    /// nowhere in the graph. References follow their binders and free
+    /// variables are left alone, so the result is
+    /// [`alpha_eq`](Self::alpha_eq) to this graph.
+    pub fn fresh_rename(&self) -> Graph {
+        let env = Env {
+            subs: BTreeMap::new(),
+            renames: BTreeMap::new(),
+        };
+
+        Subst {
+            free: BTreeMap::new(),
+            taken: spellings(self).collect(),
+            distinct: Some(self.free_vars()),
+        }
+        .graph(self, &env)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_subst_many_swaps() {
+        let graph = parse_to_ast("a | b | 0".into()).unwrap();
+        let subs = BTreeMap::from([
+            ("a".to_owned(), parse_to_ast("b | 0".into()).unwrap()),
+            ("b".to_owned(), parse_to_ast("a | 0".into()).unwrap()),
+        ]);
+
+        assert_eq!(
+            graph.subst_many(&subs),
+            parse_to_ast("b | 0 * a | 0".into()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_subst_many_respects_shadowing() {
+        let graph = parse_to_ast("(let a = <v> in a | 0, let c = <c> in a | 0)".into()).unwrap();
+        let subs = BTreeMap::from([("a".to_owned(), parse_to_ast("<w> | 0".into()).unwrap())]);
+
+        assert_eq!(
+            graph.subst_many(&subs),
+            parse_to_ast("(let a = <v> in a | 0, let c = <c> in <w> | 0)".into()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_substitute_into_tensor() {
+        let graph = parse_to_ast("a | 0 * { <b> | a | 0 * let a = <a> in a | 0 }".into()).unwrap();
+        let replacement = parse_to_ast("<c> | 0".into()).unwrap();
+
+        assert_eq!(
+            graph.substitute("a", &replacement),
+            parse_to_ast("<c> | 0 * { <b> | <c> | 0 * let a = <a> in a | 0 }".into()).unwrap()
+        );
+        assert_eq!(graph.substitute("z", &replacement), graph);
+    }
+
+    #[test]
+    fn test_substitute_into_edge_binding_avoids_capture() {
+        let graph =
+            parse_to_ast("(let x = <v> in a | x | 0, let y = <w> in a | 0)".into()).unwrap();
+        let replacement = parse_to_ast("x | y | 0".into()).unwrap();
+
+        assert_eq!(
+            graph.substitute("a", &replacement),
+            parse_to_ast(
+                "(let x' = <v> in { x | y | 0 * x' | 0 }, let y' = <w> in x | y | 0)".into()
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fresh_rename_removes_shadowing() {
+        let graph = parse_to_ast(
+            "a | 0 * let a = <a> in { a | 0 * (let a = <b> in a | 0, let a = <c> in <@{a | 0}> | 0) }"
+                .into(),
+        )
+        .unwrap();
+        let renamed = graph.fresh_rename();
+
+        assert_eq!(
+            renamed,
+            parse_to_ast(
+                "a | 0 * let a' = <a> in { a' | 0 * (let a'' = <b> in a'' | 0, let a''' = <c> in <@{a''' | 0}> | 0) }"
+                    .into()
+            )
+            .unwrap()
+        );
+        assert!(renamed.alpha_eq(&graph));
+        assert_eq!(renamed.free_vars(), graph.free_vars());
+
+        let unshadowed = parse_to_ast("E(let a = <a> in a | 0, let b = <E> in 0)".into()).unwrap();
+        assert_eq!(unshadowed.fresh_rename(), unshadowed);
+    }
+
+    #[test]
+    fn test_subst_many_avoids_capture() {
+        let graph = parse_to_ast("let x = <v> in a | x | 0".into()).unwrap();
+        let subs = BTreeMap::from([("a".to_owned(), parse_to_ast("x | 0".into()).unwrap())]);
+
+        assert_eq!(
+            graph.subst_many(&subs),
+            parse_to_ast("let x' = <v> in { x | 0 * x' | 0 }".into()).unwrap()
+        );
+    }
+}
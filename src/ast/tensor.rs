@@ -0,0 +1,56 @@
+//! Parallel composition.
+
+use crate::ast::Graph;
+
+impl Graph {
+    /// Returns the operands of the tensor chain rooted at this graph, from
+    /// left to right.
+    ///
+    /// Tensor is treated as associative, so `a * {b * c}` and `{a * b} * c`
+    /// both flatten to `[a, b, c]`; nesting of any shape is collapsed.
+    /// Operands are not flattened any further once they are not tensors, and
+    /// a graph that is not a tensor flattens to itself.
+    pub fn flatten_tensors(&self) -> Vec<&Graph> {
+        let mut operands = Vec::new();
+        let mut stack = vec![self];
+
+        while let Some(graph) = stack.pop() {
+            match graph {
+                Graph::Tensor(tensor) => {
+                    stack.push(&tensor.graph_2);
+                    stack.push(&tensor.graph_1);
+                }
+                graph => operands.push(graph),
+            }
+        }
+
+        operands
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_flatten_three_way_tensor() {
+        let operands = ["<a> | 0", "<b> | 0", "(let c = <c> in 0, let d = <d> in 0)"]
+            .map(|code| parse_to_ast(code.into()).unwrap());
+
+        for code in [
+            "<a> | 0 * { <b> | 0 * (let c = <c> in 0, let d = <d> in 0) }",
+            "<a> | 0 * <b> | 0 * (let c = <c> in 0, let d = <d> in 0)",
+        ] {
+            let graph = parse_to_ast(code.into()).unwrap();
+
+            assert_eq!(graph.flatten_tensors(), operands.iter().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_flatten_non_tensor() {
+        let graph = parse_to_ast("<a> | { <b> | 0 * <c> | 0 }".into()).unwrap();
+
+        assert_eq!(graph.flatten_tensors(), [&graph]);
+    }
+}
@@ -0,0 +1,193 @@
+//! Invariant and round-trip checks for tests.
+//!
+//! The grammar accepts any [`Name`] in name position, including wildcards and
+//! quotes on named edges and contexts, so those are not invariants. What a
+//! hand-built or transformed AST can get wrong is the spelling of
+//! identifiers: each variable must be a token of the class the grammar
+//! expects at that position, and strings must be representable as C strings.
+//! A graph violating these is rejected by the C side or prints as GraphL that
+//! does not parse back.
+//!
+//! [`Name`]: crate::ast::Name
+
+use crate::ast::node::Node;
+use crate::ast::{Error, Graph, namespace};
+use crate::parse_str;
+use crate::printer::PrintOptions;
+
+/// Inputs covering every construct of the grammar and the places where the
+/// printer has to insert braces, for [`verify_roundtrip`].
+pub const ROUNDTRIP_CORPUS: &[&str] = &[
+    "0",
+    "<a> | 0",
+    "x | <X> | 0",
+    r#"< a > | { context "foo" for f in 0 }"#,
+    r#"{ context "a\"b" for _ in <a> | 0 } * <b> | 0"#,
+    "<a> | 0 * { <b> | 0 * <c> | 0 }",
+    "(let a = <a> in a | 0, let b = <@<c>> in { <b> | 0 * 0 })",
+    "E(let a = <a> in 0, let b = <b> in 0) * n(let c = <c> in 0, let d = <_> in 0)",
+    "[= <a> | 0 <b> | 0] * R [= x | 0 { <y> | 0 * 0 }]",
+    "let G = <g> | 0 * <h> | 0 in <G> | 0",
+    "let x = <x> in { let y = <@{<x> | 0}> in { x | y | 0 } }",
+    "<@{let X = [= 0 0] in 0}> | 0",
+];
+
+/// The linear graph of three edges between a notification, an encryption
+/// and a store service that many tests share.
+#[cfg(test)]
+pub(crate) const THREE_EDGE_GRAPH: &str = "{
+  (
+    let n2 = <notification> in {
+      (
+        let e2 = <encryption> in {
+          (
+            let e1 = <encryption> in <encryption> | 0,
+            let s = <store> in <store> | 0
+          )
+        } ,
+        let n1 = <notification> in <notification> | 0
+      )
+    },
+    let e3 = <encryption> in e1 | 0
+  )
+}";
+
+/// An input of [`verify_roundtrip`] that does not survive printing and
+/// parsing.
+#[derive(Debug, Clone)]
+pub enum RoundTripFailure {
+    /// The input itself does not parse.
+    Parse { input: String, error: Error },
+    /// The parsed input prints as `printed`, which does not parse.
+    Reparse {
+        input: String,
+        printed: String,
+        error: Error,
+    },
+    /// Parsing `printed` gives a different graph, which prints as
+    /// `reprinted`.
+    Mismatch {
+        input: String,
+        printed: String,
+        reprinted: String,
+    },
+}
+
+/// Parses, prints and re-parses every input, returning those whose graph
+/// changes along the way, in input order.
+///
+/// Graphs are printed with [`Graph::print`] and default options and
+/// compared with [`Graph::alpha_eq`].
+pub fn verify_roundtrip(inputs: &[&str]) -> Vec<RoundTripFailure> {
+    inputs
+        .iter()
+        .filter_map(|&input| roundtrip(input).err())
+        .collect()
+}
+
+fn roundtrip(input: &str) -> Result<(), RoundTripFailure> {
+    let opts = PrintOptions::default();
+    let graph = parse_str(input).map_err(|error| RoundTripFailure::Parse {
+        input: input.to_owned(),
+        error,
+    })?;
+
+    let printed = graph.print(&opts);
+    let reparsed = match parse_str(&printed) {
+        Ok(reparsed) if reparsed.alpha_eq(&graph) => return Ok(()),
+        Ok(reparsed) => reparsed,
+        Err(error) => {
+            return Err(RoundTripFailure::Reparse {
+                input: input.to_owned(),
+                printed,
+                error,
+            });
+        }
+    };
+
+    Err(RoundTripFailure::Mismatch {
+        input: input.to_owned(),
+        printed,
+        reprinted: reparsed.print(&opts),
+    })
+}
+
+impl Graph {
+    /// Panics with a description of the first invariant this graph violates.
+    ///
+    /// Checked invariants:
+    ///
+    /// * variables are in the namespace their position requires, as checked
+    ///   by [`validate_name_namespaces`](Self::validate_name_namespaces);
+    /// * context strings contain no NUL character.
+    pub fn assert_valid(&self) {
+        for node in Node::Graph(self).preorder() {
+            if let Some(error) = namespace::check(node) {
+                panic!("invalid graph: {error}");
+            }
+            if let Node::Graph(Graph::Context(context)) = node {
+                assert!(
+                    !context.string.contains('\0'),
+                    "invalid graph: context string {:?} contains a NUL character",
+                    context.string
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ROUNDTRIP_CORPUS, RoundTripFailure, verify_roundtrip};
+    use crate::ast::{Binding, GVertex, Graph, Name, Vertex};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_corpus_round_trips() {
+        let failures = verify_roundtrip(ROUNDTRIP_CORPUS);
+        assert!(failures.is_empty(), "{failures:#?}");
+
+        let failures = verify_roundtrip(&["<a> | 0", "<a> |"]);
+        assert!(matches!(
+            &failures[..],
+            [RoundTripFailure::Parse { input, .. }] if input == "<a> |"
+        ));
+    }
+
+    #[test]
+    fn test_parsed_graph_is_valid() {
+        parse_to_ast(
+            r#"{ (let a = <a> in x' | 0, let _b = <X> in 0) * let G = <_> | 0 in {context "c" for @<a> in 0} }"#
+                .into(),
+        )
+        .unwrap()
+        .assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "binding variable `A` is not a lowercase variable")]
+    fn test_uppercase_binding_variable_is_invalid() {
+        Graph::Nominate(Binding {
+            graph: Box::new(Graph::Nil),
+            var: "A".into(),
+            vertex: Vertex {
+                name: Name::VVar { value: "a".into() },
+            },
+        })
+        .assert_valid();
+    }
+
+    #[test]
+    #[should_panic(expected = "vertex variable name `let` is not a lowercase variable")]
+    fn test_keyword_vertex_name_is_invalid() {
+        Graph::Vertex(GVertex {
+            graph: Box::new(Graph::Nil),
+            vertex: Vertex {
+                name: Name::VVar {
+                    value: "let".into(),
+                },
+            },
+        })
+        .assert_valid();
+    }
+}
@@ -0,0 +1,686 @@
+//! Transformations that rebuild a graph into a new one.
+
+use std::collections::BTreeSet;
+use std::vec::IntoIter;
+
+use crate::ast::node::Node;
+use crate::ast::{
+    Binding,
+    Error,
+    FromGraph,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    Vertex,
+    namespace,
+};
+
+/// A node that has already been rebuilt, waiting for its parent.
+enum Built {
+    Graph(Graph),
+    Binding(Binding),
+    Vertex(Vertex),
+    Name(Name),
+}
+
+enum Task<'a> {
+    Visit(Node<'a>),
+    Assemble(Node<'a>),
+}
+
+struct Children(IntoIter<Built>);
+
+impl Children {
+    fn graph(&mut self) -> Box<Graph> {
+        match self.0.next() {
+            Some(Built::Graph(graph)) => Box::new(graph),
+            _ => unreachable!("expected a rebuilt graph"),
+        }
+    }
+
+    fn binding(&mut self) -> Binding {
+        match self.0.next() {
+            Some(Built::Binding(binding)) => binding,
+            _ => unreachable!("expected a rebuilt binding"),
+        }
+    }
+
+    fn vertex(&mut self) -> Vertex {
+        match self.0.next() {
+            Some(Built::Vertex(vertex)) => vertex,
+            _ => unreachable!("expected a rebuilt vertex"),
+        }
+    }
+
+    fn name(&mut self) -> Name {
+        match self.0.next() {
+            Some(Built::Name(name)) => name,
+            _ => unreachable!("expected a rebuilt name"),
+        }
+    }
+}
+
+/// Rebuilds `graph` top-down without recursion.
+///
+/// `replace` is called on every [`Graph`] node reachable from the root,
+/// including graphs inside bindings and quoted names. When it returns
+/// `Some`, that graph is used as is and the original node's children are
+/// not visited; otherwise the node is rebuilt from its rebuilt children.
+pub(crate) fn rewrite(graph: &Graph, mut replace: impl FnMut(&Graph) -> Option<Graph>) -> Graph {
+    let mut tasks = vec![Task::Visit(Node::Graph(graph))];
+    let mut built: Vec<Built> = Vec::new();
+
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(node) => {
+                if let Node::Graph(graph) = node
+                    && let Some(replacement) = replace(graph)
+                {
+                    built.push(Built::Graph(replacement));
+                    continue;
+                }
+                tasks.push(Task::Assemble(node));
+                tasks.extend(node.children().into_iter().rev().map(Task::Visit));
+            }
+            Task::Assemble(node) => {
+                let arity = node.children().len();
+                let children = built.split_off(built.len() - arity);
+                built.push(assemble(node, Children(children.into_iter())));
+            }
+        }
+    }
+
+    match built.pop() {
+        Some(Built::Graph(graph)) => graph,
+        _ => unreachable!("the root is a graph"),
+    }
+}
+
+fn assemble(node: Node<'_>, mut children: Children) -> Built {
+    match node {
+        Node::Graph(graph) => Built::Graph(match graph {
+            Graph::Nil => Graph::Nil,
+            Graph::Vertex(_) => {
+                let vertex = children.vertex();
+                Graph::Vertex(GVertex {
+                    vertex,
+                    graph: children.graph(),
+                })
+            }
+            Graph::Var(var) => Graph::Var(GVar {
+                graph: children.graph(),
+                var: var.var.clone(),
+            }),
+            Graph::Nominate(_) => Graph::Nominate(children.binding()),
+            Graph::EdgeAnon(_) => Graph::EdgeAnon(GEdgeAnon {
+                binding_1: children.binding(),
+                binding_2: children.binding(),
+            }),
+            Graph::EdgeNamed(_) => Graph::EdgeNamed(GEdgeNamed {
+                name: children.name(),
+                binding_1: children.binding(),
+                binding_2: children.binding(),
+            }),
+            Graph::RuleAnon(_) => Graph::RuleAnon(GRuleAnon {
+                graph_1: children.graph(),
+                graph_2: children.graph(),
+            }),
+            Graph::RuleNamed(_) => Graph::RuleNamed(GRuleNamed {
+                name: children.name(),
+                graph_1: children.graph(),
+                graph_2: children.graph(),
+            }),
+            Graph::Subgraph(binding) => Graph::Subgraph(GraphBinding {
+                graph_1: children.graph(),
+                graph_2: children.graph(),
+                var: binding.var.clone(),
+            }),
+            Graph::Tensor(_) => Graph::Tensor(GTensor {
+                graph_1: children.graph(),
+                graph_2: children.graph(),
+            }),
+            Graph::Context(context) => Graph::Context(GContext {
+                name: children.name(),
+                graph: children.graph(),
+                string: context.string.clone(),
+            }),
+        }),
+        Node::Binding(binding) => Built::Binding(Binding {
+            vertex: children.vertex(),
+            graph: children.graph(),
+            var: binding.var.clone(),
+        }),
+        Node::Vertex(_) => Built::Vertex(Vertex {
+            name: children.name(),
+        }),
+        Node::Name(name) => Built::Name(match name {
+            Name::QuoteGraph { .. } => Name::QuoteGraph {
+                value: children.graph(),
+            },
+            Name::QuoteVertex { .. } => Name::QuoteVertex {
+                value: Box::new(children.vertex()),
+            },
+            Name::Wildcard | Name::VVar { .. } | Name::GVar { .. } => name.clone(),
+        }),
+    }
+}
+
+type StringMap<'f> = dyn FnMut(String) -> String + 'f;
+
+fn map_graph(graph: Graph, f: &mut StringMap<'_>) -> Graph {
+    match graph {
+        Graph::Nil => Graph::Nil,
+        Graph::Vertex(vertex) => {
+            let mapped = map_vertex(vertex.vertex, f);
+            Graph::Vertex(GVertex {
+                vertex: mapped,
+                graph: Box::new(map_graph(*vertex.graph, f)),
+            })
+        }
+        Graph::Var(var) => {
+            let name = f(var.var);
+            Graph::Var(GVar {
+                graph: Box::new(map_graph(*var.graph, f)),
+                var: name,
+            })
+        }
+        Graph::Nominate(binding) => Graph::Nominate(map_binding(binding, f)),
+        Graph::EdgeAnon(edge) => {
+            let binding_1 = map_binding(edge.binding_1, f);
+            Graph::EdgeAnon(GEdgeAnon {
+                binding_1,
+                binding_2: map_binding(edge.binding_2, f),
+            })
+        }
+        Graph::EdgeNamed(edge) => {
+            let name = map_name(edge.name, f);
+            let binding_1 = map_binding(edge.binding_1, f);
+            Graph::EdgeNamed(GEdgeNamed {
+                name,
+                binding_1,
+                binding_2: map_binding(edge.binding_2, f),
+            })
+        }
+        Graph::RuleAnon(rule) => {
+            let graph_1 = Box::new(map_graph(*rule.graph_1, f));
+            Graph::RuleAnon(GRuleAnon {
+                graph_1,
+                graph_2: Box::new(map_graph(*rule.graph_2, f)),
+            })
+        }
+        Graph::RuleNamed(rule) => {
+            let name = map_name(rule.name, f);
+            let graph_1 = Box::new(map_graph(*rule.graph_1, f));
+            Graph::RuleNamed(GRuleNamed {
+                name,
+                graph_1,
+                graph_2: Box::new(map_graph(*rule.graph_2, f)),
+            })
+        }
+        Graph::Subgraph(binding) => {
+            let var = f(binding.var);
+            let graph_1 = Box::new(map_graph(*binding.graph_1, f));
+            Graph::Subgraph(GraphBinding {
+                graph_1,
+                graph_2: Box::new(map_graph(*binding.graph_2, f)),
+                var,
+            })
+        }
+        Graph::Tensor(tensor) => {
+            let graph_1 = Box::new(map_graph(*tensor.graph_1, f));
+            Graph::Tensor(GTensor {
+                graph_1,
+                graph_2: Box::new(map_graph(*tensor.graph_2, f)),
+            })
+        }
+        Graph::Context(context) => {
+            let string = f(context.string);
+            let name = map_name(context.name, f);
+            Graph::Context(GContext {
+                graph: Box::new(map_graph(*context.graph, f)),
+                name,
+                string,
+            })
+        }
+    }
+}
+
+fn map_binding(binding: Binding, f: &mut StringMap<'_>) -> Binding {
+    let var = f(binding.var);
+    let vertex = map_vertex(binding.vertex, f);
+    Binding {
+        graph: Box::new(map_graph(*binding.graph, f)),
+        var,
+        vertex,
+    }
+}
+
+fn map_vertex(vertex: Vertex, f: &mut StringMap<'_>) -> Vertex {
+    Vertex {
+        name: map_name(vertex.name, f),
+    }
+}
+
+fn map_name(name: Name, f: &mut StringMap<'_>) -> Name {
+    match name {
+        Name::Wildcard => Name::Wildcard,
+        Name::VVar { value } => Name::VVar { value: f(value) },
+        Name::GVar { value } => Name::GVar { value: f(value) },
+        Name::QuoteGraph { value } => Name::QuoteGraph {
+            value: Box::new(map_graph(*value, f)),
+        },
+        Name::QuoteVertex { value } => Name::QuoteVertex {
+            value: Box::new(map_vertex(*value, f)),
+        },
+    }
+}
+
+impl Graph {
+    /// Applies `f` to every string in the tree and returns the result.
+    ///
+    /// The strings are the variables of `x | g`, `let x = ...` and
+    /// `let X = ...`, variable names (of vertices, edges, rules and
+    /// contexts, including quoted ones) and context strings. Each is passed
+    /// to `f` exactly once, in source order. The result is not validated, so
+    /// `f` can produce strings that the grammar would not accept.
+    pub fn map_strings(self, mut f: impl FnMut(String) -> String) -> Graph {
+        map_graph(self, &mut f)
+    }
+
+    /// Returns a copy of this graph in which every subtree rooted at a graph
+    /// node rejected by `keep` is replaced with [`Graph::Nil`].
+    ///
+    /// The predicate is evaluated top-down. A rejected node is dropped
+    /// together with everything beneath it, so `keep` is never called on its
+    /// descendants. Graphs nested in bindings and quoted names are filtered
+    /// too. Dropping the continuation of a binding or vertex keeps the
+    /// binding or vertex itself: filtering `<a> | <b> | 0` with a predicate
+    /// that rejects `<b> | 0` yields `<a> | 0`.
+    pub fn retain(&self, keep: impl Fn(&Graph) -> bool) -> Graph {
+        rewrite(self, |graph| (!keep(graph)).then_some(Graph::Nil))
+    }
+
+    /// Replaces every `context ... in g` node, including its scope `g`, with
+    /// [`Graph::Nil`].
+    pub fn strip_contexts(&self) -> Graph {
+        self.retain(|graph| !matches!(graph, Graph::Context(_)))
+    }
+
+    /// Returns this graph with repeated subgraphs defined once by a
+    /// `let X = g1 in g2` at the root and referenced as `<X> | 0`.
+    ///
+    /// The candidates are the groups of
+    /// [`common_subexpressions`](Self::common_subexpressions), outermost
+    /// first. A group is shared only if
+    ///
+    /// * it is larger than its reference, so not a lone `<v> | 0` or `x | 0`;
+    /// * no occurrence refers to a binder that encloses it, since the
+    ///   definition is outside every binder;
+    /// * none of its occurrences overlaps an occurrence already shared.
+    ///
+    /// The graph variables are named `X0`, `X1`, ... skipping names that
+    /// occur anywhere in the graph, and the definitions are nested in that
+    /// order, `X0` outermost.
+    pub fn introduce_sharing(&self) -> Graph {
+        let mut used = BTreeSet::new();
+        self.clone().map_strings(|s| {
+            used.insert(s.clone());
+            s
+        });
+        let mut fresh = (0..)
+            .map(|i| format!("X{i}"))
+            .filter(|name| !used.contains(name));
+
+        let mut shared: Vec<Vec<usize>> = Vec::new();
+        let mut definitions = Vec::new();
+        let mut graph = self.clone();
+
+        for (subgraph, paths) in self.common_subexpressions() {
+            let trivial = match &subgraph {
+                Graph::Vertex(vertex) => *vertex.graph == Graph::Nil,
+                Graph::Var(var) => *var.graph == Graph::Nil,
+                _ => false,
+            };
+            let overlaps = paths.iter().any(|path| {
+                shared
+                    .iter()
+                    .any(|other| path.starts_with(other) || other.starts_with(path))
+            });
+            let captured = paths.iter().any(|path| {
+                let free = self
+                    .subgraph_at(path)
+                    .map(Graph::free_vars)
+                    .unwrap_or_default();
+                binders_along(self, path).any(|binder| free.contains(binder))
+            });
+            if trivial || overlaps || captured {
+                continue;
+            }
+
+            let var = fresh.next().expect("there are infinitely many names");
+            for path in &paths {
+                if let Some(node) = graph.node_at_mut(path) {
+                    *node = Graph::Vertex(GVertex {
+                        graph: Box::new(Graph::Nil),
+                        vertex: Vertex {
+                            name: Name::GVar { value: var.clone() },
+                        },
+                    });
+                }
+            }
+            shared.extend(paths);
+            definitions.push((var, subgraph));
+        }
+
+        definitions
+            .into_iter()
+            .rev()
+            .fold(graph, |graph, (var, definition)| {
+                Graph::Subgraph(GraphBinding {
+                    graph_1: Box::new(definition),
+                    graph_2: Box::new(graph),
+                    var,
+                })
+            })
+    }
+
+    /// Joins this graph and `other` with an edge between their entry
+    /// vertices.
+    ///
+    /// Both graphs must start with a vertex, `<v> | g` and `<w> | h`, which
+    /// become the two bindings of
+    /// `edge_name(let var_self = <v> in g, let var_other = <w> in h)`, or an
+    /// anonymous edge without a name. An edge name spelled as an uppercase
+    /// variable (`E`, `_E1`) is a graph variable, any other a vertex
+    /// variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidVariant`] if either graph is not a vertex, and
+    /// [`Error::VarInUse`] if `var_self`, `var_other` or the edge name is a
+    /// free variable of either graph, which the new binder would capture, or
+    /// if the two variables are the same.
+    pub fn connect(
+        self,
+        other: Graph,
+        edge_name: Option<String>,
+        var_self: String,
+        var_other: String,
+    ) -> Result<Graph, Error> {
+        let mut free = self.free_vars();
+        free.extend(other.free_vars());
+        if var_self == var_other {
+            free.insert(var_self.clone());
+        }
+        if let Some(var) = [Some(&var_self), Some(&var_other), edge_name.as_ref()]
+            .into_iter()
+            .flatten()
+            .find(|var| free.contains(*var))
+        {
+            return Err(Error::VarInUse { var: var.clone() });
+        }
+
+        let entry = |graph: Graph, var: String| {
+            GVertex::from_graph(graph).map(|vertex| Binding {
+                graph: vertex.graph,
+                var,
+                vertex: vertex.vertex,
+            })
+        };
+        let binding_1 = entry(self, var_self)?;
+        let binding_2 = entry(other, var_other)?;
+
+        Ok(match edge_name {
+            Some(value) => Graph::EdgeNamed(GEdgeNamed {
+                binding_1,
+                binding_2,
+                name: namespace::vertex_name(value),
+            }),
+            None => Graph::EdgeAnon(GEdgeAnon {
+                binding_1,
+                binding_2,
+            }),
+        })
+    }
+
+    /// Unwraps every `context s for n in g` node to its scope `g`, returning
+    /// the context-free graph and the removed contexts.
+    ///
+    /// Each context is recorded as its path in the returned graph (see the
+    /// `paths` module) with its name and string, in pre-order. Nested contexts
+    /// share a path, outermost first. Contexts inside quoted names are not
+    /// reached by paths and stay in place. [`insert_contexts`] undoes this.
+    ///
+    /// [`insert_contexts`]: Self::insert_contexts
+    pub fn extract_contexts(&self) -> (Graph, Vec<(Vec<usize>, Name, String)>) {
+        let mut graph = self.clone();
+        let mut contexts = Vec::new();
+        let mut stack = vec![(&mut graph, Vec::new())];
+
+        while let Some((graph, path)) = stack.pop() {
+            while let Graph::Context(_) = graph {
+                let Graph::Context(context) = std::mem::replace(graph, Graph::Nil) else {
+                    unreachable!("checked above");
+                };
+                contexts.push((path.clone(), context.name, context.string));
+                *graph = *context.graph;
+            }
+
+            for (index, child) in graph.children_mut().into_iter().enumerate().rev() {
+                let mut child_path = path.clone();
+                child_path.push(index);
+                stack.push((child, child_path));
+            }
+        }
+
+        (graph, contexts)
+    }
+
+    /// Wraps the node at each path in a context, the inverse of
+    /// [`extract_contexts`](Self::extract_contexts).
+    ///
+    /// Paths refer to the graph without the contexts, and the contexts are
+    /// expected in pre-order as `extract_contexts` returns them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidPath`] if a path does not lead to a node.
+    pub fn insert_contexts(&self, contexts: &[(Vec<usize>, Name, String)]) -> Result<Graph, Error> {
+        let mut graph = self.clone();
+
+        // Going backwards, a context is inserted only after those beneath
+        // it, so wrapping a node never moves a path that is still to come.
+        for (path, name, string) in contexts.iter().rev() {
+            let node = graph
+                .node_at_mut(path)
+                .ok_or_else(|| Error::InvalidPath { path: path.clone() })?;
+            let scope = std::mem::replace(node, Graph::Nil);
+            *node = Graph::Context(GContext {
+                graph: Box::new(scope),
+                name: name.clone(),
+                string: string.clone(),
+            });
+        }
+
+        Ok(graph)
+    }
+}
+
+/// The variables bound by the nodes from the root of `graph` down to, but
+/// not including, the node at `path`.
+fn binders_along<'a>(graph: &'a Graph, path: &'a [usize]) -> impl Iterator<Item = &'a str> {
+    fn name_var(name: &Name) -> Option<&str> {
+        match name {
+            Name::VVar { value } | Name::GVar { value } => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    path.iter()
+        .scan(graph, |graph, &index| {
+            let binders = match (*graph, index) {
+                (Graph::Nominate(binding), _) => vec![Some(binding.var.as_str())],
+                (Graph::EdgeAnon(edge), 0) => vec![Some(edge.binding_1.var.as_str())],
+                (Graph::EdgeAnon(edge), _) => vec![Some(edge.binding_2.var.as_str())],
+                (Graph::EdgeNamed(edge), 0) => {
+                    vec![Some(edge.binding_1.var.as_str()), name_var(&edge.name)]
+                }
+                (Graph::EdgeNamed(edge), _) => {
+                    vec![Some(edge.binding_2.var.as_str()), name_var(&edge.name)]
+                }
+                (Graph::RuleNamed(rule), _) => vec![name_var(&rule.name)],
+                (Graph::Subgraph(binding), 1) => vec![Some(binding.var.as_str())],
+                _ => vec![],
+            };
+            *graph = graph.children().get(index).copied()?;
+            Some(binders)
+        })
+        .flatten()
+        .flatten()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ast::{Error, Graph, Name};
+    use crate::{ast_to_graphl, parse_to_ast};
+
+    #[test]
+    fn test_map_strings_visits_each_string_once_in_order() {
+        let graph = parse_to_ast(
+            r#"context "ctx" for c in { n(let a = <@{<q> | 0}> in x | 0, let b = <B> in 0) * let G = <g> | 0 in 0 }"#
+                .into(),
+        )
+        .unwrap();
+
+        let mut seen = Vec::new();
+        let mapped = graph.map_strings(|s| {
+            seen.push(s.clone());
+            s.len().to_string()
+        });
+
+        assert_eq!(seen, ["ctx", "c", "n", "a", "q", "x", "b", "B", "G", "g"]);
+        let Graph::Context(context) = mapped else {
+            panic!("expected a context");
+        };
+        assert_eq!(context.string, "3");
+    }
+
+    #[test]
+    fn test_retain_everything_is_identity() {
+        let graph = parse_to_ast(
+            "{ (let a = <a> in <@{<b> | 0}> | 0, let c = <c> in x | 0) * [= <d> | 0 <e> | 0] }"
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(graph.retain(|_| true), graph);
+    }
+
+    #[test]
+    fn test_retain_drops_rules() {
+        let graph = parse_to_ast("{ <a> | 0 * { [= <b> | 0 <c> | 0] * <d> | 0 } }".into()).unwrap();
+        let expected = parse_to_ast("{ <a> | 0 * { 0 * <d> | 0 } }".into()).unwrap();
+
+        assert_eq!(
+            graph.retain(|graph| !matches!(graph, Graph::RuleAnon(_) | Graph::RuleNamed(_))),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_retain_non_contexts_matches_strip_contexts() {
+        let graph = parse_to_ast(
+            r#"{ <a> | 0 * { {context "foo" for f in <f> | 0} * (let b = <b> in {context "bar" for g in 0}, let c = <c> in 0) } }"#
+                .into(),
+        )
+        .unwrap();
+        let expected =
+            parse_to_ast("{ <a> | 0 * { 0 * (let b = <b> in 0, let c = <c> in 0) } }".into())
+                .unwrap();
+
+        let retained = graph.retain(|graph| !matches!(graph, Graph::Context(_)));
+
+        assert_eq!(retained, graph.strip_contexts());
+        assert_eq!(retained, expected);
+    }
+
+    #[test]
+    fn test_extract_and_insert_contexts_round_trip() {
+        let graph = parse_to_ast(
+            r#"context "outer" for o in { <a> | 0 * (let b = <b> in {context "foo" for f in context "bar" for _ in <f> | 0}, let c = <c> in 0) }"#
+                .into(),
+        )
+        .unwrap();
+        let expected =
+            parse_to_ast("{ <a> | 0 * (let b = <b> in <f> | 0, let c = <c> in 0) }".into())
+                .unwrap();
+
+        let (stripped, contexts) = graph.extract_contexts();
+
+        assert_eq!(stripped, expected);
+        assert_eq!(
+            contexts,
+            [
+                (vec![], Name::VVar { value: "o".into() }, "outer".into()),
+                (vec![1, 0], Name::VVar { value: "f".into() }, "foo".into()),
+                (vec![1, 0], Name::Wildcard, "bar".into()),
+            ]
+        );
+        assert_eq!(stripped.insert_contexts(&contexts).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_introduce_sharing() {
+        let graph = parse_to_ast(
+            "{ <a> | <b> | 0 * let x = <c> in { <a> | <b> | 0 * x | <d> | 0 } } * let X0 = <e> | 0 in <X0> | x | <d> | 0"
+                .into(),
+        )
+        .unwrap();
+        let expected = parse_to_ast(
+            "let X1 = <a> | <b> | 0 in { { <X1> | 0 * let x = <c> in { <X1> | 0 * x | <d> | 0 } } * let X0 = <e> | 0 in <X0> | x | <d> | 0 }"
+                .into(),
+        )
+        .unwrap();
+
+        let shared = graph.introduce_sharing();
+
+        assert_eq!(shared, expected);
+        assert_eq!(shared.introduce_sharing(), shared);
+    }
+
+    #[test]
+    fn test_connect_single_vertex_graphs() {
+        let a = parse_to_ast("<a> | 0".into()).unwrap();
+        let b = parse_to_ast("<b> | x | 0".into()).unwrap();
+
+        let edge = a
+            .clone()
+            .connect(b.clone(), Some("E".into()), "a".into(), "b".into())
+            .unwrap();
+
+        assert_eq!(
+            edge,
+            parse_to_ast("E(let a = <a> in 0, let b = <b> in x | 0)".into()).unwrap()
+        );
+        assert_eq!(
+            parse_to_ast(ast_to_graphl(edge.clone()).unwrap()).unwrap(),
+            edge
+        );
+        assert!(matches!(
+            a.clone().connect(b.clone(), None, "x".into(), "b".into()),
+            Err(Error::VarInUse { var }) if var == "x"
+        ));
+        assert!(matches!(
+            a.connect(Graph::Nil, None, "a".into(), "b".into()),
+            Err(Error::InvalidVariant { .. })
+        ));
+    }
+}
@@ -0,0 +1,350 @@
+//! One entry point for the well-formedness checks of a graph.
+
+use crate::ast::namespace::{self, NamespaceError};
+use crate::ast::node::Node;
+use crate::ast::{Binding, EdgeConflict, Graph, Name};
+
+/// A problem found by [`Graph::validate_all`].
+///
+/// Every diagnostic carries the path of the node it concerns, following
+/// [`Graph::children`]. Problems inside a quoted name are reported at the
+/// node holding the name.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum Diagnostic {
+    /// A reference outside the scope of any binder of its name, see
+    /// [`Graph::free_vars`].
+    #[error("`{var}` at {path:?} is not bound")]
+    UnboundVariable { path: Vec<usize>, var: String },
+    /// A binder for a variable that an enclosing binder already binds,
+    /// hiding it.
+    #[error("`{var}` at {path:?} shadows an enclosing binding")]
+    DuplicateBinding { path: Vec<usize>, var: String },
+    /// A variable outside its namespace, see
+    /// [`Graph::validate_name_namespaces`].
+    #[error("{error} at {path:?}")]
+    Namespace {
+        path: Vec<usize>,
+        error: NamespaceError,
+    },
+    /// See [`Graph::check_edge_binding_conflicts`].
+    #[error(transparent)]
+    EdgeConflict(EdgeConflict),
+    /// See [`Graph::orphan_contexts`].
+    #[error("the context at {path:?} names nothing in its scope")]
+    OrphanContext { path: Vec<usize> },
+}
+
+impl Diagnostic {
+    /// The path of the node the problem was found at.
+    pub fn path(&self) -> &[usize] {
+        match self {
+            Diagnostic::UnboundVariable { path, .. }
+            | Diagnostic::DuplicateBinding { path, .. }
+            | Diagnostic::Namespace { path, .. }
+            | Diagnostic::EdgeConflict(EdgeConflict { path, .. })
+            | Diagnostic::OrphanContext { path } => path,
+        }
+    }
+}
+
+/// Where a node is reported, and whether references in it are resolved.
+#[derive(Clone)]
+struct Place {
+    path: Vec<usize>,
+    /// Inside a quoted name, whose graphs have no paths of their own, so
+    /// everything is reported at the node holding the name.
+    quoted: bool,
+    /// Cleared inside the name of a context, which is not searched for
+    /// references.
+    resolve: bool,
+}
+
+impl Place {
+    fn child(&self, index: usize) -> Place {
+        let mut place = self.clone();
+        if !self.quoted {
+            place.path.push(index);
+        }
+        place
+    }
+}
+
+enum Step<'a> {
+    Graph(&'a Graph, Place),
+    /// A binding of the node at the place, whose continuation is child
+    /// `index`.
+    Binding(&'a Binding, Place, usize),
+    Name(&'a Name, Place),
+    Enter(&'a str, Vec<usize>),
+    Leave,
+}
+
+#[derive(Default)]
+struct Checker<'a> {
+    scopes: Vec<&'a str>,
+    stack: Vec<Step<'a>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Checker<'a> {
+    fn namespace(&mut self, node: Node<'_>, path: &[usize]) {
+        if let Some(error) = namespace::check(node) {
+            self.diagnostics.push(Diagnostic::Namespace {
+                path: path.to_vec(),
+                error,
+            });
+        }
+    }
+
+    fn reference(&mut self, var: &str, place: &Place) {
+        if place.resolve && !self.scopes.contains(&var) {
+            self.diagnostics.push(Diagnostic::UnboundVariable {
+                path: place.path.clone(),
+                var: var.to_owned(),
+            });
+        }
+    }
+
+    /// Pushes the scope of a named edge or rule around `steps`, or just
+    /// `steps` and the name when it binds nothing.
+    fn named(&mut self, name: &'a Name, place: &Place, steps: [Step<'a>; 2]) {
+        match name {
+            Name::VVar { value } | Name::GVar { value } => {
+                self.namespace(Node::Name(name), &place.path);
+                self.stack.push(Step::Leave);
+                self.stack.extend(steps);
+                self.stack.push(Step::Enter(value, place.path.clone()));
+            }
+            Name::Wildcard | Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => {
+                self.stack.push(Step::Name(name, place.clone()));
+                self.stack.extend(steps);
+            }
+        }
+    }
+
+    fn graph(&mut self, graph: &'a Graph, place: Place) {
+        self.namespace(Node::Graph(graph), &place.path);
+
+        match graph {
+            Graph::Nil => {}
+            Graph::Vertex(vertex) => {
+                self.stack.push(Step::Graph(&vertex.graph, place.child(0)));
+                self.stack.push(Step::Name(&vertex.vertex.name, place));
+            }
+            Graph::Var(var) => {
+                self.reference(&var.var, &place);
+                self.stack.push(Step::Graph(&var.graph, place.child(0)));
+            }
+            Graph::Nominate(binding) => self.stack.push(Step::Binding(binding, place, 0)),
+            Graph::EdgeAnon(edge) => {
+                self.stack
+                    .push(Step::Binding(&edge.binding_2, place.clone(), 1));
+                self.stack.push(Step::Binding(&edge.binding_1, place, 0));
+            }
+            Graph::EdgeNamed(edge) => {
+                let steps = [
+                    Step::Binding(&edge.binding_2, place.clone(), 1),
+                    Step::Binding(&edge.binding_1, place.clone(), 0),
+                ];
+                self.named(&edge.name, &place, steps);
+            }
+            Graph::RuleAnon(rule) => {
+                self.stack.push(Step::Graph(&rule.graph_2, place.child(1)));
+                self.stack.push(Step::Graph(&rule.graph_1, place.child(0)));
+            }
+            Graph::RuleNamed(rule) => {
+                let steps = [
+                    Step::Graph(&rule.graph_2, place.child(1)),
+                    Step::Graph(&rule.graph_1, place.child(0)),
+                ];
+                self.named(&rule.name, &place, steps);
+            }
+            Graph::Subgraph(binding) => {
+                self.stack.push(Step::Leave);
+                self.stack
+                    .push(Step::Graph(&binding.graph_2, place.child(1)));
+                self.stack
+                    .push(Step::Enter(&binding.var, place.path.clone()));
+                self.stack
+                    .push(Step::Graph(&binding.graph_1, place.child(0)));
+            }
+            Graph::Tensor(tensor) => {
+                self.stack
+                    .push(Step::Graph(&tensor.graph_2, place.child(1)));
+                self.stack
+                    .push(Step::Graph(&tensor.graph_1, place.child(0)));
+            }
+            Graph::Context(context) => {
+                self.stack.push(Step::Graph(&context.graph, place.child(0)));
+                self.stack.push(Step::Name(
+                    &context.name,
+                    Place {
+                        resolve: false,
+                        ..place
+                    },
+                ));
+            }
+        }
+    }
+
+    fn run(mut self, graph: &'a Graph) -> Vec<Diagnostic> {
+        let root = Place {
+            path: Vec::new(),
+            quoted: false,
+            resolve: true,
+        };
+        self.stack.push(Step::Graph(graph, root));
+
+        while let Some(step) = self.stack.pop() {
+            match step {
+                Step::Graph(graph, place) => self.graph(graph, place),
+                Step::Binding(binding, place, index) => {
+                    self.namespace(Node::Binding(binding), &place.path);
+                    self.stack.push(Step::Leave);
+                    self.stack
+                        .push(Step::Graph(&binding.graph, place.child(index)));
+                    self.stack
+                        .push(Step::Enter(&binding.var, place.path.clone()));
+                    self.stack.push(Step::Name(&binding.vertex.name, place));
+                }
+                Step::Name(name, place) => {
+                    self.namespace(Node::Name(name), &place.path);
+                    match name {
+                        Name::Wildcard | Name::VVar { .. } => {}
+                        Name::GVar { value } => self.reference(value, &place),
+                        Name::QuoteGraph { value } => self.stack.push(Step::Graph(
+                            value,
+                            Place {
+                                quoted: true,
+                                ..place
+                            },
+                        )),
+                        Name::QuoteVertex { value } => {
+                            self.stack.push(Step::Name(&value.name, place));
+                        }
+                    }
+                }
+                Step::Enter(var, path) => {
+                    if self.scopes.contains(&var) {
+                        self.diagnostics.push(Diagnostic::DuplicateBinding {
+                            path,
+                            var: var.to_owned(),
+                        });
+                    }
+                    self.scopes.push(var);
+                }
+                Step::Leave => {
+                    self.scopes.pop();
+                }
+            }
+        }
+
+        self.diagnostics
+    }
+}
+
+impl Graph {
+    /// Runs every well-formedness check on this graph and returns all the
+    /// problems found, ordered by path.
+    ///
+    /// The checks are: unbound references and bindings that shadow an
+    /// enclosing one, both scoped as described in the `scope` module;
+    /// [`validate_name_namespaces`](Self::validate_name_namespaces);
+    /// [`check_edge_binding_conflicts`](Self::check_edge_binding_conflicts);
+    /// and [`orphan_contexts`](Self::orphan_contexts). The first three share
+    /// one traversal. Problems at the same path keep the order above.
+    ///
+    /// # Errors
+    ///
+    /// Returns the diagnostics if there is at least one.
+    pub fn validate_all(&self) -> Result<(), Vec<Diagnostic>> {
+        let mut diagnostics = Checker::default().run(self);
+        if let Err(conflicts) = self.check_edge_binding_conflicts() {
+            diagnostics.extend(conflicts.into_iter().map(Diagnostic::EdgeConflict));
+        }
+        diagnostics.extend(
+            self.orphan_contexts()
+                .into_iter()
+                .map(|path| Diagnostic::OrphanContext { path }),
+        );
+
+        let rank = |diagnostic: &Diagnostic| match diagnostic {
+            Diagnostic::UnboundVariable { .. } => 0,
+            Diagnostic::DuplicateBinding { .. } => 1,
+            Diagnostic::Namespace { .. } => 2,
+            Diagnostic::EdgeConflict(_) => 3,
+            Diagnostic::OrphanContext { .. } => 4,
+        };
+        diagnostics.sort_by(|a, b| a.path().cmp(b.path()).then(rank(a).cmp(&rank(b))));
+
+        if diagnostics.is_empty() {
+            Ok(())
+        } else {
+            Err(diagnostics)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Diagnostic;
+    use crate::ast::namespace::{NamespaceError, VarRole};
+    use crate::ast::{EdgeConflict, GVar, Graph, GraphBuilder};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_validate_all_reports_every_problem_by_path() {
+        // `X | 0` does not parse, so the misplaced variable is built by hand.
+        let quoted = Graph::Var(GVar {
+            graph: Box::new(Graph::Nil),
+            var: "X".into(),
+        });
+        let graph = GraphBuilder::tensor(
+            parse_to_ast(r#"context "c" for z in <a> | 0"#.into()).unwrap(),
+            GraphBuilder::tensor(
+                parse_to_ast("(let a = <a> in y | 0, let a = <b> in 0)".into()).unwrap(),
+                GraphBuilder::nil()
+                    .prefix_quoted_vertex(quoted)
+                    .prefix_nominate("x", "x")
+                    .prefix_nominate("x", "x"),
+            ),
+        )
+        .build();
+
+        assert_eq!(
+            graph.validate_all(),
+            Err(vec![
+                Diagnostic::OrphanContext { path: vec![0] },
+                Diagnostic::EdgeConflict(EdgeConflict {
+                    path: vec![1, 0],
+                    var: "a".into(),
+                }),
+                Diagnostic::UnboundVariable {
+                    path: vec![1, 0, 0],
+                    var: "y".into(),
+                },
+                Diagnostic::DuplicateBinding {
+                    path: vec![1, 1, 0],
+                    var: "x".into(),
+                },
+                Diagnostic::UnboundVariable {
+                    path: vec![1, 1, 0, 0],
+                    var: "X".into(),
+                },
+                Diagnostic::Namespace {
+                    path: vec![1, 1, 0, 0],
+                    error: NamespaceError {
+                        role: VarRole::Process,
+                        var: "X".into(),
+                    },
+                },
+            ])
+        );
+        assert_eq!(
+            parse_to_ast("let a = <a> in a | 0".into())
+                .unwrap()
+                .validate_all(),
+            Ok(())
+        );
+    }
+}
@@ -1,6 +1,31 @@
+//! Raw bindings to the BNFC-generated C parser, generated by bindgen from
+//! `parser/wrapper.h`.
+//!
+//! This is the only path through which the crate reaches the C code: the
+//! absyn types, the `free_*` destructors, `psGraph`, `psClearError`,
+//! `psLastError`, `printGraph`, `showGraph` and `bufReset` are all used as
+//! `crate::bindings::...`, and none of them is re-exported. The module is
+//! private because the items are regenerated on every build and carry no
+//! stability guarantees; external code should go through [`crate::ast`] and
+//! the parsing functions instead.
+
 #![allow(dead_code)]
 #![allow(non_upper_case_globals)]
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+#[cfg(test)]
+mod test {
+    use crate::bindings;
+
+    #[test]
+    fn test_raw_graph_through_bindings_path() {
+        let graph: bindings::Graph = unsafe { bindings::psGraph(c"<a> | 0".as_ptr()) };
+
+        assert!(!graph.is_null());
+        assert_eq!(unsafe { (*graph).kind }, bindings::Graph__is_GVertex);
+        unsafe { bindings::free_Graph(graph) };
+    }
+}
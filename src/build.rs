@@ -0,0 +1,219 @@
+//! Constructors for assembling graphs without writing GraphL.
+//!
+//! Each function builds one node from its parts and is exported to
+//! JavaScript under the camel-cased name, so TypeScript code can assemble an
+//! AST and hand it to `astToGraphl`. JavaScript reserves `var`, so [`var`]
+//! is exported as `variable`.
+//!
+//! The constructors do not check variables against the grammar; use
+//! [`Graph::validate_name_namespaces`] on the result when the parts come from
+//! user input.
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::ast::{
+    Binding,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    Vertex,
+};
+
+/// `0`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn nil() -> Graph {
+    Graph::Nil
+}
+
+/// `<name> | graph`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn vertex(name: Name, graph: Graph) -> Graph {
+    Graph::Vertex(GVertex {
+        graph: Box::new(graph),
+        vertex: Vertex { name },
+    })
+}
+
+/// `var | graph`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = variable))]
+pub fn var(var: String, graph: Graph) -> Graph {
+    Graph::Var(GVar {
+        graph: Box::new(graph),
+        var,
+    })
+}
+
+/// `let var = <name> in graph`, for use in [`nominate`] and edges.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn binding(var: String, name: Name, graph: Graph) -> Binding {
+    Binding {
+        graph: Box::new(graph),
+        var,
+        vertex: Vertex { name },
+    }
+}
+
+/// A binding on its own, as a graph.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn nominate(binding: Binding) -> Graph {
+    Graph::Nominate(binding)
+}
+
+/// `(binding_1, binding_2)`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = edgeAnon))]
+pub fn edge_anon(binding_1: Binding, binding_2: Binding) -> Graph {
+    Graph::EdgeAnon(GEdgeAnon {
+        binding_1,
+        binding_2,
+    })
+}
+
+/// `name(binding_1, binding_2)`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = edgeNamed))]
+pub fn edge_named(name: Name, binding_1: Binding, binding_2: Binding) -> Graph {
+    Graph::EdgeNamed(GEdgeNamed {
+        binding_1,
+        binding_2,
+        name,
+    })
+}
+
+/// `[= graph_1 graph_2]`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = ruleAnon))]
+pub fn rule_anon(graph_1: Graph, graph_2: Graph) -> Graph {
+    Graph::RuleAnon(GRuleAnon {
+        graph_1: Box::new(graph_1),
+        graph_2: Box::new(graph_2),
+    })
+}
+
+/// `name [= graph_1 graph_2]`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = ruleNamed))]
+pub fn rule_named(name: Name, graph_1: Graph, graph_2: Graph) -> Graph {
+    Graph::RuleNamed(GRuleNamed {
+        graph_1: Box::new(graph_1),
+        graph_2: Box::new(graph_2),
+        name,
+    })
+}
+
+/// `let var = graph_1 in graph_2`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn subgraph(var: String, graph_1: Graph, graph_2: Graph) -> Graph {
+    Graph::Subgraph(GraphBinding {
+        graph_1: Box::new(graph_1),
+        graph_2: Box::new(graph_2),
+        var,
+    })
+}
+
+/// `graph_1 * graph_2`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn tensor(graph_1: Graph, graph_2: Graph) -> Graph {
+    Graph::Tensor(GTensor {
+        graph_1: Box::new(graph_1),
+        graph_2: Box::new(graph_2),
+    })
+}
+
+/// `context "string" for name in graph`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn context(string: String, name: Name, graph: Graph) -> Graph {
+    Graph::Context(GContext {
+        graph: Box::new(graph),
+        name,
+        string,
+    })
+}
+
+/// `_`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn wildcard() -> Name {
+    Name::Wildcard
+}
+
+/// A lowercase variable name.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn vvar(value: String) -> Name {
+    Name::VVar { value }
+}
+
+/// An uppercase variable name.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
+pub fn gvar(value: String) -> Name {
+    Name::GVar { value }
+}
+
+/// `@{graph}`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = quoteGraph))]
+pub fn quote_graph(graph: Graph) -> Name {
+    Name::QuoteGraph {
+        value: Box::new(graph),
+    }
+}
+
+/// `@<name>`.
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = quoteVertex))]
+pub fn quote_vertex(name: Name) -> Name {
+    Name::QuoteVertex {
+        value: Box::new(Vertex { name }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        binding,
+        context,
+        edge_named,
+        gvar,
+        nil,
+        nominate,
+        quote_graph,
+        quote_vertex,
+        rule_anon,
+        subgraph,
+        tensor,
+        var,
+        vertex,
+        vvar,
+        wildcard,
+    };
+    use crate::{ast_to_graphl, parse_to_ast};
+
+    #[test]
+    fn test_constructors_round_trip_through_graphl() {
+        let graph = tensor(
+            edge_named(
+                gvar("E".into()),
+                binding("a".into(), vvar("a".into()), var("x".into(), nil())),
+                binding("b".into(), quote_graph(vertex(wildcard(), nil())), nil()),
+            ),
+            context(
+                "ctx".into(),
+                vvar("c".into()),
+                subgraph(
+                    "G".into(),
+                    vertex(quote_vertex(vvar("v".into())), nil()),
+                    rule_anon(
+                        nominate(binding("d".into(), vvar("d".into()), nil())),
+                        nil(),
+                    ),
+                ),
+            ),
+        );
+
+        assert_eq!(graph.validate_name_namespaces(), Ok(()));
+        let graphl = ast_to_graphl(graph.clone()).unwrap();
+        assert_eq!(parse_to_ast(graphl).unwrap(), graph);
+    }
+}
@@ -0,0 +1,477 @@
+//! Graphviz DOT export.
+//!
+//! Every vertex becomes a DOT node identified by its name, and every edge
+//! `(let x = <a> in ..., let y = <b> in ...)` becomes a DOT edge `a -> b`.
+//! Named edges carry their name as the edge label. The variable of every
+//! binding becomes a node labelled `x`, linked to the node of its vertex by
+//! a dotted line.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::iter;
+
+use crate::ast::{
+    Binding,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GVertex,
+    Graph,
+    Name,
+    NodeKind,
+    Vertex,
+};
+use crate::printer::{LineEnding, PrintOptions, Printer};
+use crate::visitor::Visitor;
+use crate::walker::Walker;
+
+type Attributes = Vec<(String, String)>;
+
+/// Styling for [`to_dot_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotOptions {
+    /// Graphviz attributes added to the statements generated for each node
+    /// kind: [`NodeKind::Vertex`] and [`NodeKind::Nominate`] style the nodes
+    /// of process and binding vertices, [`NodeKind::Var`] those of binding
+    /// variables, and [`NodeKind::EdgeAnon`] and [`NodeKind::EdgeNamed`]
+    /// style DOT edges.
+    pub attributes: BTreeMap<NodeKind, Attributes>,
+    /// Whether `context "key=value, ..." for n in g` adds its `key=value`
+    /// pairs as attributes of the node for `n`.
+    pub context_attributes: bool,
+    /// Line break written after every line but the last.
+    pub line_ending: LineEnding,
+}
+
+impl Default for DotOptions {
+    /// Box-shaped vertices, diamond arrowheads and dashed named edges, with
+    /// context attributes enabled.
+    fn default() -> Self {
+        let attributes = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+                .collect()
+        };
+
+        Self {
+            attributes: BTreeMap::from([
+                (NodeKind::Vertex, attributes(&[("shape", "box")])),
+                (NodeKind::Nominate, attributes(&[("shape", "box")])),
+                (NodeKind::EdgeAnon, attributes(&[("arrowhead", "diamond")])),
+                (
+                    NodeKind::EdgeNamed,
+                    attributes(&[("arrowhead", "diamond"), ("style", "dashed")]),
+                ),
+            ]),
+            context_attributes: true,
+            line_ending: LineEnding::Lf,
+        }
+    }
+}
+
+/// The vertices and edges of a graph, as shared by the DOT and Mermaid
+/// exporters.
+#[derive(Default)]
+pub(crate) struct Dot<'a> {
+    /// The name and kind of every vertex occurrence, in pre-order.
+    pub(crate) vertices: Vec<(String, NodeKind)>,
+    /// The variable of every binding with the index of its vertex, in order.
+    pub(crate) variables: Vec<(&'a str, usize)>,
+    /// The vertex indices, kind and label of every edge, in order.
+    pub(crate) edges: Vec<(usize, usize, NodeKind, Option<String>)>,
+    /// The vertex index of every binding.
+    bindings: Vec<(&'a Binding, usize)>,
+    /// Edges whose bindings have not been visited yet.
+    pending: Vec<(&'a Binding, &'a Binding, NodeKind, Option<String>)>,
+    bound: BTreeMap<&'a str, String>,
+    contexts: Vec<(&'a Name, &'a str)>,
+}
+
+impl<'a> Dot<'a> {
+    fn add_vertex(&mut self, vertex: &Vertex, kind: NodeKind) -> String {
+        let name = name_text(&vertex.name);
+        self.vertices.push((name.clone(), kind));
+        name
+    }
+
+    /// The vertex names with the kind of their first occurrence, in order.
+    fn nodes(&self) -> Vec<(&str, NodeKind)> {
+        let mut nodes: Vec<(&str, NodeKind)> = Vec::new();
+        for (name, kind) in &self.vertices {
+            if !nodes.iter().any(|(node, _)| node == name) {
+                nodes.push((name, *kind));
+            }
+        }
+        nodes
+    }
+
+    /// Resolves the bindings of the edges to the vertices visited for them.
+    fn link_edges(&mut self) {
+        let vertex = |bindings: &[(&Binding, usize)], binding: &Binding| {
+            bindings
+                .iter()
+                .find(|(visited, _)| std::ptr::eq(*visited, binding))
+                .map(|&(_, index)| index)
+                .expect("the walker visits the bindings of every edge")
+        };
+        for (binding_1, binding_2, kind, label) in std::mem::take(&mut self.pending) {
+            let from = vertex(&self.bindings, binding_1);
+            let to = vertex(&self.bindings, binding_2);
+            self.edges.push((from, to, kind, label));
+        }
+    }
+}
+
+struct DotVisitor;
+
+impl<'a> Visitor<'a, Dot<'a>, Infallible> for DotVisitor {
+    fn visit_vertex(&self, mut acc: Dot<'a>, vertex: &'a GVertex) -> Result<Dot<'a>, Infallible> {
+        acc.add_vertex(&vertex.vertex, NodeKind::Vertex);
+        Ok(acc)
+    }
+
+    fn visit_nominate(
+        &self,
+        mut acc: Dot<'a>,
+        binding: &'a Binding,
+    ) -> Result<Dot<'a>, Infallible> {
+        let name = acc.add_vertex(&binding.vertex, NodeKind::Nominate);
+        let vertex = acc.vertices.len() - 1;
+        acc.bindings.push((binding, vertex));
+        acc.variables.push((&binding.var, vertex));
+        acc.bound.insert(&binding.var, name);
+        Ok(acc)
+    }
+
+    fn visit_edge_anon(
+        &self,
+        mut acc: Dot<'a>,
+        edge: &'a GEdgeAnon,
+    ) -> Result<Dot<'a>, Infallible> {
+        acc.pending
+            .push((&edge.binding_1, &edge.binding_2, NodeKind::EdgeAnon, None));
+        Ok(acc)
+    }
+
+    fn visit_edge_named(
+        &self,
+        mut acc: Dot<'a>,
+        edge: &'a GEdgeNamed,
+    ) -> Result<Dot<'a>, Infallible> {
+        let label = name_text(&edge.name);
+        acc.pending.push((
+            &edge.binding_1,
+            &edge.binding_2,
+            NodeKind::EdgeNamed,
+            Some(label),
+        ));
+        Ok(acc)
+    }
+
+    fn visit_context(
+        &self,
+        mut acc: Dot<'a>,
+        context: &'a GContext,
+    ) -> Result<Dot<'a>, Infallible> {
+        acc.contexts.push((&context.name, &context.string));
+        Ok(acc)
+    }
+}
+
+/// Collects the vertices and edges of `graph`.
+pub(crate) fn collect(graph: &Graph) -> Dot<'_> {
+    let mut dot = Walker::new(graph).visit(Dot::default(), DotVisitor);
+    dot.link_edges();
+    dot
+}
+
+/// Renders `graph` as a DOT `digraph` with the default [`DotOptions`].
+pub fn to_dot(graph: &Graph) -> String {
+    to_dot_with(graph, &DotOptions::default())
+}
+
+/// Renders `graph` as a DOT `digraph` styled by `opts`.
+///
+/// With [`DotOptions::context_attributes`] enabled, the context's name is
+/// resolved to a vertex either directly (`for a`, with a vertex `<a>`) or
+/// through the variable of a binding (`for x`, with `let x = <a> in ...`).
+/// Contexts for names without a node are ignored.
+pub fn to_dot_with(graph: &Graph, opts: &DotOptions) -> String {
+    let dot = collect(graph);
+    let nodes = dot.nodes();
+
+    let mut context_attributes: BTreeMap<&str, Attributes> = BTreeMap::new();
+    if opts.context_attributes {
+        for (name, string) in &dot.contexts {
+            let (Name::VVar { value } | Name::GVar { value }) = name else {
+                continue;
+            };
+            let target = dot
+                .bound
+                .get(value.as_str())
+                .map_or(value.as_str(), String::as_str);
+            if nodes.iter().any(|&(node, _)| node == target) {
+                context_attributes
+                    .entry(target)
+                    .or_default()
+                    .extend(parse_attributes(string));
+            }
+        }
+    }
+
+    let eol = opts.line_ending.as_str();
+    let mut out = format!("digraph {{{eol}");
+    for &(node, kind) in &nodes {
+        let attributes = kind_attributes(opts, kind)
+            .chain(context_attributes.get(node).into_iter().flatten().cloned());
+        write!(out, "    {}{};{eol}", id(node), attribute_list(attributes)).unwrap();
+    }
+    // Variables are told apart from vertices by an id no name prints as, and
+    // from each other by the vertex they name.
+    let mut variables: Vec<(String, &str, &str)> = Vec::new();
+    for &(var, vertex) in &dot.variables {
+        let vertex = dot.vertices[vertex].0.as_str();
+        let node = format!("let {var} = {vertex}");
+        if !variables.iter().any(|(other, _, _)| *other == node) {
+            variables.push((node, var, vertex));
+        }
+    }
+    for (node, var, _) in &variables {
+        let attributes = iter::once(("label".to_owned(), (*var).to_owned()))
+            .chain(kind_attributes(opts, NodeKind::Var));
+        write!(out, "    {}{};{eol}", id(node), attribute_list(attributes)).unwrap();
+    }
+    for (node, _, vertex) in &variables {
+        write!(
+            out,
+            "    {} -> {} [style=dotted, arrowhead=none];{eol}",
+            id(node),
+            id(vertex)
+        )
+        .unwrap();
+    }
+    for &(from, to, kind, ref label) in &dot.edges {
+        let attributes = label
+            .iter()
+            .map(|label| ("label".to_owned(), label.clone()))
+            .chain(kind_attributes(opts, kind));
+        write!(
+            out,
+            "    {} -> {}{};{eol}",
+            id(&dot.vertices[from].0),
+            id(&dot.vertices[to].0),
+            attribute_list(attributes)
+        )
+        .unwrap();
+    }
+    out.push('}');
+    out
+}
+
+fn kind_attributes(
+    opts: &DotOptions,
+    kind: NodeKind,
+) -> impl Iterator<Item = (String, String)> + '_ {
+    opts.attributes.get(&kind).into_iter().flatten().cloned()
+}
+
+fn name_text(name: &Name) -> String {
+    let opts = PrintOptions::default();
+    let mut printer = Printer::new(&opts);
+    printer.name(name);
+    printer.finish()
+}
+
+/// Splits a `key=value, key=value` string. `;` also separates pairs, values
+/// may be wrapped in double quotes and entries without `=` are skipped.
+fn parse_attributes(string: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    string.split([',', ';']).filter_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        let key = key.trim();
+        (!key.is_empty()).then(|| (key.to_owned(), value.trim().trim_matches('"').to_owned()))
+    })
+}
+
+fn attribute_list(attributes: impl Iterator<Item = (String, String)>) -> String {
+    let attributes: Vec<_> = attributes
+        .map(|(key, value)| format!("{}={}", id(&key), id(&value)))
+        .collect();
+
+    if attributes.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", attributes.join(", "))
+    }
+}
+
+/// DOT keywords, which are reserved in any case and must be quoted to be
+/// used as ids.
+const KEYWORDS: [&str; 6] = ["node", "edge", "graph", "digraph", "subgraph", "strict"];
+
+/// Quotes `s` unless it is already a plain DOT identifier or number.
+fn id(s: &str) -> Cow<'_, str> {
+    let plain = s
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS
+            .iter()
+            .any(|keyword| keyword.eq_ignore_ascii_case(s));
+    let number = !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    if plain || number {
+        Cow::Borrowed(s)
+    } else {
+        Cow::Owned(format!(
+            "\"{}\"",
+            s.replace('\\', "\\\\").replace('"', "\\\"")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DotOptions, to_dot, to_dot_with};
+    use crate::ast::NodeKind;
+    use crate::parse_to_ast;
+    use crate::printer::LineEnding;
+
+    #[test]
+    fn test_to_dot_edge() {
+        let graph =
+            parse_to_ast("(let a = <a> in <a> | 0, let b = <b> in <b> | 0)".into()).unwrap();
+
+        assert_eq!(
+            to_dot(&graph),
+            concat!(
+                "digraph {\n",
+                "    a [shape=box];\n",
+                "    b [shape=box];\n",
+                "    \"let a = a\" [label=a];\n",
+                "    \"let b = b\" [label=b];\n",
+                "    \"let a = a\" -> a [style=dotted, arrowhead=none];\n",
+                "    \"let b = b\" -> b [style=dotted, arrowhead=none];\n",
+                "    a -> b [arrowhead=diamond];\n",
+                "}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_dot_binding_vertices_and_edge_styles() {
+        let anon = to_dot(
+            &parse_to_ast("(let a = <a> in <a> | 0, let b = <b> in <b> | 0)".into()).unwrap(),
+        );
+
+        assert!(anon.starts_with("digraph {") && anon.ends_with('}'));
+        assert!(anon.contains("    a [shape=box];") && anon.contains("    b [shape=box];"));
+        assert_eq!(anon.matches(" -> ").count(), 3);
+        assert_eq!(anon.matches(" [style=dotted, arrowhead=none];").count(), 2);
+
+        // The vertices of bindings are nodes even when nothing else mentions
+        // them, their variables are labelled nodes linked to them, and named
+        // edges are dashed.
+        let named = to_dot(
+            &parse_to_ast("e(let a = <x> in 0, let b = <y> in { let a = <y> in 0 })".into())
+                .unwrap(),
+        );
+        assert_eq!(
+            named,
+            concat!(
+                "digraph {\n",
+                "    x [shape=box];\n",
+                "    y [shape=box];\n",
+                "    \"let a = x\" [label=a];\n",
+                "    \"let b = y\" [label=b];\n",
+                "    \"let a = y\" [label=a];\n",
+                "    \"let a = x\" -> x [style=dotted, arrowhead=none];\n",
+                "    \"let b = y\" -> y [style=dotted, arrowhead=none];\n",
+                "    \"let a = y\" -> y [style=dotted, arrowhead=none];\n",
+                "    x -> y [label=e, arrowhead=diamond, style=dashed];\n",
+                "}"
+            )
+        );
+
+        let styled = DotOptions {
+            attributes: [(
+                NodeKind::Var,
+                vec![("shape".to_owned(), "plain".to_owned())],
+            )]
+            .into(),
+            ..DotOptions::default()
+        };
+        let graph = parse_to_ast("let x = <a> in 0".into()).unwrap();
+        assert!(to_dot_with(&graph, &styled).contains("    \"let x = a\" [label=x, shape=plain];"));
+    }
+
+    #[test]
+    fn test_to_dot_context_attributes() {
+        let graph = parse_to_ast(r#"context "color=red" for a in <a> | 0"#.into()).unwrap();
+
+        assert!(to_dot(&graph).contains("a [shape=box, color=red];"));
+
+        let plain = DotOptions {
+            context_attributes: false,
+            ..DotOptions::default()
+        };
+        assert!(!to_dot_with(&graph, &plain).contains("color"));
+    }
+
+    #[test]
+    fn test_to_dot_context_for_binding_variable() {
+        let graph = parse_to_ast(
+            r#"context "fillcolor = \"light blue\"; style=filled" for x in n(let x = <a> in 0, let y = <b> in 0)"#
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_dot(&graph),
+            concat!(
+                "digraph {\n",
+                "    a [shape=box, fillcolor=\"light blue\", style=filled];\n",
+                "    b [shape=box];\n",
+                "    \"let x = a\" [label=x];\n",
+                "    \"let y = b\" [label=y];\n",
+                "    \"let x = a\" -> a [style=dotted, arrowhead=none];\n",
+                "    \"let y = b\" -> b [style=dotted, arrowhead=none];\n",
+                "    a -> b [label=n, arrowhead=diamond, style=dashed];\n",
+                "}"
+            )
+        );
+    }
+
+    #[test]
+    fn test_to_dot_quotes_keywords() {
+        let graph = parse_to_ast(
+            "Node(let graph = <graph> in 0, let x = <EDGE> in 0) * <Strict> | <nodes> | 0".into(),
+        )
+        .unwrap();
+        let dot = to_dot(&graph);
+
+        assert!(
+            dot.contains("    \"graph\" -> \"EDGE\" [label=\"Node\","),
+            "{dot}"
+        );
+        assert!(dot.contains("    \"Strict\" [shape=box];"), "{dot}");
+        assert!(dot.contains("    nodes [shape=box];"), "{dot}");
+    }
+
+    #[test]
+    fn test_to_dot_crlf() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+        let opts = DotOptions {
+            line_ending: LineEnding::CrLf,
+            ..DotOptions::default()
+        };
+
+        assert_eq!(
+            to_dot_with(&graph, &opts),
+            "digraph {\r\n    a [shape=box];\r\n}"
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Conversions of the AST into representations used by other tools.
+
+#[cfg(feature = "color")]
+mod ansi;
+pub mod mermaid;
+mod sexpr;
+#[cfg(feature = "indextree")]
+mod tree;
+
+#[cfg(feature = "color")]
+pub use self::ansi::to_ansi;
+pub use self::mermaid::{to_mermaid, to_mermaid_with};
+pub use self::sexpr::{to_sexpr, write_sexpr};
+#[cfg(feature = "indextree")]
+pub use self::tree::{NodeLabel, to_indextree};
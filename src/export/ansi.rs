@@ -0,0 +1,66 @@
+use crate::ast::Graph;
+use crate::printer::{PrintOptions, Printer, Token};
+
+const RESET: &str = "\x1b[0m";
+
+/// The SGR sequence each token category is colored with.
+fn color(token: Token) -> &'static str {
+    match token {
+        Token::Keyword => "\x1b[1;35m",
+        Token::Variable => "\x1b[36m",
+        Token::Vertex => "\x1b[33m",
+        Token::String => "\x1b[32m",
+    }
+}
+
+fn highlight(token: Token, s: &str, out: &mut String) {
+    out.push_str(color(token));
+    out.push_str(s);
+    out.push_str(RESET);
+}
+
+/// Prints `graph` like [`Graph::print`] with default options, coloring
+/// keywords, variables, vertex names and strings with ANSI escape sequences.
+///
+/// Removing every escape sequence from the result gives back the plain
+/// output of [`Graph::print`].
+pub fn to_ansi(graph: &Graph) -> String {
+    let opts = PrintOptions::default();
+    let mut printer = Printer::new(&opts).with_style(highlight);
+    printer.graph(graph, 0);
+    printer.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::to_ansi;
+    use crate::parse_to_ast;
+    use crate::printer::PrintOptions;
+
+    /// Removes `ESC [ ... m` sequences.
+    fn strip(s: &str) -> String {
+        let mut out = String::new();
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|&c| c == 'm');
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_to_ansi_colors_keywords() {
+        let graph =
+            parse_to_ast(r#"context "s" for f in (let a = <a> in x | 0, let b = <b> in 0)"#.into())
+                .unwrap();
+        let colored = to_ansi(&graph);
+
+        assert!(colored.starts_with("\x1b[1;35mcontext\x1b[0m \x1b[32m\"s\"\x1b[0m"));
+        assert!(colored.contains("\x1b[1;35mlet\x1b[0m \x1b[36ma\x1b[0m = < \x1b[33ma\x1b[0m >"));
+        assert!(colored.contains("\x1b[1;35min\x1b[0m"));
+        assert_eq!(strip(&colored), graph.print(&PrintOptions::default()));
+    }
+}
@@ -0,0 +1,139 @@
+//! Mermaid flowchart export, for diagrams embedded in Markdown.
+//!
+//! Also reachable as `graphl_parser::mermaid`, next to [`crate::dot`].
+
+use std::fmt::Write;
+
+use crate::ast::{Graph, NodeKind};
+use crate::dot;
+use crate::printer::LineEnding;
+
+/// Renders `graph` as a Mermaid `graph TD` flowchart.
+///
+/// Every occurrence of a vertex is a node, numbered `n0`, `n1`, ... in the
+/// [`Walker`](crate::Walker) pre-order of the DOT exporter and labelled with
+/// its name, so two `<a>` give two nodes `a`. Vertices of bindings are drawn
+/// with rounded ends. Every edge is a link between the vertices of its two
+/// bindings, labelled with the edge's name if it has one.
+///
+/// Node ids come from the position of a vertex rather than from its name, so
+/// they are unique and valid Mermaid ids whatever the names are spelled
+/// like, and the same graph always gives the same output.
+pub fn to_mermaid(graph: &Graph) -> String {
+    to_mermaid_with(graph, LineEnding::Lf)
+}
+
+/// Renders `graph` like [`to_mermaid`], breaking lines with `line_ending`.
+pub fn to_mermaid_with(graph: &Graph, line_ending: LineEnding) -> String {
+    let dot = dot::collect(graph);
+
+    let eol = line_ending.as_str();
+    let mut out = format!("graph TD{eol}");
+    for (i, (name, kind)) in dot.vertices.iter().enumerate() {
+        let label = text(name);
+        match kind {
+            NodeKind::Nominate => write!(out, "    n{i}([\"{label}\"]){eol}"),
+            _ => write!(out, "    n{i}[\"{label}\"]{eol}"),
+        }
+        .unwrap();
+    }
+    for (from, to, _, label) in &dot.edges {
+        let link = match label {
+            Some(label) => format!("-->|\"{}\"|", text(label)),
+            None => "-->".to_owned(),
+        };
+        write!(out, "    n{from} {link} n{to}{eol}").unwrap();
+    }
+    out.truncate(out.len() - eol.len());
+    out
+}
+
+/// Escapes `s` for use inside a quoted Mermaid label.
+fn text(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_mermaid, to_mermaid_with};
+    use crate::parse_to_ast;
+    use crate::printer::LineEnding;
+
+    #[test]
+    fn test_to_mermaid_vertices() {
+        let graph = parse_to_ast("<a> | <b> | 0".into()).unwrap();
+
+        assert_eq!(to_mermaid(&graph), "graph TD\n    n0[\"a\"]\n    n1[\"b\"]");
+    }
+
+    #[test]
+    fn test_to_mermaid_crlf() {
+        let graph = parse_to_ast("(let a = <a> in 0, let b = <b> in 0)".into()).unwrap();
+
+        assert_eq!(
+            to_mermaid_with(&graph, LineEnding::CrLf),
+            "graph TD\r\n    n0([\"a\"])\r\n    n1([\"b\"])\r\n    n0 --> n1"
+        );
+        assert_eq!(to_mermaid_with(&graph, LineEnding::Lf), to_mermaid(&graph));
+    }
+
+    #[test]
+    fn test_to_mermaid_tensor_counts() {
+        let graph = parse_to_ast(
+            "(let a = <a> in <a> | 0, let b = <b> in 0) * { <b> | <@{0}> | 0 * n(let c = <c> in 0, let d = <a> in 0) }"
+                .into(),
+        )
+        .unwrap();
+        let mermaid = crate::mermaid::to_mermaid(&graph);
+        let lines: Vec<_> = mermaid.lines().collect();
+
+        assert_eq!(mermaid, to_mermaid(&graph));
+        assert_eq!(lines[0], "graph TD");
+        let nodes = lines
+            .iter()
+            .filter(|line| line.ends_with(']') || line.ends_with(')'));
+        assert_eq!(nodes.count(), 7);
+        assert_eq!(lines.iter().filter(|line| line.contains("-->")).count(), 2);
+        assert_eq!(lines.len(), 10);
+    }
+
+    #[test]
+    fn test_to_mermaid_repeated_names_are_distinct_nodes() {
+        let graph =
+            parse_to_ast("<a> | 0 * (let x = <a> in 0, let y = <b> in <a> | 0)".into()).unwrap();
+
+        assert_eq!(
+            to_mermaid(&graph).lines().collect::<Vec<_>>(),
+            [
+                "graph TD",
+                "    n0[\"a\"]",
+                "    n1([\"a\"])",
+                "    n2([\"b\"])",
+                "    n3[\"a\"]",
+                "    n1 --> n2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_mermaid_edges() {
+        let graph = parse_to_ast(
+            "{ (let a = <a> in 0, let b = <b> in 0) * e(let x = <b> in 0, let y = <@<c>> in 0) }"
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            to_mermaid(&graph).lines().collect::<Vec<_>>(),
+            [
+                "graph TD",
+                "    n0([\"a\"])",
+                "    n1([\"b\"])",
+                "    n2([\"b\"])",
+                "    n3([\"@ < c >\"])",
+                "    n0 --> n1",
+                "    n2 -->|\"e\"| n3",
+            ]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt::{self, Write};
+
+use crate::ast::node::Node;
+use crate::ast::{Graph, Name};
+use crate::printer::write_escaped;
+
+enum Item<'a> {
+    Node(Node<'a>),
+    Text(&'static str),
+    Ident(&'a str),
+    String(&'a str),
+    /// A constructor that has no [`Node`] of its own, like `GBind`.
+    Constructor(&'static str, Vec<Item<'a>>),
+}
+
+/// Renders `graph` as an S-expression.
+///
+/// The format is the one of the `show*` functions generated by BNFC: every
+/// constructor is written as `(Constructor arg ...)` using the grammar's
+/// label (`GVertex`, `VBind`, `NameVVar`, ...), constructors without
+/// arguments are bare atoms (`GNil`, `NameWildcard`) and identifiers and
+/// strings are double-quoted.
+pub fn to_sexpr(graph: &Graph) -> String {
+    let mut out = String::new();
+    write_sexpr(graph, &mut out).expect("writing to a String cannot fail");
+    out
+}
+
+/// Streams the S-expression of [`to_sexpr`] into `w` without building the
+/// whole string first.
+///
+/// # Errors
+///
+/// Returns the first error reported by `w`.
+pub fn write_sexpr<W: Write>(graph: &Graph, w: &mut W) -> fmt::Result {
+    let mut stack = vec![Item::Node(Node::Graph(graph))];
+
+    while let Some(item) = stack.pop() {
+        match item {
+            Item::Node(node) => match expand(node) {
+                Ok(atom) => w.write_str(atom)?,
+                Err((label, args)) => stack.push(Item::Constructor(label, args)),
+            },
+            Item::Constructor(label, args) => {
+                write!(w, "({label}")?;
+                stack.push(Item::Text(")"));
+                for arg in args.into_iter().rev() {
+                    stack.push(arg);
+                    stack.push(Item::Text(" "));
+                }
+            }
+            Item::Text(text) => w.write_str(text)?,
+            Item::Ident(ident) => write!(w, "\"{ident}\"")?,
+            Item::String(string) => {
+                w.write_char('"')?;
+                write_escaped(w, string)?;
+                w.write_char('"')?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns either the atom for `node` or its constructor label and arguments.
+fn expand(node: Node<'_>) -> Result<&'static str, (&'static str, Vec<Item<'_>>)> {
+    let graph = |graph| Item::Node(Node::Graph(graph));
+    let binding = |binding| Item::Node(Node::Binding(binding));
+    let vertex = |vertex| Item::Node(Node::Vertex(vertex));
+    let name = |name| Item::Node(Node::Name(name));
+
+    Err(match node {
+        Node::Graph(Graph::Nil) => return Ok("GNil"),
+        Node::Graph(Graph::Vertex(v)) => ("GVertex", vec![vertex(&v.vertex), graph(&v.graph)]),
+        Node::Graph(Graph::Var(v)) => ("GVar", vec![Item::Ident(&v.var), graph(&v.graph)]),
+        Node::Graph(Graph::Nominate(b)) => ("GNominate", vec![binding(b)]),
+        Node::Graph(Graph::EdgeAnon(e)) => (
+            "GEdgeAnon",
+            vec![binding(&e.binding_1), binding(&e.binding_2)],
+        ),
+        Node::Graph(Graph::EdgeNamed(e)) => (
+            "GEdgeNamed",
+            vec![name(&e.name), binding(&e.binding_1), binding(&e.binding_2)],
+        ),
+        Node::Graph(Graph::RuleAnon(r)) => {
+            ("GRuleAnon", vec![graph(&r.graph_1), graph(&r.graph_2)])
+        }
+        Node::Graph(Graph::RuleNamed(r)) => (
+            "GRuleNamed",
+            vec![name(&r.name), graph(&r.graph_1), graph(&r.graph_2)],
+        ),
+        Node::Graph(Graph::Subgraph(b)) => (
+            "GSubgraph",
+            vec![Item::Constructor(
+                "GBind",
+                vec![Item::Ident(&b.var), graph(&b.graph_1), graph(&b.graph_2)],
+            )],
+        ),
+        Node::Graph(Graph::Tensor(t)) => ("GTensor", vec![graph(&t.graph_1), graph(&t.graph_2)]),
+        Node::Graph(Graph::Context(c)) => (
+            "GContext",
+            vec![Item::String(&c.string), name(&c.name), graph(&c.graph)],
+        ),
+        Node::Binding(b) => (
+            "VBind",
+            vec![Item::Ident(&b.var), vertex(&b.vertex), graph(&b.graph)],
+        ),
+        Node::Vertex(v) => ("VName", vec![name(&v.name)]),
+        Node::Name(Name::Wildcard) => return Ok("NameWildcard"),
+        Node::Name(Name::VVar { value }) => ("NameVVar", vec![Item::Ident(value)]),
+        Node::Name(Name::GVar { value }) => ("NameGVar", vec![Item::Ident(value)]),
+        Node::Name(Name::QuoteGraph { value }) => ("NameQuoteGraph", vec![graph(value)]),
+        Node::Name(Name::QuoteVertex { value }) => ("NameQuoteVertex", vec![vertex(value)]),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CStr;
+
+    use super::{to_sexpr, write_sexpr};
+    use crate::ast::Graph;
+    use crate::guard::Guard;
+    use crate::{bindings, parse_to_ast};
+
+    fn show_graph(graph: Graph) -> String {
+        let graph: Guard<_> = graph.try_into().unwrap();
+        let shown = unsafe { bindings::showGraph(*graph) };
+        scopeguard::defer!(unsafe { bindings::bufReset() });
+
+        unsafe { CStr::from_ptr(shown) }
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn test_to_sexpr_matches_c_show() {
+        for code in [
+            "0",
+            "<a> | x | 0",
+            "n(let a = <_> in 0, let b = <@<c>> in 0) * [= <A> | 0 <@{<d> | 0}> | 0]",
+            r#"let G = R [= 0 0] in {context "x \"y\"" for f in 0}"#,
+        ] {
+            let graph = parse_to_ast(code.into()).unwrap();
+
+            assert_eq!(to_sexpr(&graph), show_graph(graph), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_write_sexpr_into_string() {
+        let graph = parse_to_ast("(let a = <a> in <a> | 0, let b = <b> in 0)".into()).unwrap();
+        let mut out = String::from("sexpr: ");
+
+        write_sexpr(&graph, &mut out).unwrap();
+
+        assert_eq!(out, format!("sexpr: {}", to_sexpr(&graph)));
+    }
+}
@@ -0,0 +1,122 @@
+use indextree::{Arena, NodeId};
+
+use crate::ast::node::Node;
+use crate::ast::{Graph, Name, NodeKind};
+
+/// The payload stored in each node of the tree built by [`to_indextree`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum NodeLabel {
+    /// A graph node. `text` holds the variable of `x | g` and `let X = ...`,
+    /// or the string of a context.
+    Graph {
+        kind: NodeKind,
+        text: Option<String>,
+    },
+    /// A `let x = <v> in g` binding.
+    Binding { var: String },
+    /// A vertex `<name>`; its name is the only child.
+    Vertex,
+    /// A name. Variables carry their spelling and the wildcard is `"_"`;
+    /// quoted names have no text and hold the quoted node as their child.
+    Name { text: Option<String> },
+}
+
+impl NodeLabel {
+    fn of(node: Node<'_>) -> Self {
+        match node {
+            Node::Graph(graph) => {
+                let text = match graph {
+                    Graph::Var(var) => Some(var.var.clone()),
+                    Graph::Subgraph(binding) => Some(binding.var.clone()),
+                    Graph::Context(context) => Some(context.string.clone()),
+                    _ => None,
+                };
+                NodeLabel::Graph {
+                    kind: graph.kind(),
+                    text,
+                }
+            }
+            Node::Binding(binding) => NodeLabel::Binding {
+                var: binding.var.clone(),
+            },
+            Node::Vertex(_) => NodeLabel::Vertex,
+            Node::Name(name) => NodeLabel::Name {
+                text: match name {
+                    Name::Wildcard => Some("_".into()),
+                    Name::VVar { value } | Name::GVar { value } => Some(value.clone()),
+                    Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => None,
+                },
+            },
+        }
+    }
+}
+
+/// Materializes `graph` into an [`indextree`] arena.
+///
+/// Every [`Graph`], [`Binding`](crate::ast::Binding),
+/// [`Vertex`](crate::ast::Vertex) and [`Name`] becomes one arena node, with
+/// children appended in source order. Returns the arena and the id of the
+/// root node.
+pub fn to_indextree(graph: &Graph) -> (Arena<NodeLabel>, NodeId) {
+    let mut arena = Arena::new();
+    let root = arena.new_node(NodeLabel::of(Node::Graph(graph)));
+    let mut stack = vec![(Node::Graph(graph), root)];
+
+    while let Some((node, id)) = stack.pop() {
+        for child in node.children() {
+            let child_id = arena.new_node(NodeLabel::of(child));
+            id.append(child_id, &mut arena);
+            stack.push((child, child_id));
+        }
+    }
+
+    (arena, root)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{NodeLabel, to_indextree};
+    use crate::ast::{NodeKind, THREE_EDGE_GRAPH};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_to_indextree_node_count() {
+        let graph = parse_to_ast(THREE_EDGE_GRAPH.into()).unwrap();
+
+        let (arena, _) = to_indextree(&graph);
+
+        assert_eq!(arena.len(), graph.node_count());
+    }
+
+    #[test]
+    fn test_to_indextree_labels() {
+        let graph = parse_to_ast(r#"context "foo" for f in <@<a>> | x | 0"#.into()).unwrap();
+
+        let (arena, root) = to_indextree(&graph);
+        let labels: Vec<_> = root
+            .children(&arena)
+            .map(|id| arena.get(id).unwrap().get().clone())
+            .collect();
+
+        assert_eq!(arena.len(), graph.node_count());
+        assert_eq!(
+            arena.get(root).unwrap().get(),
+            &NodeLabel::Graph {
+                kind: NodeKind::Context,
+                text: Some("foo".into()),
+            }
+        );
+        assert_eq!(
+            labels,
+            vec![
+                NodeLabel::Name {
+                    text: Some("f".into()),
+                },
+                NodeLabel::Graph {
+                    kind: NodeKind::Vertex,
+                    text: None,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! JSON serialization of the AST.
+//!
+//! The plain form follows the serde derives on the AST types. The canonical
+//! form additionally guarantees byte-reproducible output: object keys are
+//! emitted in lexicographic order at every level and no insignificant
+//! whitespace is written, so equal graphs always produce identical bytes.
+//!
+//! [`Graph::from_json`] reads the plain form back, checking the `type` tags
+//! first so that untrusted input with an unknown tag is reported as such.
+//!
+//! [`Graph::shape_json`] writes only the [`shape`](Graph::shape) of a graph,
+//! for grouping graphs by structure without exposing their names.
+//!
+//! [`diff_json_patch`](crate::ast::diff_json_patch) describes the change
+//! between the plain forms of two graphs as an RFC 6902 JSON Patch.
+
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+use crate::ast::{Error, Graph};
+
+impl Graph {
+    /// Serializes the graph to compact JSON, with keys in declaration order.
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(json_error)
+    }
+
+    /// Serializes the graph to canonical JSON suitable for content addressing.
+    ///
+    /// Keys are sorted explicitly rather than relying on the map type backing
+    /// [`serde_json::Value`], so the output does not change if another crate
+    /// in the dependency graph enables `serde_json/preserve_order`.
+    pub fn to_json_canonical(&self) -> Result<String, Error> {
+        let value = serde_json::to_value(self).map_err(json_error)?;
+
+        let mut out = String::new();
+        write_canonical(&value, &mut out)?;
+        Ok(out)
+    }
+
+    /// Serializes the [`shape`](Self::shape) of the graph to compact JSON,
+    /// e.g. `{"kind":"Vertex","children":[{"kind":"Nil","children":[]}]}`.
+    ///
+    /// The shape holds no maps, so its fields always come out in the same
+    /// order, and graphs differing only in their labels give identical
+    /// strings.
+    pub fn shape_json(&self) -> String {
+        serde_json::to_string(&self.shape()).expect("shapes serialize to JSON")
+    }
+
+    /// Deserializes a graph from the JSON written by [`Graph::to_json`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::UnknownNodeType`] if an object carries a `type` tag
+    /// that names no variant of the node expected at its position, such as a
+    /// [`Name`](crate::ast::Name) tag where a graph belongs, and
+    /// [`Error::Json`] for any other malformed input.
+    pub fn from_json(json: &str) -> Result<Graph, Error> {
+        let value: Value = serde_json::from_str(json).map_err(json_error)?;
+        Graph::from_json_value(&value)
+    }
+
+    /// Like [`from_json`](Self::from_json), for JSON that has already been
+    /// parsed into a [`Value`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`from_json`](Self::from_json).
+    pub fn from_json_value(value: &Value) -> Result<Graph, Error> {
+        check_tags(value, Expected::Graph)?;
+        Graph::deserialize(value).map_err(json_error)
+    }
+}
+
+/// The `type` tags of [`Graph`] variants.
+const GRAPH_TAGS: [&str; 11] = [
+    "Nil",
+    "Vertex",
+    "Var",
+    "Nominate",
+    "EdgeAnon",
+    "EdgeNamed",
+    "RuleAnon",
+    "RuleNamed",
+    "Subgraph",
+    "Tensor",
+    "Context",
+];
+
+/// The `type` tags of [`Name`](crate::ast::Name) variants.
+const NAME_TAGS: [&str; 5] = ["Wildcard", "VVar", "GVar", "QuoteGraph", "QuoteVertex"];
+
+/// The AST type a JSON object is read as.
+#[derive(Clone, Copy)]
+enum Expected {
+    Graph,
+    Binding,
+    Vertex,
+    Name,
+}
+
+/// Checks the `type` tag of every object against the type it is read as,
+/// following the field names of the AST. Objects of the wrong shape are left
+/// for serde to report.
+fn check_tags(value: &Value, expected: Expected) -> Result<(), Error> {
+    let Value::Object(map) = value else {
+        return Ok(());
+    };
+    let tag = match map.get("type") {
+        Some(Value::String(tag)) => Some(tag.as_str()),
+        _ => None,
+    };
+    let tags: &[&str] = match expected {
+        Expected::Graph => &GRAPH_TAGS,
+        Expected::Name => &NAME_TAGS,
+        Expected::Binding | Expected::Vertex => &[],
+    };
+    if let Some(tag) = tag
+        && !tags.is_empty()
+        && !tags.contains(&tag)
+    {
+        return Err(Error::UnknownNodeType {
+            tag: tag.to_owned(),
+        });
+    }
+
+    for (key, field) in map {
+        let expected = match (expected, key.as_str(), tag) {
+            (Expected::Name, "value", Some("QuoteGraph")) => Expected::Graph,
+            (Expected::Name, "value", Some("QuoteVertex")) => Expected::Vertex,
+            (_, "graph" | "graph_1" | "graph_2", _) => Expected::Graph,
+            (_, "binding_1" | "binding_2", _) => Expected::Binding,
+            (_, "vertex", _) => Expected::Vertex,
+            (_, "name", _) => Expected::Name,
+            _ => continue,
+        };
+        check_tags(field, expected)?;
+    }
+    Ok(())
+}
+
+/// Returns an RFC 6902 JSON Patch that turns the JSON of `a`, as written by
+/// [`Graph::to_json`], into the JSON of `b`.
+///
+/// The two values are compared structurally: nodes of the same variant are
+/// patched field by field, and everything else that differs is replaced
+/// whole, so the patch only touches the subtrees that actually changed. Equal
+/// graphs give an empty patch.
+pub fn diff_json_patch(a: &Graph, b: &Graph) -> Value {
+    let a = serde_json::to_value(a).expect("graphs serialize to JSON");
+    let b = serde_json::to_value(b).expect("graphs serialize to JSON");
+
+    let mut ops = Vec::new();
+    diff_values(&a, &b, &mut String::new(), &mut ops);
+    Value::Array(ops)
+}
+
+fn diff_values(a: &Value, b: &Value, path: &mut String, ops: &mut Vec<Value>) {
+    match (a, b) {
+        _ if a == b => {}
+        (Value::Object(a), Value::Object(b)) if a.get("type") == b.get("type") => {
+            diff_objects(a, b, path, ops);
+        }
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                with_segment(path, &i.to_string(), |path| diff_values(a, b, path, ops));
+            }
+        }
+        _ => ops.push(json!({ "op": "replace", "path": path, "value": b })),
+    }
+}
+
+fn diff_objects(
+    a: &Map<String, Value>,
+    b: &Map<String, Value>,
+    path: &mut String,
+    ops: &mut Vec<Value>,
+) {
+    for (key, old) in a {
+        with_segment(path, key, |path| match b.get(key) {
+            Some(new) => diff_values(old, new, path, ops),
+            None => ops.push(json!({ "op": "remove", "path": path })),
+        });
+    }
+    for (key, new) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+        with_segment(path, key, |path| {
+            ops.push(json!({ "op": "add", "path": path, "value": new }));
+        });
+    }
+}
+
+/// Runs `f` with `segment` appended to the JSON Pointer `path`, escaped as
+/// RFC 6901 requires.
+fn with_segment(path: &mut String, segment: &str, f: impl FnOnce(&mut String)) {
+    let len = path.len();
+    path.push('/');
+    path.push_str(&segment.replace('~', "~0").replace('/', "~1"));
+    f(path);
+    path.truncate(len);
+}
+
+fn write_canonical(value: &Value, out: &mut String) -> Result<(), Error> {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out)?;
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(key).map_err(json_error)?);
+                out.push(':');
+                write_canonical(item, out)?;
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&serde_json::to_string(scalar).map_err(json_error)?),
+    }
+
+    Ok(())
+}
+
+fn json_error(err: serde_json::Error) -> Error {
+    Error::Json {
+        message: err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use crate::ast::{Error, Graph, diff_json_patch};
+    use crate::parse_to_ast;
+
+    /// Applies the `add`, `remove` and `replace` operations of a JSON Patch.
+    fn apply_patch(target: &mut Value, patch: &Value) {
+        for op in patch.as_array().unwrap() {
+            let path = op["path"].as_str().unwrap();
+            let (parent, key) = path.rsplit_once('/').unwrap_or(("", ""));
+            let key = key.replace("~1", "/").replace("~0", "~");
+            match op["op"].as_str().unwrap() {
+                "replace" => *target.pointer_mut(path).unwrap() = op["value"].clone(),
+                "add" => {
+                    let parent = target.pointer_mut(parent).unwrap();
+                    parent
+                        .as_object_mut()
+                        .unwrap()
+                        .insert(key, op["value"].clone());
+                }
+                "remove" => {
+                    let parent = target.pointer_mut(parent).unwrap();
+                    parent.as_object_mut().unwrap().remove(&key);
+                }
+                other => panic!("unexpected op {other}"),
+            }
+        }
+    }
+
+    /// 64-bit FNV-1a, spelled out so the expected digest cannot drift with
+    /// std's hasher implementation.
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    #[test]
+    fn test_canonical_json_is_byte_identical() {
+        let graph = parse_to_ast("context \"foo=bar\" for a in <a> | 0".into()).unwrap();
+
+        let first = graph.to_json_canonical().unwrap();
+        let second = graph.clone().to_json_canonical().unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(
+            first,
+            r#"{"graph":{"graph":{"type":"Nil"},"type":"Vertex","vertex":{"name":{"type":"VVar","value":"a"}}},"name":{"type":"VVar","value":"a"},"string":"foo=bar","type":"Context"}"#
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_content_hash_is_stable() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+        let json = graph.to_json_canonical().unwrap();
+
+        assert_eq!(fnv1a(json.as_bytes()), 0x3bf6_6954_d18d_fb44);
+    }
+
+    #[test]
+    fn test_from_json_reports_unknown_tag() {
+        let err = Graph::from_json(r#"{"type":"Bogus"}"#).unwrap_err();
+        assert!(matches!(err, Error::UnknownNodeType { ref tag } if tag == "Bogus"));
+
+        let graph = parse_to_ast("let x = <a> in x | 0".into()).unwrap();
+        let json = graph.to_json().unwrap();
+        assert_eq!(Graph::from_json(&json).unwrap(), graph);
+
+        let nested = json.replacen("\"VVar\"", "\"Bogus\"", 1);
+        let err = Graph::from_json(&nested).unwrap_err();
+        assert!(matches!(err, Error::UnknownNodeType { ref tag } if tag == "Bogus"));
+    }
+
+    #[test]
+    fn test_from_json_checks_tags_by_position() {
+        let err = Graph::from_json(r#"{"type":"VVar","value":"a"}"#).unwrap_err();
+        assert!(matches!(err, Error::UnknownNodeType { ref tag } if tag == "VVar"));
+
+        let json = parse_to_ast("<a> | 0".into()).unwrap().to_json().unwrap();
+        let nil_name = json.replace(r#"{"type":"VVar","value":"a"}"#, r#"{"type":"Nil"}"#);
+        let err = Graph::from_json(&nil_name).unwrap_err();
+        assert!(matches!(err, Error::UnknownNodeType { ref tag } if tag == "Nil"));
+
+        // The graph quoted by a name is checked as a graph.
+        let graph = parse_to_ast("context \"s\" for @{<a> | 0} in x | 0".into()).unwrap();
+        let json = graph.to_json().unwrap();
+        assert_eq!(Graph::from_json(&json).unwrap(), graph);
+        let quoted = json.replacen(r#"{"type":"Vertex""#, r#"{"type":"VVar""#, 1);
+        assert_ne!(quoted, json);
+        assert!(matches!(
+            Graph::from_json(&quoted),
+            Err(Error::UnknownNodeType { ref tag }) if tag == "VVar"
+        ));
+    }
+
+    #[test]
+    fn test_from_json_value() {
+        let graph =
+            parse_to_ast("n(let a = <a> in x | 0, let b = <@{<c> | 0}> in 0)".into()).unwrap();
+        let value: Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+
+        assert_eq!(Graph::from_json_value(&value).unwrap(), graph);
+        assert!(matches!(
+            Graph::from_json_value(&serde_json::json!({ "type": "Vertex" })),
+            Err(Error::Json { .. })
+        ));
+    }
+
+    #[test]
+    fn test_shape_json_ignores_labels() {
+        let a = parse_to_ast(
+            "context \"x\" for x in { <a> | x | 0 * E(let a = <a> in 0, let b = <b> in 0) }".into(),
+        )
+        .unwrap();
+        let b = parse_to_ast(
+            "context \"y\" for y in { <@<q>> | y | 0 * F(let c = <c> in 0, let d = <_> in 0) }"
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(a.shape_json(), b.shape_json());
+        assert_ne!(
+            a.shape_json(),
+            parse_to_ast("<a> | 0".into()).unwrap().shape_json()
+        );
+        assert_eq!(
+            parse_to_ast("<a> | 0".into()).unwrap().shape_json(),
+            r#"{"kind":"Vertex","children":[{"kind":"Nil","children":[]}]}"#
+        );
+    }
+
+    #[test]
+    fn test_json_patch_transforms_a_into_b() {
+        let a = parse_to_ast("let x = <a> in x | 0 * { context \"c\" for n in <n> | 0 }".into())
+            .unwrap();
+        let b = parse_to_ast("let x = <b> in x | 0 * (let y = <y> in 0, let z = <z> in 0)".into())
+            .unwrap();
+
+        let patch = diff_json_patch(&a, &b);
+        let mut json = serde_json::to_value(&a).unwrap();
+        apply_patch(&mut json, &patch);
+
+        assert_eq!(json, serde_json::to_value(&b).unwrap());
+        assert_eq!(patch.as_array().unwrap().len(), 2);
+        assert_eq!(patch[0]["path"], "/graph_1/vertex/name/value");
+        assert_eq!(diff_json_patch(&a, &a), Value::Array(vec![]));
+    }
+}
@@ -0,0 +1,267 @@
+//! A GraphL tokenizer for editors and other tools that need the tokens of a
+//! source without parsing it.
+//!
+//! It recognizes the same tokens as the BNFC-generated C lexer: symbols,
+//! the `context`, `for`, `in` and `let` keywords, `LVar` and `UVar`
+//! variables and string literals, with longest match and keywords taking
+//! precedence over variables. Whitespace and `//` and `/* */` comments are
+//! skipped. Tokens carry byte spans into the source rather than copies of
+//! their text.
+
+use std::ops::Range;
+
+use crate::ast::Error;
+
+/// The kind of a [`Token`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// `{`
+    LBrace,
+    /// `}`
+    RBrace,
+    /// `0`
+    Nil,
+    /// `|`
+    Bar,
+    /// `(`
+    LParen,
+    /// `,`
+    Comma,
+    /// `)`
+    RParen,
+    /// `[`
+    LBrack,
+    /// `=`
+    Eq,
+    /// `]`
+    RBrack,
+    /// `*`
+    Star,
+    /// `<`
+    Lt,
+    /// `>`
+    Gt,
+    /// `_`
+    Underscore,
+    /// `@`
+    At,
+    /// `context`
+    Context,
+    /// `for`
+    For,
+    /// `in`
+    In,
+    /// `let`
+    Let,
+    /// A lowercase variable: `x`, `x'`, `_x1`.
+    LVar,
+    /// An uppercase variable: `X`, `_X1`.
+    UVar,
+    /// A string literal.
+    String,
+}
+
+/// A token and the byte range it covers in the source.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// For a string literal, the range includes the quotes.
+    pub span: Range<usize>,
+}
+
+/// Splits `code` into tokens.
+///
+/// # Errors
+///
+/// Fails on the first character that starts no token, or on a string literal
+/// left open, with [`Error::InvalidToken`].
+pub fn tokenize(code: &str) -> Result<Vec<Token>, Error> {
+    tokenize_iter(code).collect()
+}
+
+/// Lazily splits `code` into tokens, lexing each one only when it is
+/// requested.
+///
+/// Yields the same items as [`tokenize`], ending after the first error.
+pub fn tokenize_iter(code: &str) -> impl Iterator<Item = Result<Token, Error>> + '_ {
+    Lexer { code, pos: 0 }
+}
+
+struct Lexer<'a> {
+    code: &'a str,
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    /// Advances past whitespace and comments. An unterminated block comment
+    /// runs to the end of the input, as in the C lexer.
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = &self.code[self.pos..];
+            let trimmed = rest.trim_start_matches([' ', '\t', '\r', '\n', '\x0C']);
+            self.pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if let Some(comment) = trimmed.strip_prefix("/*") {
+                self.pos += comment.find("*/").map_or(trimmed.len(), |end| end + 4);
+            } else {
+                return;
+            }
+        }
+    }
+
+    /// Returns the length of the string literal at the start of `rest`.
+    fn string(rest: &str) -> Option<usize> {
+        let mut escaped = false;
+        for (i, c) in rest.char_indices().skip(1) {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return Some(i + 1),
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Returns the length of the variable at the start of `rest`, preferring an
+/// uppercase variable when both match equally far.
+fn variable(rest: &[u8]) -> Option<(TokenKind, usize)> {
+    let tail = |from: usize, allowed: fn(u8) -> bool| {
+        from + rest[from..].iter().take_while(|&&b| allowed(b)).count()
+    };
+    fn word(b: u8) -> bool {
+        b.is_ascii_alphanumeric() || b == b'_' || b == b'\''
+    }
+
+    match rest[0] {
+        b if b.is_ascii_uppercase() => Some((TokenKind::UVar, tail(1, word))),
+        b if b.is_ascii_lowercase() || b == b'\'' => Some((TokenKind::LVar, tail(1, word))),
+        b'_' => {
+            let upper = tail(1, |b| !b.is_ascii_lowercase() && word(b));
+            let lower = tail(1, |b| !b.is_ascii_uppercase() && word(b));
+            match upper.max(lower) {
+                1 => None,
+                _ if upper >= lower => Some((TokenKind::UVar, upper)),
+                _ => Some((TokenKind::LVar, lower)),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn symbol(b: u8) -> Option<TokenKind> {
+    Some(match b {
+        b'{' => TokenKind::LBrace,
+        b'}' => TokenKind::RBrace,
+        b'0' => TokenKind::Nil,
+        b'|' => TokenKind::Bar,
+        b'(' => TokenKind::LParen,
+        b',' => TokenKind::Comma,
+        b')' => TokenKind::RParen,
+        b'[' => TokenKind::LBrack,
+        b'=' => TokenKind::Eq,
+        b']' => TokenKind::RBrack,
+        b'*' => TokenKind::Star,
+        b'<' => TokenKind::Lt,
+        b'>' => TokenKind::Gt,
+        b'_' => TokenKind::Underscore,
+        b'@' => TokenKind::At,
+        _ => return None,
+    })
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Result<Token, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.skip_trivia();
+        let rest = &self.code[self.pos..];
+        let first = *rest.as_bytes().first()?;
+
+        let token = if first == b'"' {
+            Self::string(rest).map(|len| (TokenKind::String, len))
+        } else if let Some((kind, len)) = variable(rest.as_bytes()) {
+            let kind = match &rest[..len] {
+                "context" => TokenKind::Context,
+                "for" => TokenKind::For,
+                "in" => TokenKind::In,
+                "let" => TokenKind::Let,
+                _ => kind,
+            };
+            Some((kind, len))
+        } else {
+            symbol(first).map(|kind| (kind, 1))
+        };
+
+        let start = self.pos;
+        match token {
+            Some((kind, len)) => {
+                self.pos += len;
+                Some(Ok(Token {
+                    kind,
+                    span: start..self.pos,
+                }))
+            }
+            None => {
+                self.pos = self.code.len();
+                Some(Err(Error::InvalidToken { offset: start }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TokenKind, tokenize, tokenize_iter};
+    use crate::ast::Error;
+
+    #[test]
+    fn test_tokenize_iter_matches_tokenize() {
+        let code = r#"context "a \"b\"" for _ in // note
+            { let x' = <_X1> in x' | 0 * /* skipped */ letter(let _a = <@{0}> in 0, let b = <B> in 0) }"#;
+
+        let batch = tokenize(code).unwrap();
+        let lazy: Vec<_> = tokenize_iter(code).collect::<Result<_, _>>().unwrap();
+        let kinds: Vec<_> = batch.iter().map(|token| token.kind).collect();
+
+        assert_eq!(lazy, batch);
+        assert_eq!(&code[batch[1].span.clone()], r#""a \"b\"""#);
+        assert_eq!(
+            kinds[..12],
+            [
+                TokenKind::Context,
+                TokenKind::String,
+                TokenKind::For,
+                TokenKind::Underscore,
+                TokenKind::In,
+                TokenKind::LBrace,
+                TokenKind::Let,
+                TokenKind::LVar,
+                TokenKind::Eq,
+                TokenKind::Lt,
+                TokenKind::UVar,
+                TokenKind::Gt,
+            ]
+        );
+        assert_eq!(kinds.len(), 41);
+        assert_eq!(
+            (batch[17].kind, &code[batch[17].span.clone()]),
+            (TokenKind::LVar, "letter")
+        );
+    }
+
+    #[test]
+    fn test_tokenize_stops_at_invalid_token() {
+        let mut tokens = tokenize_iter("<a> | \"open");
+
+        assert_eq!(tokens.by_ref().filter(Result::is_ok).count(), 4);
+        assert!(tokens.next().is_none());
+        assert!(matches!(
+            tokenize("<a> | # 0"),
+            Err(Error::InvalidToken { offset: 6 })
+        ));
+    }
+}
@@ -1,4 +1,6 @@
 use std::ffi::{CStr, CString};
+use std::fs;
+use std::path::Path;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -10,27 +12,211 @@ mod wasm;
 
 pub mod ast;
 mod bindings;
+pub mod build;
+pub mod dot;
+pub mod export;
 mod guard;
+#[cfg(feature = "json")]
+mod json;
+pub mod lexer;
+pub mod printer;
+mod program;
+#[cfg(feature = "proto")]
+pub mod proto;
+pub mod rholang;
 mod visitor;
 mod walker;
 
+pub use export::mermaid;
+pub use lexer::{tokenize, tokenize_iter};
+pub use program::{parse_many, parse_program};
+pub use rholang::{CompileResult, Diagnostic, compile};
 pub use visitor::Visitor;
-pub use walker::Walker;
+pub use walker::{BindingOrder, Walker};
 
+/// Parses GraphL from an owned string.
+///
+/// Kept for the JavaScript bindings, which pass strings by value; Rust
+/// callers holding a slice should use [`parse_str`], which this forwards to
+/// and which avoids allocating a `String` per call.
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = parseToAst))]
 pub fn parse_to_ast(code: String) -> Result<ast::Graph, ast::Error> {
+    parse_str(&code)
+}
+
+/// Parses GraphL from a borrowed string.
+///
+/// The nil graph, written `0` or `{0}` and optionally surrounded by the
+/// spaces, tabs, line breaks and form feeds the lexer skips, is recognized
+/// directly and returned as [`ast::Graph::Nil`]
+/// without calling into the C parser. Braces do not survive parsing, so this
+/// is the same result the parser would produce.
+///
+/// # Errors
+///
+/// Returns [`ast::Error::InvalidCString`] with the byte position of the
+/// first NUL if `code` contains one, since the C parser reads NUL-terminated
+/// input, and [`ast::Error::ParseFailed`] with the position of the token the
+/// parser stopped at if the code does not parse. Failures the parser gives no
+/// position for are reported as [`ast::Error::InvalidGraphL`].
+pub fn parse_str(code: &str) -> Result<ast::Graph, ast::Error> {
+    // Only the whitespace the C lexer skips: `trim` would also accept
+    // Unicode spaces and `\v`, which the parser rejects.
+    if let "0" | "{0}" = code.trim_matches([' ', '\t', '\n', '\r', '\x0c']) {
+        return Ok(ast::Graph::Nil);
+    }
+
+    parse_with_c_parser(code)
+}
+
+/// Reads the file at `path` and parses its contents as GraphL.
+///
+/// # Errors
+///
+/// Returns [`ast::Error::Io`] if the file cannot be read,
+/// [`ast::Error::InvalidUtf8String`] if it is not UTF-8, and otherwise the
+/// errors of [`parse_str`], including [`ast::Error::InvalidCString`] for a
+/// file containing a NUL byte.
+pub fn parse_file(path: impl AsRef<Path>) -> Result<ast::Graph, ast::Error> {
+    let bytes = fs::read(path).map_err(|err| ast::Error::Io {
+        message: err.to_string(),
+    })?;
+    let code = String::from_utf8(bytes).map_err(|_| ast::Error::InvalidUtf8String)?;
+    parse_str(&code)
+}
+
+/// Parses GraphL whose root is expected to be one particular kind of node,
+/// returning that node.
+///
+/// # Errors
+///
+/// Returns the parse error, or [`ast::Error::InvalidVariant`] if the root
+/// is another kind of graph.
+pub fn parse_as<T: ast::FromGraph>(code: &str) -> Result<T, ast::Error> {
+    parse_str(code).and_then(T::from_graph)
+}
+
+/// Parses GraphL and checks its names against an embedder's policy.
+///
+/// See [`ast::Graph::validate_names`] for which names are passed to `valid`.
+///
+/// # Errors
+///
+/// Returns the parse error, or [`ast::Error::InvalidName`] for the first name
+/// that `valid` rejects.
+pub fn parse_with_validator(
+    code: &str,
+    valid: impl Fn(&str) -> bool,
+) -> Result<ast::Graph, ast::Error> {
+    let graph = parse_str(code)?;
+    graph.validate_names(valid)?;
+    Ok(graph)
+}
+
+/// The input encoding understood by the bundled C lexer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// UTF-8 text whose identifiers are ASCII.
+    ///
+    /// The lexer works on bytes. Keywords, variables and punctuation are
+    /// ASCII: any other byte outside a string literal or comment is a lexing
+    /// error, so a non-ASCII identifier such as `<é>` is rejected with
+    /// [`ast::Error::ParseFailed`] rather than misread. String literals
+    /// and comments pass arbitrary bytes through, so UTF-8 in `context`
+    /// strings survives parsing unchanged.
+    Utf8AsciiIdentifiers,
+}
+
+/// Returns the encoding accepted by [`parse_str`] and the other parsing
+/// functions.
+pub fn parser_encoding() -> Encoding {
+    Encoding::Utf8AsciiIdentifiers
+}
+
+/// Parses GraphL, accepting a final vertex or variable that lacks its `| 0`
+/// continuation.
+///
+/// `code` is first parsed as is. Only if that fails with a syntax error,
+/// [`ast::Error::ParseFailed`] or [`ast::Error::InvalidGraphL`], is it
+/// parsed a second time with `| 0` appended on a new line, so that `<a>`
+/// reads as `<a> | 0`. When the retry fails as well, the error of the first
+/// attempt is returned. [`Fragment::terminated`] tells which of the two
+/// parses succeeded.
+pub fn parse_fragment(code: &str) -> Result<Fragment, ast::Error> {
+    match parse_str(code) {
+        Ok(graph) => Ok(Fragment {
+            graph,
+            terminated: true,
+        }),
+        Err(err @ (ast::Error::ParseFailed { .. } | ast::Error::InvalidGraphL)) => {
+            let graph = parse_str(&format!("{code}\n| 0")).map_err(|_| err)?;
+            Ok(Fragment {
+                graph,
+                terminated: false,
+            })
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// A graph read by [`parse_fragment`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    /// The graph, ending in the inserted `| 0` if there was one.
+    pub graph: ast::Graph,
+    /// Whether `code` parsed as is, rather than only with `| 0` appended.
+    pub terminated: bool,
+}
+
+fn parse_with_c_parser(code: &str) -> Result<ast::Graph, ast::Error> {
     let c_code = CString::new(code).map_err(|err| ast::Error::InvalidCString {
         position: err.nul_position(),
     })?;
+    unsafe { bindings::psClearError() };
     let graph = unsafe { bindings::psGraph(c_code.as_ptr()) }.guarded();
 
     if graph.is_null() {
-        return Err(ast::Error::InvalidGraphL);
+        return Err(last_parse_error());
     }
 
     (*graph).try_into()
 }
 
+/// Converts the position the C parser recorded for its last failure on this
+/// thread, falling back to [`ast::Error::InvalidGraphL`] without one.
+fn last_parse_error() -> ast::Error {
+    let error = unsafe { bindings::psLastError() };
+
+    match (usize::try_from(error.line), usize::try_from(error.column)) {
+        (Ok(line @ 1..), Ok(column)) => ast::Error::ParseFailed {
+            line,
+            // The generated lexer starts counting columns at 1 on the first
+            // line but at 0 on every line after a line break.
+            column: if line == 1 { column } else { column + 1 },
+            message: if error.message.is_null() {
+                String::new()
+            } else {
+                unsafe { CStr::from_ptr(error.message) }
+                    .to_string_lossy()
+                    .into_owned()
+            },
+        },
+        _ => ast::Error::InvalidGraphL,
+    }
+}
+
+/// Parses GraphL after applying Unicode NFC normalization to the input.
+///
+/// Canonically equivalent spellings (e.g. a precomposed `é` versus `e`
+/// followed by a combining acute accent) are folded into the same code point
+/// sequence, so they produce equal ASTs.
+#[cfg(feature = "normalization")]
+pub fn parse_normalized(code: &str) -> Result<ast::Graph, ast::Error> {
+    use unicode_normalization::UnicodeNormalization;
+
+    parse_to_ast(code.nfc().collect())
+}
+
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = astToGraphl))]
 pub fn ast_to_graphl(ast: ast::Graph) -> Result<String, ast::Error> {
     let ast: Guard<_> = ast.try_into()?;
@@ -48,3 +234,201 @@ pub fn ast_to_graphl(ast: ast::Graph) -> Result<String, ast::Error> {
         .map(ToOwned::to_owned)
         .map_err(|_| ast::Error::InvalidUtf8String)
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        Encoding,
+        Fragment,
+        ast,
+        parse_as,
+        parse_file,
+        parse_fragment,
+        parse_str,
+        parse_to_ast,
+        parse_with_c_parser,
+        parser_encoding,
+    };
+
+    #[test]
+    fn test_nil_fast_path_matches_c_parser() {
+        for code in ["0", "{0}", "  0\n", "\t{0} "] {
+            assert_eq!(parse_str(code).unwrap(), ast::Graph::Nil);
+            assert_eq!(parse_str(code).unwrap(), parse_with_c_parser(code).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_nil_fast_path_only_skips_lexer_whitespace() {
+        for code in ["\u{a0}0", "\u{3000}{0}", "\u{b}0", "0\u{85}", "\x0c0\r\n"] {
+            let fast = parse_str(code).ok();
+            assert_eq!(fast, parse_with_c_parser(code).ok(), "{code:?}");
+            assert_eq!(fast, parse_to_ast(code.into()).ok(), "{code:?}");
+        }
+        assert!(parse_str("\u{a0}0").is_err());
+        assert_eq!(parse_str("\x0c0\r\n").unwrap(), ast::Graph::Nil);
+    }
+
+    #[test]
+    fn test_nil_lookalikes_use_c_parser() {
+        assert_eq!(parse_str("{ 0 }").unwrap(), ast::Graph::Nil);
+        assert_eq!(parse_str("{{0}}").unwrap(), ast::Graph::Nil);
+        assert!(matches!(
+            parse_str("00"),
+            Err(ast::Error::ParseFailed {
+                line: 1,
+                column: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_error_reports_line_and_column() {
+        let err = parse_str("<a> | 0 *\n  * <b> | 0").unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                ast::Error::ParseFailed { line: 2, column: 3, ref message } if message == "syntax error"
+            ),
+            "{err:?}"
+        );
+        assert!(matches!(
+            parse_str("<a> | 0 * ]"),
+            Err(ast::Error::ParseFailed {
+                line: 1,
+                column: 11,
+                ..
+            })
+        ));
+        assert!(parse_str("<a> | 0").is_ok());
+    }
+
+    #[test]
+    fn test_parse_error_columns_are_one_based_on_every_line() {
+        let position = |code: &str| match parse_str(code) {
+            Err(ast::Error::ParseFailed { line, column, .. }) => (line, column),
+            result => panic!("{code:?}: {result:?}"),
+        };
+
+        assert_eq!(position("]"), (1, 1));
+        assert_eq!(position("<a> | 0 *\n\n* <b> | 0"), (3, 1));
+        assert_eq!(position("<a> | 0 *\n\t]"), (2, 2));
+        assert_eq!(position("context \"x\ny\" for a in\n  <a> | ]"), (3, 9));
+    }
+
+    #[test]
+    fn test_interior_nul_is_rejected() {
+        let code = "<a> | 0 * <b>\0 | 0";
+
+        assert!(matches!(
+            parse_str(code),
+            Err(ast::Error::InvalidCString { position: 13 })
+        ));
+        assert!(matches!(
+            parse_to_ast(code.to_owned()),
+            Err(ast::Error::InvalidCString { position: 13 })
+        ));
+    }
+
+    /// Writes `contents` to a file in the temporary directory that is unique
+    /// to this process and `name`.
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("graphl-{}-{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_parse_file() {
+        let nil = temp_file("nil.graphl", b"{0}\n");
+        let code =
+            "(let a = <a> in 0, let b = <b> in 0) * e(let c = <c> in x | 0, let d = <d> in 0)";
+        let edges = temp_file("edges.graphl", code.as_bytes());
+        let nul = temp_file("nul.graphl", b"<a> |\0 0");
+
+        assert_eq!(parse_file(&nil).unwrap(), ast::Graph::Nil);
+        assert_eq!(parse_file(&edges).unwrap(), parse_str(code).unwrap());
+        assert!(matches!(
+            parse_file(&nul),
+            Err(ast::Error::InvalidCString { position: 5 })
+        ));
+        assert!(matches!(
+            parse_file(nil.with_extension("missing")),
+            Err(ast::Error::Io { .. })
+        ));
+
+        for path in [nil, edges, nul] {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_fragment_completes_missing_continuation() {
+        assert_eq!(
+            parse_fragment("<a>").unwrap(),
+            Fragment {
+                graph: parse_str("<a> | 0").unwrap(),
+                terminated: false,
+            }
+        );
+        assert_eq!(
+            parse_fragment("<a> | x // trailing comment").unwrap(),
+            Fragment {
+                graph: parse_str("<a> | x | 0").unwrap(),
+                terminated: false,
+            }
+        );
+        assert_eq!(
+            parse_fragment("<a> | 0").unwrap(),
+            Fragment {
+                graph: parse_str("<a> | 0").unwrap(),
+                terminated: true,
+            }
+        );
+        assert!(matches!(
+            parse_fragment("<a> |"),
+            Err(ast::Error::ParseFailed { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_as() {
+        let tensor = parse_as::<ast::GTensor>("<a> | 0 * <b> | 0").unwrap();
+
+        assert_eq!(*tensor.graph_1, parse_str("<a> | 0").unwrap());
+        assert!(matches!(
+            parse_as::<ast::GTensor>("0"),
+            Err(ast::Error::InvalidVariant { context }) if context == "expected GTensor, found Nil"
+        ));
+    }
+
+    #[test]
+    fn test_non_ascii_outside_strings_is_rejected() {
+        assert_eq!(parser_encoding(), Encoding::Utf8AsciiIdentifiers);
+
+        for code in ["<é> | 0", "<aé> | 0", "<a> | ω | 0", "<Ω> | 0"] {
+            assert!(
+                matches!(parse_str(code), Err(ast::Error::ParseFailed { .. })),
+                "{code}"
+            );
+        }
+
+        let Ok(ast::Graph::Context(context)) = parse_str("context \"café ☕\" for a in 0") else {
+            panic!("expected a context");
+        };
+        assert_eq!(context.string, "café ☕");
+    }
+
+    #[cfg(feature = "normalization")]
+    #[test]
+    fn test_differently_normalized_inputs_produce_equal_asts() {
+        let precomposed =
+            crate::parse_normalized("context \"caf\u{e9}\" for a in <a> | 0").unwrap();
+        let decomposed =
+            crate::parse_normalized("context \"cafe\u{301}\" for a in <a> | 0").unwrap();
+
+        assert_eq!(precomposed, decomposed)
+    }
+}
@@ -0,0 +1,943 @@
+//! A pure Rust GraphL printer.
+//!
+//! The output follows the token spacing of the BNFC-generated C printer
+//! (`< a > | 0`, `(let a = < a > in 0, ...)`), without its trailing space, and
+//! inserts braces at the same precedence levels. Unlike the C printer it also
+//! braces a `context` used as the left operand of a tensor, which would
+//! otherwise swallow the right operand when re-parsed.
+//!
+//! [`Graph`], [`Vertex`] and [`Name`] implement [`Display`](fmt::Display)
+//! with the default [`PrintOptions`], so `graph.to_string()` gives GraphL
+//! without the C printer and its global output buffer.
+//!
+//! [`Graph::to_graphl_with_sourcemap`] additionally reports where each node
+//! ended up in the output, for editors that keep a selection in the AST and
+//! the formatted text in sync.
+//!
+//! [`CachedPrinter`] keeps the printed text of the subtrees of the last graph,
+//! so an editor re-formatting a large document after a small edit only
+//! renders the parts that changed.
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+
+use crate::ast::{Binding, Graph, Name, Vertex};
+
+/// Layout options for [`Graph::print`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrintOptions {
+    /// Break tensors, edges and rules over several lines.
+    pub multiline: bool,
+    /// Number of spaces per nesting level in multi-line output.
+    pub indent: usize,
+    /// Leave out the `| 0` that ends the output, so `<a> | 0` prints as
+    /// `< a >`. Only the final continuation is dropped, which
+    /// [`parse_fragment`](crate::parse_fragment) restores when parsing.
+    pub omit_trailing_nil: bool,
+    /// Line break written between the lines of multi-line output.
+    pub line_ending: LineEnding,
+    /// Brace every nested graph other than `0`, and every quoted graph,
+    /// whether or not precedence requires it, so `<a> | <b> | 0` prints as
+    /// `< a > | {< b > | 0}`. GraphL groups only with braces; the parentheses
+    /// of edges are part of their syntax and always printed.
+    pub fully_parenthesized: bool,
+}
+
+/// A line break, for output meant for a particular platform.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the characters of the line break.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            multiline: false,
+            indent: 2,
+            omit_trailing_nil: false,
+            line_ending: LineEnding::Lf,
+            fully_parenthesized: false,
+        }
+    }
+}
+
+/// A byte range of printed output, see [`Graph::to_graphl_with_sourcemap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// The span as a range, for slicing the output.
+    pub fn range(self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+
+    /// Shortens the span to end within the first `len` bytes.
+    fn clamp(&mut self, len: usize) {
+        self.start = self.start.min(len);
+        self.end = self.end.min(len);
+    }
+}
+
+/// Where a node was printed, see [`Graph::to_graphl_with_sourcemap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeSpan {
+    /// The node's whole text.
+    pub span: Span,
+    /// The vertex or variable a `|` node starts with, `None` for other
+    /// nodes.
+    pub head: Option<Span>,
+}
+
+/// The category of a styled token, see [`Printer::with_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Token {
+    /// `let`, `in`, `context` and `for`.
+    Keyword,
+    /// A bound or referenced variable, or the name of an edge, rule or
+    /// context.
+    Variable,
+    /// The name inside `< >`.
+    Vertex,
+    /// A string literal, quotes included.
+    String,
+}
+
+/// Writes a token of the given category to the output.
+pub(crate) type Style = fn(Token, &str, &mut String);
+
+pub(crate) struct Printer<'o> {
+    out: String,
+    opts: &'o PrintOptions,
+    depth: usize,
+    line_start: usize,
+    style: Option<Style>,
+    /// The path of the node being printed, following [`Graph::children`].
+    path: Vec<usize>,
+    /// How many quoted names enclose the node being printed.
+    quoted: usize,
+    sourcemap: Option<Vec<(Vec<usize>, NodeSpan)>>,
+    cache: Option<Cache>,
+}
+
+impl<'o> Printer<'o> {
+    pub(crate) fn new(opts: &'o PrintOptions) -> Self {
+        Self {
+            out: String::new(),
+            opts,
+            depth: 0,
+            line_start: 0,
+            style: None,
+            path: Vec::new(),
+            quoted: 0,
+            sourcemap: None,
+            cache: None,
+        }
+    }
+
+    /// Routes every keyword, variable, vertex name and string through
+    /// `style` instead of writing it as is.
+    #[cfg_attr(not(feature = "color"), allow(dead_code))]
+    pub(crate) fn with_style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Records the span of every graph node outside quoted names.
+    fn with_sourcemap(mut self) -> Self {
+        self.sourcemap = Some(Vec::new());
+        self
+    }
+
+    /// Like [`finish`](Self::finish), also returning the recorded spans. A
+    /// span that reached into trailing output dropped by `finish` is cut off
+    /// at the end of the text.
+    fn finish_with_sourcemap(mut self) -> (String, Vec<(Vec<usize>, NodeSpan)>) {
+        let mut sourcemap = self.sourcemap.take().unwrap_or_default();
+        let out = self.finish();
+        for (_, node) in &mut sourcemap {
+            node.span.clamp(out.len());
+            if let Some(head) = &mut node.head {
+                head.clamp(out.len());
+            }
+        }
+        (out, sourcemap)
+    }
+
+    pub(crate) fn finish(mut self) -> String {
+        self.line_start = 0;
+        self.trim();
+        // Only vertices and variables print `|`, so a final `| 0` is always
+        // the `0` continuation of the last one.
+        if self.opts.omit_trailing_nil && self.out.ends_with("| 0") {
+            self.out.truncate(self.out.len() - "| 0".len());
+            self.trim();
+        }
+        self.out
+    }
+
+    fn trim(&mut self) {
+        while self.out.len() > self.line_start && self.out.ends_with(' ') {
+            self.out.pop();
+        }
+    }
+
+    fn open(&mut self, c: char) {
+        self.out.push(c);
+    }
+
+    fn close(&mut self, c: char) {
+        self.trim();
+        self.out.push(c);
+        self.out.push(' ');
+    }
+
+    fn symbol(&mut self, s: &str) {
+        self.out.push_str(s);
+        self.out.push(' ');
+    }
+
+    fn token(&mut self, token: Token, s: &str) {
+        match self.style {
+            Some(style) => {
+                style(token, s, &mut self.out);
+                self.out.push(' ');
+            }
+            None => self.symbol(s),
+        }
+    }
+
+    fn string(&mut self, s: &str) {
+        let mut literal = String::from('"');
+        write_escaped(&mut literal, s).expect("writing to a String cannot fail");
+        literal.push('"');
+        self.token(Token::String, &literal);
+    }
+
+    fn newline(&mut self) {
+        if self.opts.multiline {
+            self.trim();
+            self.out.push_str(self.opts.line_ending.as_str());
+            self.out
+                .extend(std::iter::repeat_n(' ', self.depth * self.opts.indent));
+            self.line_start = self.out.len();
+        }
+    }
+
+    fn indent(&mut self) {
+        self.depth += 1;
+        self.newline();
+    }
+
+    fn dedent(&mut self) {
+        self.depth -= 1;
+        self.newline();
+    }
+
+    pub(crate) fn graph(&mut self, graph: &Graph, level: u8) {
+        self.graph_in(graph, level, false);
+    }
+
+    /// Prints `graph` as child `index` of the node being printed.
+    fn child(&mut self, index: usize, graph: &Graph, level: u8) {
+        self.child_in(index, graph, level, false);
+    }
+
+    fn child_in(&mut self, index: usize, graph: &Graph, level: u8, tensor_left: bool) {
+        self.path.push(index);
+        self.graph_in(graph, self.nested_level(level), tensor_left);
+        self.path.pop();
+    }
+
+    /// The level to print a nested graph at: `level`, or the highest level
+    /// when every composite graph is braced, which only `0` reaches unbraced.
+    fn nested_level(&self, level: u8) -> u8 {
+        if self.opts.fully_parenthesized {
+            precedence(&Graph::Nil)
+        } else {
+            level
+        }
+    }
+
+    /// Prints `graph`, copying its text from the cache instead when the
+    /// same subtree was printed at the same path and position last time.
+    fn graph_in(&mut self, graph: &Graph, level: u8, tensor_left: bool) {
+        // Graphs inside quoted names have no path of their own.
+        let Some(cache) = self.cache.as_mut().filter(|_| self.quoted == 0) else {
+            return self.render(graph, level, tensor_left);
+        };
+        let key = FragmentKey {
+            path: self.path.clone(),
+            level,
+            tensor_left,
+            depth: self.depth,
+        };
+
+        let start = self.out.len();
+        if let Some(fragment) = cache
+            .previous
+            .remove(&key)
+            .filter(|fragment| fragment.graph == *graph)
+        {
+            self.out.push_str(&fragment.text);
+            if let Some(line_start) = fragment.line_start {
+                self.line_start = start + line_start;
+            }
+            cache.nodes += fragment.nodes;
+            cache.candidates.push(Candidate {
+                key,
+                nodes: fragment.nodes,
+                text: fragment.text,
+                line_start: fragment.line_start,
+                graph: Some(fragment.graph),
+            });
+            return;
+        }
+
+        let (marker, before) = (cache.candidates.len(), cache.nodes);
+        self.render(graph, level, tensor_left);
+        let line_start = self
+            .line_start
+            .checked_sub(start)
+            .filter(|&offset| offset > 0);
+        let cache = self.cache.as_mut().expect("checked above");
+        cache.nodes += 1;
+        let nodes = cache.nodes - before;
+
+        if nodes <= MAX_CACHED_NODES {
+            // Small enough to be kept whole, unless the parent is as well.
+            cache.candidates.truncate(marker);
+            cache.candidates.push(Candidate {
+                key,
+                nodes,
+                text: self.out[start..].to_owned(),
+                line_start,
+                graph: None,
+            });
+        } else {
+            let children = graph.children();
+            for candidate in cache.candidates.drain(marker..) {
+                let index = *candidate.key.path.last().expect("a child has a path");
+                let (key, fragment) = candidate.into_fragment(children[index]);
+                cache.current.insert(key, fragment);
+            }
+        }
+    }
+
+    fn render(&mut self, graph: &Graph, level: u8, tensor_left: bool) {
+        let braced =
+            level > precedence(graph) || (tensor_left && matches!(graph, Graph::Context(_)));
+        if braced {
+            self.open('{');
+            self.indent();
+        }
+
+        let entry = match &mut self.sourcemap {
+            Some(sourcemap) if self.quoted == 0 => {
+                let start = self.out.len();
+                let span = Span { start, end: start };
+                sourcemap.push((self.path.clone(), NodeSpan { span, head: None }));
+                Some(sourcemap.len() - 1)
+            }
+            _ => None,
+        };
+
+        match graph {
+            Graph::Nil => self.symbol("0"),
+            Graph::Vertex(vertex) => {
+                self.vertex(&vertex.vertex);
+                self.head(entry);
+                self.symbol("|");
+                self.child(0, &vertex.graph, 1);
+            }
+            Graph::Var(var) => {
+                self.token(Token::Variable, &var.var);
+                self.head(entry);
+                self.symbol("|");
+                self.child(0, &var.graph, 1);
+            }
+            Graph::Nominate(binding) => self.binding_at(0, binding),
+            Graph::EdgeAnon(edge) => self.edge(&edge.binding_1, &edge.binding_2),
+            Graph::EdgeNamed(edge) => {
+                self.name(&edge.name);
+                self.edge(&edge.binding_1, &edge.binding_2);
+            }
+            Graph::RuleAnon(rule) => self.rule(&rule.graph_1, &rule.graph_2),
+            Graph::RuleNamed(rule) => {
+                self.name(&rule.name);
+                self.rule(&rule.graph_1, &rule.graph_2);
+            }
+            Graph::Subgraph(binding) => {
+                self.token(Token::Keyword, "let");
+                self.token(Token::Variable, &binding.var);
+                self.symbol("=");
+                self.child(0, &binding.graph_1, 0);
+                self.token(Token::Keyword, "in");
+                self.child(1, &binding.graph_2, 2);
+            }
+            Graph::Tensor(tensor) => {
+                self.child_in(0, &tensor.graph_1, 0, true);
+                self.newline();
+                self.symbol("*");
+                self.child(1, &tensor.graph_2, 1);
+            }
+            Graph::Context(context) => {
+                self.token(Token::Keyword, "context");
+                self.string(&context.string);
+                self.token(Token::Keyword, "for");
+                self.name(&context.name);
+                self.token(Token::Keyword, "in");
+                self.child(0, &context.graph, 0);
+            }
+        }
+
+        if let (Some(sourcemap), Some(entry)) = (&mut self.sourcemap, entry) {
+            sourcemap[entry].1.span.end = self.out.trim_end_matches(' ').len();
+        }
+
+        if braced {
+            self.dedent();
+            self.close('}');
+        }
+    }
+
+    /// Records the text printed so far for sourcemap `entry` as its head.
+    fn head(&mut self, entry: Option<usize>) {
+        if let (Some(sourcemap), Some(entry)) = (&mut self.sourcemap, entry) {
+            let start = sourcemap[entry].1.span.start;
+            let end = self.out.trim_end_matches(' ').len();
+            sourcemap[entry].1.head = Some(Span { start, end });
+        }
+    }
+
+    fn edge(&mut self, binding_1: &Binding, binding_2: &Binding) {
+        self.open('(');
+        self.indent();
+        self.binding_at(0, binding_1);
+        self.close(',');
+        self.newline();
+        self.binding_at(1, binding_2);
+        self.dedent();
+        self.close(')');
+    }
+
+    fn rule(&mut self, graph_1: &Graph, graph_2: &Graph) {
+        self.open('[');
+        self.symbol("=");
+        self.indent();
+        self.child(0, graph_1, 0);
+        self.newline();
+        self.child(1, graph_2, 0);
+        self.dedent();
+        self.close(']');
+    }
+
+    pub(crate) fn binding(&mut self, binding: &Binding) {
+        self.binding_at(0, binding);
+    }
+
+    /// Prints `binding`, whose continuation is child `index` of the node
+    /// being printed.
+    fn binding_at(&mut self, index: usize, binding: &Binding) {
+        self.token(Token::Keyword, "let");
+        self.token(Token::Variable, &binding.var);
+        self.symbol("=");
+        self.vertex(&binding.vertex);
+        self.token(Token::Keyword, "in");
+        self.child(index, &binding.graph, 2);
+    }
+
+    pub(crate) fn vertex(&mut self, vertex: &Vertex) {
+        self.symbol("<");
+        self.name_as(&vertex.name, Token::Vertex);
+        self.symbol(">");
+    }
+
+    pub(crate) fn name(&mut self, name: &Name) {
+        self.name_as(name, Token::Variable);
+    }
+
+    /// Prints `name`, styling a plain variable as `token`.
+    fn name_as(&mut self, name: &Name, token: Token) {
+        match name {
+            Name::Wildcard => self.symbol("_"),
+            Name::VVar { value } | Name::GVar { value } => self.token(token, value),
+            Name::QuoteGraph { value } => {
+                self.symbol("@");
+                self.quoted += 1;
+                self.graph(value, self.nested_level(0));
+                self.quoted -= 1;
+            }
+            Name::QuoteVertex { value } => {
+                self.symbol("@");
+                self.vertex(value);
+            }
+        }
+    }
+}
+
+/// Writes `s` with the escapes the C printer uses inside string literals.
+pub(crate) fn write_escaped(w: &mut impl Write, s: &str) -> fmt::Result {
+    for c in s.chars() {
+        match c {
+            '\u{c}' => w.write_str("\\f")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            '\u{b}' => w.write_str("\\v")?,
+            '\\' => w.write_str("\\\\")?,
+            '\'' => w.write_str("\\'")?,
+            '"' => w.write_str("\\\"")?,
+            c => w.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// The grammar level (`Graph`, `Graph1`, ...) a graph is parsed at.
+fn precedence(graph: &Graph) -> u8 {
+    match graph {
+        Graph::Tensor(_) | Graph::Context(_) => 0,
+        Graph::Nominate(_)
+        | Graph::EdgeAnon(_)
+        | Graph::EdgeNamed(_)
+        | Graph::RuleAnon(_)
+        | Graph::RuleNamed(_)
+        | Graph::Subgraph(_) => 1,
+        Graph::Vertex(_) | Graph::Var(_) => 2,
+        Graph::Nil => 3,
+    }
+}
+
+impl Graph {
+    /// Renders this graph as GraphL without going through the C printer.
+    pub fn print(&self, opts: &PrintOptions) -> String {
+        let mut printer = Printer::new(opts);
+        printer.graph(self, 0);
+        printer.finish()
+    }
+
+    /// Renders this graph like [`print`](Self::print), also returning the
+    /// span of every node's text in the output.
+    ///
+    /// The map has one entry per node in pre-order, keyed by its path as in
+    /// [`print_at`](Self::print_at), so quoted graphs inside names have no
+    /// entry of their own. A node's span covers its own text without
+    /// surrounding braces and whitespace: in `<a> | 0 * x | 0` the left
+    /// operand spans `< a > | 0`. A vertex or variable node also has the
+    /// span of its head, `< a >`, so an editor can select just the vertex.
+    pub fn to_graphl_with_sourcemap(
+        &self,
+        opts: &PrintOptions,
+    ) -> (String, Vec<(Vec<usize>, NodeSpan)>) {
+        let mut printer = Printer::new(opts).with_sourcemap();
+        printer.graph(self, 0);
+        printer.finish_with_sourcemap()
+    }
+}
+
+/// A printer that remembers the text of the subtrees it printed, for
+/// re-printing a document after small edits.
+///
+/// The graph is cut into the largest subtrees of at most
+/// `MAX_CACHED_NODES` nodes, and each is kept with its text, keyed by its
+/// path, precedence level and indentation. On the next call a subtree at the
+/// same path and position is compared with the kept one using `==`, and if
+/// they are equal its text is copied instead of rendered again. Only the
+/// nodes above those subtrees and the subtrees that changed are rendered;
+/// the output is always that of [`Graph::print`] with the same options.
+///
+/// There is no hashing pass: a re-print costs one structural comparison of
+/// the unchanged parts instead of rendering them, see
+/// `benches/cached_print.rs`. Keying by path means an edit that moves
+/// subtrees to other paths, such as wrapping the whole graph in a tensor,
+/// renders them again. Only the subtrees of the last printed graph are kept,
+/// so the cache holds about one copy of it.
+///
+/// # Examples
+///
+/// ```
+/// use graphl_parser::parse_str;
+/// use graphl_parser::printer::{CachedPrinter, PrintOptions};
+///
+/// let mut printer = CachedPrinter::new(PrintOptions::default());
+/// let graph = parse_str("<a> | 0 * <b> | 0").unwrap();
+///
+/// assert_eq!(printer.print(&graph), graph.print(&PrintOptions::default()));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CachedPrinter {
+    opts: PrintOptions,
+    fragments: HashMap<FragmentKey, Fragment>,
+}
+
+impl CachedPrinter {
+    /// Creates a printer with an empty cache.
+    pub fn new(opts: PrintOptions) -> Self {
+        Self {
+            opts,
+            fragments: HashMap::new(),
+        }
+    }
+
+    /// The options every graph is printed with.
+    pub fn options(&self) -> &PrintOptions {
+        &self.opts
+    }
+
+    /// Renders `graph` like [`Graph::print`], reusing the subtrees kept from
+    /// the previous call and keeping those of `graph` instead.
+    pub fn print(&mut self, graph: &Graph) -> String {
+        let mut printer = Printer::new(&self.opts);
+        printer.cache = Some(Cache {
+            previous: std::mem::take(&mut self.fragments),
+            current: HashMap::new(),
+            candidates: Vec::new(),
+            nodes: 0,
+        });
+        printer.graph(graph, 0);
+
+        let mut cache = printer.cache.take().expect("set above");
+        for candidate in cache.candidates {
+            let (key, fragment) = candidate.into_fragment(graph);
+            cache.current.insert(key, fragment);
+        }
+        self.fragments = cache.current;
+        printer.finish()
+    }
+
+    /// Forgets every kept subtree.
+    pub fn clear(&mut self) {
+        self.fragments.clear();
+    }
+}
+
+/// Where a subtree was printed: its text depends on the braces and
+/// indentation its position calls for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct FragmentKey {
+    path: Vec<usize>,
+    level: u8,
+    tensor_left: bool,
+    depth: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Fragment {
+    /// The subtree, to confirm that the one being printed is the same.
+    graph: Graph,
+    text: String,
+    /// Where the last line of `text` starts, if it is not the first.
+    line_start: Option<usize>,
+    /// The number of graph nodes in `graph`.
+    nodes: usize,
+}
+
+/// A subtree of at most this many nodes is kept whole rather than split
+/// into smaller ones.
+const MAX_CACHED_NODES: usize = 256;
+
+/// A subtree small enough to be kept, waiting to see whether its parent is
+/// kept whole instead.
+struct Candidate {
+    key: FragmentKey,
+    nodes: usize,
+    text: String,
+    line_start: Option<usize>,
+    /// The kept subtree, if the text came from the cache.
+    graph: Option<Graph>,
+}
+
+impl Candidate {
+    /// Keeps the candidate, whose subtree is `graph`.
+    fn into_fragment(self, graph: &Graph) -> (FragmentKey, Fragment) {
+        let fragment = Fragment {
+            graph: self.graph.unwrap_or_else(|| graph.clone()),
+            text: self.text,
+            line_start: self.line_start,
+            nodes: self.nodes,
+        };
+        (self.key, fragment)
+    }
+}
+
+/// The state of a [`CachedPrinter`] during one call.
+struct Cache {
+    /// The subtrees kept from the previous call, removed as they are used.
+    previous: HashMap<FragmentKey, Fragment>,
+    /// The subtrees kept from this call.
+    current: HashMap<FragmentKey, Fragment>,
+    /// Candidates of the nodes being printed, innermost last.
+    candidates: Vec<Candidate>,
+    /// The number of graph nodes printed so far.
+    nodes: usize,
+}
+
+/// Writes whatever `print` puts into a default-configured printer.
+fn display(f: &mut fmt::Formatter<'_>, print: impl FnOnce(&mut Printer<'_>)) -> fmt::Result {
+    let opts = PrintOptions::default();
+    let mut printer = Printer::new(&opts);
+    print(&mut printer);
+    f.write_str(&printer.finish())
+}
+
+impl fmt::Display for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(f, |printer| printer.graph(self, 0))
+    }
+}
+
+impl fmt::Display for Vertex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(f, |printer| printer.vertex(self))
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        display(f, |printer| printer.name(self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CachedPrinter, LineEnding, MAX_CACHED_NODES, PrintOptions};
+    use crate::ast::{Graph, Name};
+    use crate::{ast_to_graphl, parse_fragment, parse_to_ast};
+
+    const SAMPLES: &[&str] = &[
+        "0",
+        "<a> | 0",
+        "x | <X> | 0",
+        "(let a = <a> in <a> | 0, let b = <b> in <b> | 0)",
+        "n(let a = <a> in 0, let b = <_> in { <b> | 0 * <c> | 0 })",
+        "[= <a> | 0 <b> | 0]",
+        "R [= { <a> | 0 * <b> | 0 } <c> | 0]",
+        "let G = <a> | 0 in { <b> | 0 * <c> | 0 }",
+        "<a> | { <b> | 0 * { <c> | 0 * <d> | 0 } }",
+        r#"<@<a>> | <@{<b> | 0}> | {context "x\ny" for f in 0}"#,
+    ];
+
+    #[test]
+    fn test_print_matches_c_printer() {
+        for code in SAMPLES {
+            let graph = parse_to_ast((*code).into()).unwrap();
+
+            assert_eq!(
+                graph.print(&PrintOptions::default()),
+                ast_to_graphl(graph.clone()).unwrap().trim_end(),
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_display_matches_c_printer() {
+        for code in SAMPLES.iter().chain(&["{0}"]) {
+            let graph = parse_to_ast((*code).into()).unwrap();
+
+            assert_eq!(
+                graph.to_string(),
+                ast_to_graphl(graph.clone()).unwrap().trim_end(),
+                "{code}"
+            );
+        }
+
+        let Graph::Vertex(vertex) = parse_to_ast("<@{<a> | 0}> | 0".into()).unwrap() else {
+            panic!("expected a vertex");
+        };
+        assert_eq!(vertex.vertex.to_string(), "< @ < a > | 0 >");
+        assert_eq!(vertex.vertex.name.to_string(), "@ < a > | 0");
+        assert_eq!(Name::Wildcard.to_string(), "_");
+    }
+
+    #[test]
+    fn test_print_braces_context_on_the_left_of_tensor() {
+        let graph = parse_to_ast(r#"{ context "x" for a in 0 } * <b> | 0"#.into()).unwrap();
+        let printed = graph.print(&PrintOptions::default());
+
+        assert_eq!(printed, r#"{context "x" for a in 0} * < b > | 0"#);
+        assert_eq!(parse_to_ast(printed).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_print_omitting_trailing_nil_round_trips() {
+        let opts = PrintOptions {
+            omit_trailing_nil: true,
+            ..PrintOptions::default()
+        };
+
+        for (code, printed) in [
+            ("<a> | 0", "< a >"),
+            ("<a> | 0 * x | 0", "< a > | 0 * x"),
+            ("let a = <a> in 0", "let a = < a > in 0"),
+        ] {
+            let graph = parse_to_ast(code.into()).unwrap();
+
+            assert_eq!(graph.print(&opts), printed);
+            assert_eq!(parse_fragment(printed).unwrap().graph, graph);
+        }
+    }
+
+    #[test]
+    fn test_print_multiline_round_trips() {
+        let opts = PrintOptions {
+            multiline: true,
+            ..PrintOptions::default()
+        };
+        let graph = parse_to_ast(
+            "{ (let a = <a> in 0, let b = <b> in { [= <c> | 0 <d> | 0] * <e> | 0 }) * <f> | 0 }"
+                .into(),
+        )
+        .unwrap();
+        let printed = graph.print(&opts);
+
+        assert_eq!(
+            printed,
+            [
+                "(",
+                "  let a = < a > in 0,",
+                "  let b = < b > in {",
+                "    [=",
+                "      < c > | 0",
+                "      < d > | 0",
+                "    ]",
+                "    * < e > | 0",
+                "  }",
+                ")",
+                "* < f > | 0",
+            ]
+            .join("\n")
+        );
+        assert_eq!(parse_to_ast(printed).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_print_fully_parenthesized_round_trips() {
+        let opts = PrintOptions {
+            fully_parenthesized: true,
+            ..PrintOptions::default()
+        };
+        let graph =
+            parse_to_ast("<a> | 0 * { x | 0 * let b = <@{<c> | 0}> in 0 } * 0".into()).unwrap();
+        let printed = graph.print(&opts);
+
+        assert_eq!(
+            printed,
+            "{{< a > | 0} * {{x | 0} * {let b = < @ {< c > | 0} > in 0}}} * 0"
+        );
+        assert!(parse_to_ast(printed).unwrap().alpha_eq(&graph));
+    }
+
+    #[test]
+    fn test_cached_printer_matches_full_print_after_edits() {
+        let options = [
+            PrintOptions::default(),
+            PrintOptions {
+                multiline: true,
+                omit_trailing_nil: true,
+                ..PrintOptions::default()
+            },
+            PrintOptions {
+                fully_parenthesized: true,
+                ..PrintOptions::default()
+            },
+        ];
+        let edit = parse_to_ast("{ context \"z\" for z in <z> | 0 }".into()).unwrap();
+        let chains: Vec<_> = (0..100).map(|i| format!("<v{i}> | w{i} | 0")).collect();
+        let large = format!("let G = <g> | 0 in {{ {} }}", chains.join(" * "));
+        let samples = SAMPLES.iter().copied().chain([
+            "let a = <v> in a | 0 * let b = <v> in b | 0",
+            "{ (let a = <a> in <b> | <c> | 0, let b = <b> in { [= <c> | 0 <d> | 0] * <e> | <f> | 0 }) * { <g> | 0 * <h> | <i> | 0 } }",
+            &large,
+        ]);
+
+        for opts in options {
+            let mut printer = CachedPrinter::new(opts.clone());
+            for code in samples.clone() {
+                let mut graph = parse_to_ast(code.into()).unwrap();
+                assert_eq!(printer.print(&graph), graph.print(&opts), "{code}");
+                assert_eq!(printer.print(&graph), graph.print(&opts), "{code}");
+
+                let nils: Vec<_> = graph
+                    .subgraphs_with_paths()
+                    .into_iter()
+                    .filter(|(_, node)| **node == Graph::Nil)
+                    .map(|(path, _)| path)
+                    .collect();
+                for path in nils {
+                    *graph.node_at_mut(&path).unwrap() = edit.clone();
+                    assert_eq!(
+                        printer.print(&graph),
+                        graph.print(&opts),
+                        "{code} at {path:?}"
+                    );
+                }
+
+                // Every node is kept at most once, in subtrees of bounded size.
+                let kept = printer.fragments.values().map(|fragment| fragment.nodes);
+                assert!(kept.clone().sum::<usize>() <= graph.number_nodes().len());
+                assert!(kept.clone().all(|nodes| nodes <= MAX_CACHED_NODES));
+            }
+            assert!(printer.fragments.len() > 1);
+        }
+    }
+
+    #[test]
+    fn test_print_multiline_with_crlf() {
+        let opts = PrintOptions {
+            multiline: true,
+            line_ending: LineEnding::CrLf,
+            ..PrintOptions::default()
+        };
+        let graph = parse_to_ast("(let a = <a> in 0, let b = <b> in 0)".into()).unwrap();
+        let printed = graph.print(&opts);
+
+        assert_eq!(
+            printed,
+            "(\r\n  let a = < a > in 0,\r\n  let b = < b > in 0\r\n)"
+        );
+        assert_eq!(parse_to_ast(printed).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_sourcemap_spans_cover_node_text() {
+        let graph =
+            parse_to_ast("{ <a> | 0 * let x = <b> in <@{<c> | 0}> | 0 } * y | 0".into()).unwrap();
+        let (printed, sourcemap) = graph.to_graphl_with_sourcemap(&PrintOptions::default());
+        let node = |path: &[usize]| sourcemap.iter().find(|(p, _)| p == path).unwrap().1;
+        let text = |path: &[usize]| &printed[node(path).span.range()];
+        let head = |path: &[usize]| node(path).head.map(|head| &printed[head.range()]);
+
+        assert_eq!(printed, graph.print(&PrintOptions::default()));
+        assert_eq!(sourcemap.len(), graph.number_nodes().len());
+        assert_eq!(text(&[]), printed);
+        assert_eq!(text(&[0, 0]), "< a > | 0");
+        assert_eq!(text(&[0, 0, 0]), "0");
+        assert_eq!(text(&[0, 1]), "let x = < b > in < @ < c > | 0 > | 0");
+        assert_eq!(text(&[0, 1, 0]), "< @ < c > | 0 > | 0");
+        assert_eq!(text(&[1]), "y | 0");
+
+        assert_eq!(head(&[0, 0]), Some("< a >"));
+        assert_eq!(head(&[0, 1, 0]), Some("< @ < c > | 0 >"));
+        assert_eq!(head(&[1]), Some("y"));
+        assert_eq!(head(&[0, 0, 0]), None);
+        assert_eq!(head(&[0, 1]), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Parsing inputs that hold several top-level graphs.
+
+use crate::ast::{Error, Graph};
+use crate::parse_str;
+
+/// Parses a source file holding a sequence of top-level graphs.
+///
+/// The GraphL grammar has no program production: its entry point is a
+/// single `Graph`. A program is therefore read the same way as
+/// [`parse_many`], and this function only exists so that callers can express
+/// the intent of parsing a whole file.
+///
+/// # Errors
+///
+/// Fails on the first statement that does not parse, see [`parse_many`].
+pub fn parse_program(code: &str) -> Result<Vec<Graph>, Error> {
+    parse_many(code)
+}
+
+/// Parses graphs separated by blank lines.
+///
+/// The input is split at every line that contains only whitespace, unless it
+/// is inside braces, parentheses, brackets, a string literal or a block
+/// comment. Each piece is then parsed on its own, preserving order. Pieces
+/// holding nothing but whitespace and comments are skipped.
+///
+/// # Errors
+///
+/// A piece that is not valid GraphL is reported as
+/// [`Error::InvalidStatement`] with its index and byte offset in `code`, and
+/// the line and column in `code` where the parser stopped, or where the piece
+/// starts if the parser gave no position. A NUL byte is reported as
+/// [`Error::InvalidCString`] with its position in `code`.
+pub fn parse_many(code: &str) -> Result<Vec<Graph>, Error> {
+    split_statements(code)
+        .into_iter()
+        .enumerate()
+        .map(|(index, (offset, statement))| {
+            parse_str(statement).map_err(|err| {
+                // Statements start at the beginning of a line, so only the
+                // line numbers are shifted.
+                let lines_before = code[..offset].matches('\n').count();
+                match err {
+                    Error::ParseFailed {
+                        line,
+                        column,
+                        message,
+                    } => Error::InvalidStatement {
+                        index,
+                        offset,
+                        line: lines_before + line,
+                        column,
+                        message,
+                    },
+                    Error::InvalidGraphL => Error::InvalidStatement {
+                        index,
+                        offset,
+                        line: lines_before + 1,
+                        column: 1,
+                        message: String::new(),
+                    },
+                    Error::InvalidCString { position } => Error::InvalidCString {
+                        position: offset + position,
+                    },
+                    err => err,
+                }
+            })
+        })
+        .collect()
+}
+
+enum State {
+    Code,
+    String { escaped: bool },
+    LineComment,
+    BlockComment,
+}
+
+/// Returns the non-empty statements of `code` with their byte offsets.
+fn split_statements(code: &str) -> Vec<(usize, &str)> {
+    let mut statements = Vec::new();
+    let mut state = State::Code;
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut has_code = false;
+    let mut blank_line = true;
+    let mut chars = code.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match state {
+            State::String { escaped } => {
+                state = match c {
+                    '"' if !escaped => State::Code,
+                    '\\' => State::String { escaped: !escaped },
+                    _ => State::String { escaped: false },
+                };
+                if c != '\n' {
+                    // A line holding part of a string literal is not blank.
+                    blank_line = false;
+                    continue;
+                }
+            }
+            State::BlockComment => {
+                if c == '*' && chars.next_if(|&(_, c)| c == '/').is_some() {
+                    state = State::Code;
+                }
+                blank_line = false;
+                continue;
+            }
+            State::LineComment if c != '\n' => continue,
+            State::LineComment | State::Code => {}
+        }
+
+        match c {
+            '\n' => {
+                if matches!(state, State::Code) && blank_line && depth == 0 {
+                    if has_code {
+                        statements.push((start, &code[start..index]));
+                    }
+                    start = index + 1;
+                    has_code = false;
+                }
+                if matches!(state, State::LineComment) {
+                    state = State::Code;
+                }
+                blank_line = true;
+            }
+            c if c.is_whitespace() => {}
+            '/' if chars.next_if(|&(_, c)| c == '/').is_some() => {
+                state = State::LineComment;
+                blank_line = false;
+            }
+            '/' if chars.next_if(|&(_, c)| c == '*').is_some() => {
+                state = State::BlockComment;
+                blank_line = false;
+            }
+            _ => {
+                match c {
+                    '"' => state = State::String { escaped: false },
+                    '{' | '(' | '[' => depth += 1,
+                    '}' | ')' | ']' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                has_code = true;
+                blank_line = false;
+            }
+        }
+    }
+
+    if has_code {
+        statements.push((start, &code[start..]));
+    }
+
+    statements
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ast::Error;
+    use crate::{parse_many, parse_program, parse_str, parse_to_ast};
+
+    #[test]
+    fn test_parse_program_two_statements() {
+        let program = parse_program(
+            "// header\n<a> | 0\n\n{\n  (let a = <a> in 0,\n\n   let b = <b> in 0)\n}\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            program,
+            [
+                parse_to_ast("<a> | 0".into()).unwrap(),
+                parse_to_ast("(let a = <a> in 0, let b = <b> in 0)".into()).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_program_reports_failing_statement() {
+        let code = "<a> | 0\n\n<b> |\n";
+
+        assert!(matches!(
+            parse_program(code),
+            Err(Error::InvalidStatement {
+                index: 1,
+                offset: 9,
+                line: 3,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_parse_many_keeps_statement_after_multiline_string() {
+        let code = "context \"x\n\"\nfor a in <a> | 0";
+
+        assert_eq!(parse_many(code).unwrap(), [parse_str(code).unwrap()]);
+    }
+
+    #[test]
+    fn test_parse_many_reports_whole_file_positions() {
+        let err = parse_many("<a> | 0\n\n<b> | 0 *\n  * <c> | 0").unwrap_err();
+
+        assert!(
+            matches!(
+                err,
+                Error::InvalidStatement {
+                    index: 1,
+                    offset: 9,
+                    line: 4,
+                    column: 3,
+                    ref message,
+                } if message == "syntax error"
+            ),
+            "{err:?}"
+        );
+    }
+}
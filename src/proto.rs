@@ -0,0 +1,636 @@
+//! Protobuf encoding of the AST.
+//!
+//! The schema is `proto/graph.proto`. Its Rust types are generated into
+//! [`schema`] by `prost-build` when the crate is built with the `proto`
+//! feature, which requires `protoc` on the `PATH` or in the `PROTOC`
+//! environment variable.
+
+// Which message fields prost-build boxes depends on how it breaks the
+// recursion of the schema, so conversions go through `Into<Box<_>>`, which
+// is a no-op for the fields it does box.
+#![allow(clippy::useless_conversion)]
+
+use prost::Message as _;
+
+use crate::ast::node::Node;
+use crate::ast::{
+    Binding,
+    Error,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    Vertex,
+};
+
+/// The message types generated from `proto/graph.proto`.
+#[allow(clippy::all, clippy::pedantic)]
+pub mod schema {
+    include!(concat!(env!("OUT_DIR"), "/graphl.rs"));
+}
+
+/// Encodes `graph` as a `graphl.Graph` message.
+pub fn encode(graph: &Graph) -> Vec<u8> {
+    to_proto(graph).encode_to_vec()
+}
+
+/// Decodes a `graphl.Graph` message.
+///
+/// Every level of a graph nests at least two messages, so prost's default
+/// limit of 100 nested messages would reject graphs that [`encode`] accepts;
+/// the crate enables prost's `no-recursion-limit` feature to lift it. prost
+/// still decodes nested messages recursively, so a message from an
+/// untrusted source should have its size bounded before it is decoded.
+///
+/// # Errors
+///
+/// Returns [`Error::Proto`] if `bytes` is not a valid message or a field
+/// required by the AST is missing.
+pub fn decode(bytes: &[u8]) -> Result<Graph, Error> {
+    let message = schema::Graph::decode(bytes).map_err(|err| Error::Proto {
+        message: err.to_string(),
+    })?;
+    from_proto(message)
+}
+
+/// Converts `graph` into its generated protobuf type.
+///
+/// The nodes are converted bottom-up from an explicit stack, so deep graphs
+/// cannot overflow the call stack.
+pub fn to_proto(graph: &Graph) -> schema::Graph {
+    let mut done = Messages::default();
+    let mut stack = vec![(Node::Graph(graph), false)];
+
+    while let Some((node, children_done)) = stack.pop() {
+        if children_done {
+            done.build(node);
+        } else {
+            stack.push((node, true));
+            stack.extend(
+                node.children()
+                    .into_iter()
+                    .rev()
+                    .map(|child| (child, false)),
+            );
+        }
+    }
+
+    done.graphs.pop().expect("the root is converted last")
+}
+
+/// The messages converted by [`to_proto`] that still wait for their parent,
+/// by type. A parent takes its children from the ends of the stacks.
+#[derive(Default)]
+struct Messages {
+    graphs: Vec<schema::Graph>,
+    bindings: Vec<schema::Binding>,
+    vertices: Vec<schema::Vertex>,
+    names: Vec<schema::Name>,
+}
+
+impl Messages {
+    fn graph(&mut self) -> schema::Graph {
+        self.graphs.pop().expect("children are converted first")
+    }
+
+    fn binding(&mut self) -> schema::Binding {
+        self.bindings.pop().expect("children are converted first")
+    }
+
+    fn vertex(&mut self) -> schema::Vertex {
+        self.vertices.pop().expect("children are converted first")
+    }
+
+    fn name(&mut self) -> schema::Name {
+        self.names.pop().expect("children are converted first")
+    }
+
+    /// Converts `node` from the messages of its children.
+    fn build(&mut self, node: Node<'_>) {
+        match node {
+            Node::Graph(graph) => {
+                let graph = self.build_graph(graph);
+                self.graphs.push(graph);
+            }
+            Node::Binding(binding) => {
+                let binding = schema::Binding {
+                    graph: Some(self.graph().into()),
+                    var: binding.var.clone(),
+                    vertex: Some(self.vertex().into()),
+                };
+                self.bindings.push(binding);
+            }
+            Node::Vertex(_) => {
+                let vertex = schema::Vertex {
+                    name: Some(self.name().into()),
+                };
+                self.vertices.push(vertex);
+            }
+            Node::Name(name) => {
+                use self::schema::name::Kind;
+
+                let kind = match name {
+                    Name::Wildcard => Kind::Wildcard(schema::Unit {}),
+                    Name::VVar { value } => Kind::VVar(value.clone()),
+                    Name::GVar { value } => Kind::GVar(value.clone()),
+                    Name::QuoteGraph { .. } => Kind::QuoteGraph(self.graph().into()),
+                    Name::QuoteVertex { .. } => Kind::QuoteVertex(self.vertex().into()),
+                };
+                self.names.push(schema::Name { kind: Some(kind) });
+            }
+        }
+    }
+
+    /// Converts `graph`, taking its children in the reverse of the order of
+    /// [`Node::children`].
+    fn build_graph(&mut self, graph: &Graph) -> schema::Graph {
+        use self::schema::graph::Kind;
+
+        let kind = match graph {
+            Graph::Nil => Kind::Nil(schema::Unit {}),
+            Graph::Vertex(_) => Kind::Vertex(
+                schema::GVertex {
+                    graph: Some(self.graph().into()),
+                    vertex: Some(self.vertex().into()),
+                }
+                .into(),
+            ),
+            Graph::Var(var) => Kind::Var(
+                schema::GVar {
+                    graph: Some(self.graph().into()),
+                    var: var.var.clone(),
+                }
+                .into(),
+            ),
+            Graph::Nominate(_) => Kind::Nominate(self.binding().into()),
+            Graph::EdgeAnon(_) => {
+                let binding_2 = self.binding();
+                let binding_1 = self.binding();
+                Kind::EdgeAnon(
+                    schema::GEdgeAnon {
+                        binding_1: Some(binding_1.into()),
+                        binding_2: Some(binding_2.into()),
+                    }
+                    .into(),
+                )
+            }
+            Graph::EdgeNamed(_) => {
+                let binding_2 = self.binding();
+                let binding_1 = self.binding();
+                Kind::EdgeNamed(
+                    schema::GEdgeNamed {
+                        binding_1: Some(binding_1.into()),
+                        binding_2: Some(binding_2.into()),
+                        name: Some(self.name().into()),
+                    }
+                    .into(),
+                )
+            }
+            Graph::RuleAnon(_) => {
+                let graph_2 = self.graph();
+                let graph_1 = self.graph();
+                Kind::RuleAnon(
+                    schema::GRuleAnon {
+                        graph_1: Some(graph_1.into()),
+                        graph_2: Some(graph_2.into()),
+                    }
+                    .into(),
+                )
+            }
+            Graph::RuleNamed(_) => {
+                let graph_2 = self.graph();
+                let graph_1 = self.graph();
+                Kind::RuleNamed(
+                    schema::GRuleNamed {
+                        graph_1: Some(graph_1.into()),
+                        graph_2: Some(graph_2.into()),
+                        name: Some(self.name().into()),
+                    }
+                    .into(),
+                )
+            }
+            Graph::Subgraph(binding) => {
+                let graph_2 = self.graph();
+                let graph_1 = self.graph();
+                Kind::Subgraph(
+                    schema::GraphBinding {
+                        graph_1: Some(graph_1.into()),
+                        graph_2: Some(graph_2.into()),
+                        var: binding.var.clone(),
+                    }
+                    .into(),
+                )
+            }
+            Graph::Tensor(_) => {
+                let graph_2 = self.graph();
+                let graph_1 = self.graph();
+                Kind::Tensor(
+                    schema::GTensor {
+                        graph_1: Some(graph_1.into()),
+                        graph_2: Some(graph_2.into()),
+                    }
+                    .into(),
+                )
+            }
+            Graph::Context(context) => Kind::Context(
+                schema::GContext {
+                    graph: Some(self.graph().into()),
+                    name: Some(self.name().into()),
+                    string: context.string.clone(),
+                }
+                .into(),
+            ),
+        };
+
+        schema::Graph { kind: Some(kind) }
+    }
+}
+
+/// Converts a generated protobuf graph back into the AST.
+///
+/// Like [`to_proto`], this keeps the messages left to convert on an
+/// explicit stack instead of recursing.
+///
+/// # Errors
+///
+/// Returns [`Error::Proto`] naming a missing message field or `oneof`;
+/// proto3 cannot require them. Messages are checked parents first, so the
+/// field reported is the first missing one in pre-order.
+pub fn from_proto(graph: schema::Graph) -> Result<Graph, Error> {
+    let mut done = Nodes::default();
+    let mut stack = vec![Step::Split(Message::Graph(graph))];
+
+    while let Some(step) = stack.pop() {
+        match step {
+            Step::Split(message) => {
+                let (shape, children) = message.split()?;
+                stack.push(Step::Join(shape));
+                stack.extend(children.into_iter().rev().map(Step::Split));
+            }
+            Step::Join(shape) => done.join(shape),
+        }
+    }
+
+    Ok(done.graphs.pop().expect("the root is converted last"))
+}
+
+/// A message of any type, see [`from_proto`].
+enum Message {
+    Graph(schema::Graph),
+    Binding(schema::Binding),
+    Vertex(schema::Vertex),
+    Name(schema::Name),
+}
+
+/// A node of the AST without its children: what is left of a message once
+/// its child messages have been moved out.
+enum Shape {
+    Nil,
+    Vertex,
+    Var(String),
+    Nominate,
+    EdgeAnon,
+    EdgeNamed,
+    RuleAnon,
+    RuleNamed,
+    Subgraph(String),
+    Tensor,
+    Context(String),
+    Binding(String),
+    VertexName,
+    Name(NameShape),
+}
+
+/// A [`Name`] without its quoted graph or vertex.
+enum NameShape {
+    Wildcard,
+    VVar(String),
+    GVar(String),
+    QuoteGraph,
+    QuoteVertex,
+}
+
+enum Step {
+    /// Moves the children out of a message.
+    Split(Message),
+    /// Builds a node once its children are built.
+    Join(Shape),
+}
+
+impl Message {
+    /// Separates this message into its shape and its child messages, in the
+    /// order of the fields of the AST.
+    fn split(self) -> Result<(Shape, Vec<Message>), Error> {
+        use self::schema::graph::Kind as GraphKind;
+        use self::schema::name::Kind as NameKind;
+
+        Ok(match self {
+            Message::Graph(graph) => match graph.kind.ok_or_else(|| missing("Graph.kind"))? {
+                GraphKind::Nil(_) => (Shape::Nil, vec![]),
+                GraphKind::Vertex(vertex) => {
+                    let vertex: schema::GVertex = unbox(vertex);
+                    let graph = graph_field(vertex.graph, "GVertex.graph")?;
+                    let vertex = required(vertex.vertex, "GVertex.vertex")?;
+                    (Shape::Vertex, vec![Message::Vertex(vertex), graph])
+                }
+                GraphKind::Var(var) => {
+                    let var: schema::GVar = unbox(var);
+                    let graph = graph_field(var.graph, "GVar.graph")?;
+                    (Shape::Var(var.var), vec![graph])
+                }
+                GraphKind::Nominate(binding) => {
+                    (Shape::Nominate, vec![Message::Binding(unbox(binding))])
+                }
+                GraphKind::EdgeAnon(edge) => {
+                    let edge: schema::GEdgeAnon = unbox(edge);
+                    let binding_1 = required(edge.binding_1, "GEdgeAnon.binding_1")?;
+                    let binding_2 = required(edge.binding_2, "GEdgeAnon.binding_2")?;
+                    (
+                        Shape::EdgeAnon,
+                        vec![Message::Binding(binding_1), Message::Binding(binding_2)],
+                    )
+                }
+                GraphKind::EdgeNamed(edge) => {
+                    let edge: schema::GEdgeNamed = unbox(edge);
+                    let binding_1 = required(edge.binding_1, "GEdgeNamed.binding_1")?;
+                    let binding_2 = required(edge.binding_2, "GEdgeNamed.binding_2")?;
+                    let name = required(edge.name, "GEdgeNamed.name")?;
+                    (
+                        Shape::EdgeNamed,
+                        vec![
+                            Message::Name(name),
+                            Message::Binding(binding_1),
+                            Message::Binding(binding_2),
+                        ],
+                    )
+                }
+                GraphKind::RuleAnon(rule) => {
+                    let rule: schema::GRuleAnon = unbox(rule);
+                    let graph_1 = graph_field(rule.graph_1, "GRuleAnon.graph_1")?;
+                    let graph_2 = graph_field(rule.graph_2, "GRuleAnon.graph_2")?;
+                    (Shape::RuleAnon, vec![graph_1, graph_2])
+                }
+                GraphKind::RuleNamed(rule) => {
+                    let rule: schema::GRuleNamed = unbox(rule);
+                    let graph_1 = graph_field(rule.graph_1, "GRuleNamed.graph_1")?;
+                    let graph_2 = graph_field(rule.graph_2, "GRuleNamed.graph_2")?;
+                    let name = required(rule.name, "GRuleNamed.name")?;
+                    (
+                        Shape::RuleNamed,
+                        vec![Message::Name(name), graph_1, graph_2],
+                    )
+                }
+                GraphKind::Subgraph(binding) => {
+                    let binding: schema::GraphBinding = unbox(binding);
+                    let graph_1 = graph_field(binding.graph_1, "GraphBinding.graph_1")?;
+                    let graph_2 = graph_field(binding.graph_2, "GraphBinding.graph_2")?;
+                    (Shape::Subgraph(binding.var), vec![graph_1, graph_2])
+                }
+                GraphKind::Tensor(tensor) => {
+                    let tensor: schema::GTensor = unbox(tensor);
+                    let graph_1 = graph_field(tensor.graph_1, "GTensor.graph_1")?;
+                    let graph_2 = graph_field(tensor.graph_2, "GTensor.graph_2")?;
+                    (Shape::Tensor, vec![graph_1, graph_2])
+                }
+                GraphKind::Context(context) => {
+                    let context: schema::GContext = unbox(context);
+                    let graph = graph_field(context.graph, "GContext.graph")?;
+                    let name = required(context.name, "GContext.name")?;
+                    (
+                        Shape::Context(context.string),
+                        vec![Message::Name(name), graph],
+                    )
+                }
+            },
+            Message::Binding(binding) => {
+                let graph = graph_field(binding.graph, "Binding.graph")?;
+                let vertex = required(binding.vertex, "Binding.vertex")?;
+                (
+                    Shape::Binding(binding.var),
+                    vec![Message::Vertex(vertex), graph],
+                )
+            }
+            Message::Vertex(vertex) => {
+                let name = required(vertex.name, "Vertex.name")?;
+                (Shape::VertexName, vec![Message::Name(name)])
+            }
+            Message::Name(name) => match name.kind.ok_or_else(|| missing("Name.kind"))? {
+                NameKind::Wildcard(_) => (Shape::Name(NameShape::Wildcard), vec![]),
+                NameKind::VVar(value) => (Shape::Name(NameShape::VVar(value)), vec![]),
+                NameKind::GVar(value) => (Shape::Name(NameShape::GVar(value)), vec![]),
+                NameKind::QuoteGraph(graph) => (
+                    Shape::Name(NameShape::QuoteGraph),
+                    vec![Message::Graph(unbox(graph))],
+                ),
+                NameKind::QuoteVertex(vertex) => (
+                    Shape::Name(NameShape::QuoteVertex),
+                    vec![Message::Vertex(unbox(vertex))],
+                ),
+            },
+        })
+    }
+}
+
+/// The nodes built by [`from_proto`] that still wait for their parent, by
+/// type, like [`Messages`].
+#[derive(Default)]
+struct Nodes {
+    graphs: Vec<Graph>,
+    bindings: Vec<Binding>,
+    vertices: Vec<Vertex>,
+    names: Vec<Name>,
+}
+
+impl Nodes {
+    fn graph(&mut self) -> Box<Graph> {
+        Box::new(self.graphs.pop().expect("children are built first"))
+    }
+
+    fn binding(&mut self) -> Binding {
+        self.bindings.pop().expect("children are built first")
+    }
+
+    fn vertex(&mut self) -> Vertex {
+        self.vertices.pop().expect("children are built first")
+    }
+
+    fn name(&mut self) -> Name {
+        self.names.pop().expect("children are built first")
+    }
+
+    /// Builds the node of `shape` from its children, which were built in
+    /// the order [`Message::split`] returned them.
+    fn join(&mut self, shape: Shape) {
+        let graph = match shape {
+            Shape::Nil => Graph::Nil,
+            Shape::Vertex => Graph::Vertex(GVertex {
+                graph: self.graph(),
+                vertex: self.vertex(),
+            }),
+            Shape::Var(var) => Graph::Var(GVar {
+                graph: self.graph(),
+                var,
+            }),
+            Shape::Nominate => Graph::Nominate(self.binding()),
+            Shape::EdgeAnon => {
+                let binding_2 = self.binding();
+                Graph::EdgeAnon(GEdgeAnon {
+                    binding_1: self.binding(),
+                    binding_2,
+                })
+            }
+            Shape::EdgeNamed => {
+                let binding_2 = self.binding();
+                Graph::EdgeNamed(GEdgeNamed {
+                    binding_1: self.binding(),
+                    binding_2,
+                    name: self.name(),
+                })
+            }
+            Shape::RuleAnon => {
+                let graph_2 = self.graph();
+                Graph::RuleAnon(GRuleAnon {
+                    graph_1: self.graph(),
+                    graph_2,
+                })
+            }
+            Shape::RuleNamed => {
+                let graph_2 = self.graph();
+                Graph::RuleNamed(GRuleNamed {
+                    graph_1: self.graph(),
+                    graph_2,
+                    name: self.name(),
+                })
+            }
+            Shape::Subgraph(var) => {
+                let graph_2 = self.graph();
+                Graph::Subgraph(GraphBinding {
+                    graph_1: self.graph(),
+                    graph_2,
+                    var,
+                })
+            }
+            Shape::Tensor => {
+                let graph_2 = self.graph();
+                Graph::Tensor(GTensor {
+                    graph_1: self.graph(),
+                    graph_2,
+                })
+            }
+            Shape::Context(string) => Graph::Context(GContext {
+                graph: self.graph(),
+                name: self.name(),
+                string,
+            }),
+            Shape::Binding(var) => {
+                let binding = Binding {
+                    graph: self.graph(),
+                    var,
+                    vertex: self.vertex(),
+                };
+                self.bindings.push(binding);
+                return;
+            }
+            Shape::VertexName => {
+                let vertex = Vertex { name: self.name() };
+                self.vertices.push(vertex);
+                return;
+            }
+            Shape::Name(shape) => {
+                let name = self.join_name(shape);
+                self.names.push(name);
+                return;
+            }
+        };
+        self.graphs.push(graph);
+    }
+
+    fn join_name(&mut self, shape: NameShape) -> Name {
+        match shape {
+            NameShape::Wildcard => Name::Wildcard,
+            NameShape::VVar(value) => Name::VVar { value },
+            NameShape::GVar(value) => Name::GVar { value },
+            NameShape::QuoteGraph => Name::QuoteGraph {
+                value: self.graph(),
+            },
+            NameShape::QuoteVertex => Name::QuoteVertex {
+                value: Box::new(self.vertex()),
+            },
+        }
+    }
+}
+
+/// Moves the graph out of a required field, wrapped for [`Message::split`].
+fn graph_field(
+    field: Option<impl Into<Box<schema::Graph>>>,
+    context: &str,
+) -> Result<Message, Error> {
+    required(field, context).map(Message::Graph)
+}
+
+fn required<T>(field: Option<impl Into<Box<T>>>, context: &str) -> Result<T, Error> {
+    field.map(unbox).ok_or_else(|| missing(context))
+}
+
+/// Moves a message out of its field, whether or not it is boxed.
+fn unbox<T>(value: impl Into<Box<T>>) -> T {
+    *value.into()
+}
+
+fn missing(context: &str) -> Error {
+    Error::Proto {
+        message: format!("missing field {context}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, encode, schema};
+    use crate::ast::{Error, GraphBuilder};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_proto_round_trip() {
+        let graph = parse_to_ast(
+            r#"{ context "c" for @<q> in n(let a = <@{<b> | 0}> in x | 0, let b = <_> in { [= 0 <Y> | 0] }) * let G = <g> | 0 in { (let c = <c> in 0, let d = <d> in 0) } }"#
+                .into(),
+        )
+        .unwrap();
+
+        assert_eq!(decode(&encode(&graph)).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_proto_round_trip_deep_graph() {
+        // Nests 400 messages, four times prost's default recursion limit.
+        let chain = (0..200).fold(GraphBuilder::nil(), |graph, i| {
+            if i % 2 == 0 {
+                graph.prefix_vertex("a")
+            } else {
+                graph.prefix_nominate("x", "b")
+            }
+        });
+        let graph = chain.build();
+
+        assert_eq!(decode(&encode(&graph)).unwrap(), graph);
+    }
+
+    #[test]
+    fn test_proto_missing_kind() {
+        use prost::Message;
+
+        let bytes = schema::Graph { kind: None }.encode_to_vec();
+
+        assert!(matches!(
+            decode(&bytes),
+            Err(Error::Proto { message }) if message == "missing field Graph.kind"
+        ));
+    }
+}
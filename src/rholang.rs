@@ -0,0 +1,892 @@
+//! Translation of GraphL into Rholang.
+//!
+//! A graph becomes the body of a single contract. Every vertex name and every
+//! free variable is declared as a channel by one `new` at the top of that
+//! body, and the graph constructs are mapped as follows:
+//!
+//! * `0` is `Nil`, and a tensor `g1 * g2` is the parallel composition of both
+//!   sides;
+//! * a vertex `<a> | g` and a variable `x | g` send `Nil` on their channel in
+//!   parallel with `g`;
+//! * `let x = <a> in g` creates `x` and sends it the vertex channel `a`;
+//! * an edge runs its two bindings in parallel, under a `new` for its name
+//!   when it has one;
+//! * `let X = g1 in g2` declares a contract `X` that runs `g1`;
+//! * a context keeps its scope and drops the annotation.
+//!
+//! Rules and quoted names have no Rholang counterpart and are rejected with
+//! [`Error::Unsupported`]; [`check_translatable`] lists all of them up front.
+//!
+//! The sends and the channel of a named edge come from a [`RholangStrategy`],
+//! so they can be adapted to local conventions with [`to_rholang_with`]
+//! while the overall layout stays the same.
+//!
+//! [`from_graph`] produces the same process without the contract around it.
+//! Both are assembled from the nodes in the order the [`Walker`] visits them.
+
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::ast::node::Node;
+use crate::ast::{
+    Binding,
+    Error,
+    GContext,
+    GEdgeAnon,
+    GEdgeNamed,
+    GRuleAnon,
+    GRuleNamed,
+    GTensor,
+    GVar,
+    GVertex,
+    Graph,
+    GraphBinding,
+    Name,
+    NodeKind,
+};
+use crate::parse_str;
+use crate::printer::write_escaped;
+use crate::visitor::Visitor;
+use crate::walker::Walker;
+
+/// The outcome of [`compile`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileResult {
+    /// The parsed graph.
+    pub ast: Graph,
+    /// The generated contract.
+    pub rholang: String,
+    /// Problems that did not prevent code generation.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A warning reported by [`compile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Diagnostic {
+    /// The variable is referenced outside the scope of any binder. It is
+    /// declared as a fresh channel of the contract.
+    UnboundVariable { var: String },
+}
+
+/// Parses `code`, checks its scoping and translates it into a contract named
+/// `contract_name`, in one go.
+///
+/// # Errors
+///
+/// Fails if `code` does not parse, see [`parse_str`], or if it cannot be
+/// translated, see [`to_rholang`].
+pub fn compile(code: &str, contract_name: &str) -> Result<CompileResult, Error> {
+    let ast = parse_str(code)?;
+    let rholang = to_rholang(&ast, contract_name)?;
+    let diagnostics = ast
+        .free_vars_in_order()
+        .into_iter()
+        .map(|var| Diagnostic::UnboundVariable { var })
+        .collect();
+
+    Ok(CompileResult {
+        ast,
+        rholang,
+        diagnostics,
+    })
+}
+
+/// A node that [`to_rholang`] cannot translate, see [`check_translatable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Untranslatable {
+    /// The path of the node, as in [`Graph::subgraph_at`].
+    pub path: Vec<usize>,
+    pub kind: NodeKind,
+    /// What is unsupported, `"rule"` or `"quoted name"`, as in the
+    /// [`Error::Unsupported`] translation fails with.
+    pub context: String,
+}
+
+/// Finds every node of `graph` that keeps it from being translated.
+///
+/// A rule is reported as a whole, without the nodes inside it. A quoted name
+/// is reported at the node that holds it: a vertex, a binding's `let` or
+/// edge, or a named edge. Quoted names of contexts are fine, as contexts are
+/// translated as their scope.
+///
+/// # Errors
+///
+/// Returns the untranslatable nodes in pre-order, if there are any.
+pub fn check_translatable(graph: &Graph) -> Result<(), Vec<Untranslatable>> {
+    let quoted = |name: &Name| matches!(name, Name::QuoteGraph { .. } | Name::QuoteVertex { .. });
+    let mut found: Vec<Untranslatable> = Vec::new();
+    let mut rule: Option<Vec<usize>> = None;
+
+    for (path, node) in graph.subgraphs_with_paths() {
+        if rule.as_ref().is_some_and(|rule| path.starts_with(rule)) {
+            continue;
+        }
+        let context = match node {
+            Graph::RuleAnon(_) | Graph::RuleNamed(_) => {
+                rule = Some(path.clone());
+                "rule"
+            }
+            Graph::Vertex(vertex) if quoted(&vertex.vertex.name) => "quoted name",
+            Graph::Nominate(binding) if quoted(&binding.vertex.name) => "quoted name",
+            Graph::EdgeAnon(edge)
+                if quoted(&edge.binding_1.vertex.name) || quoted(&edge.binding_2.vertex.name) =>
+            {
+                "quoted name"
+            }
+            Graph::EdgeNamed(edge)
+                if quoted(&edge.name)
+                    || quoted(&edge.binding_1.vertex.name)
+                    || quoted(&edge.binding_2.vertex.name) =>
+            {
+                "quoted name"
+            }
+            _ => continue,
+        };
+        found.push(Untranslatable {
+            kind: node.kind(),
+            path,
+            context: context.into(),
+        });
+    }
+
+    if found.is_empty() { Ok(()) } else { Err(found) }
+}
+
+/// The pieces of Rholang emitted for the individual graph constructs, see
+/// [`to_rholang_with`].
+///
+/// Every method has a default that produces the output of [`to_rholang`], so
+/// an implementation only overrides what it wants to change. Returned
+/// snippets are inserted as is and should be single-line processes.
+pub trait RholangStrategy {
+    /// The send for a vertex `<channel> | g`.
+    fn vertex(&self, channel: &str) -> String {
+        format!("{channel}!(Nil)")
+    }
+
+    /// The send for a variable `var | g`.
+    fn variable(&self, var: &str) -> String {
+        format!("{var}!(Nil)")
+    }
+
+    /// The send for a binding `let var = <vertex> in g`. A binding of the
+    /// wildcard vertex sends nothing and does not call this.
+    fn nomination(&self, var: &str, vertex: &str) -> String {
+        format!("{var}!(*{vertex})")
+    }
+
+    /// The channel declared by a `new` around the bindings of the named edge
+    /// `name(b1, b2)`, or `None` to declare nothing.
+    fn edge(&self, name: &str) -> Option<String> {
+        Some(name.to_owned())
+    }
+}
+
+/// The strategy behind [`to_rholang`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultStrategy;
+
+impl RholangStrategy for DefaultStrategy {}
+
+/// Translates `graph` into a contract named `contract_name`.
+///
+/// # Errors
+///
+/// Returns [`Error::Unsupported`] for rules and quoted names.
+pub fn to_rholang(graph: &Graph, contract_name: &str) -> Result<String, Error> {
+    to_rholang_with(graph, contract_name, &DefaultStrategy)
+}
+
+/// Translates `graph` into a contract named `contract_name`, taking the code
+/// for vertices, variables, bindings and named edges from `strategy`.
+///
+/// # Errors
+///
+/// Returns [`Error::Unsupported`] for rules and quoted names.
+pub fn to_rholang_with(
+    graph: &Graph,
+    contract_name: &str,
+    strategy: &impl RholangStrategy,
+) -> Result<String, Error> {
+    let body = generate(graph, strategy)?;
+
+    let mut out = String::from("contract @\"");
+    write_escaped(&mut out, contract_name).expect("writing to a String cannot fail");
+    out.push_str("\"() = ");
+    block(&mut out, &body, 0);
+    Ok(out)
+}
+
+impl Graph {
+    /// Translates this graph into a contract named `contract_name` with a
+    /// custom strategy, see [`to_rholang_with`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unsupported`] for rules and quoted names.
+    pub fn to_rholang_with(
+        &self,
+        contract_name: &str,
+        strategy: &impl RholangStrategy,
+    ) -> Result<String, Error> {
+        to_rholang_with(self, contract_name, strategy)
+    }
+}
+
+/// The structure of the contract [`to_rholang`] would generate.
+///
+/// A plan only describes the output: there is no way to render one. Its
+/// lists are flat, so they no longer say which `new` encloses which send or
+/// which of two bindings of the same variable a send uses, and they leave
+/// out the bodies of the contracts declared by graph bindings.
+/// To change the generated code, override the sends and edge channels with
+/// a [`RholangStrategy`] and translate with [`to_rholang_with`] instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RholangPlan {
+    /// The channels declared at the top of the contract: every vertex name
+    /// and free variable, in ascending order.
+    pub channels: Vec<String>,
+    /// The names introduced by nested `new`s, in source order: the variables
+    /// of bindings and graph bindings and the names of named edges.
+    pub arguments: Vec<String>,
+    /// Every send, in the order it is rendered, e.g. `a!(Nil)` or `x!(*a)`.
+    pub sends: Vec<String>,
+}
+
+/// Describes the contract generated for `graph` without rendering it.
+///
+/// Constructs that [`to_rholang`] rejects contribute nothing to the plan:
+/// rules and the contents of quoted names are skipped, so check the result
+/// of [`to_rholang`] before relying on a plan for such graphs. The plan is
+/// for inspection only, see [`RholangPlan`].
+pub fn plan(graph: &Graph) -> RholangPlan {
+    let mut plan = RholangPlan {
+        channels: channels(graph),
+        ..RholangPlan::default()
+    };
+    let mut stack = vec![Node::Graph(graph)];
+
+    while let Some(node) = stack.pop() {
+        match node {
+            Node::Graph(Graph::RuleAnon(_) | Graph::RuleNamed(_)) => continue,
+            Node::Graph(Graph::Vertex(vertex)) => {
+                if let Name::VVar { value } | Name::GVar { value } = &vertex.vertex.name {
+                    plan.sends.push(DefaultStrategy.vertex(value));
+                }
+            }
+            Node::Graph(Graph::Var(var)) => plan.sends.push(DefaultStrategy.variable(&var.var)),
+            Node::Graph(Graph::EdgeNamed(edge)) => {
+                if let Name::VVar { value } | Name::GVar { value } = &edge.name {
+                    plan.arguments.push(value.clone());
+                }
+            }
+            Node::Graph(Graph::Subgraph(binding)) => plan.arguments.push(binding.var.clone()),
+            Node::Binding(binding) => {
+                plan.arguments.push(binding.var.clone());
+                if let Name::VVar { value } | Name::GVar { value } = &binding.vertex.name {
+                    plan.sends
+                        .push(DefaultStrategy.nomination(&binding.var, value));
+                }
+            }
+            _ => {}
+        }
+        let children = node.children().into_iter().rev();
+        stack.extend(children.filter(|child| !matches!(child, Node::Vertex(_) | Node::Name(_))));
+    }
+
+    plan
+}
+
+/// Translates `graph` into the body of the contract [`to_rholang`] would
+/// generate: the `new` declaring its channels, unindented and without the
+/// surrounding `contract`.
+///
+/// This is the process [`to_rholang`] wraps in a contract, for where a
+/// contract is not wanted, e.g. to splice a graph into hand-written Rholang.
+///
+/// # Errors
+///
+/// Returns [`Error::Unsupported`] for rules and quoted names.
+pub fn from_graph(graph: &Graph) -> Result<String, Error> {
+    let mut out = String::new();
+    generate(graph, &DefaultStrategy)?.render(&mut out, 0);
+    Ok(out)
+}
+
+/// Builds the body of the contract for `graph`: its process, under a `new`
+/// for its [`channels`] if there are any.
+fn generate(graph: &Graph, strategy: &impl RholangStrategy) -> Result<Process, Error> {
+    let assembly = Walker::new(graph).try_visit(Assembly::default(), Generator { strategy })?;
+    let body = assembly
+        .process
+        .expect("the walker visits the root and all of its children");
+
+    let channels = channels(graph);
+    Ok(if channels.is_empty() {
+        body
+    } else {
+        Process::New {
+            names: channels,
+            body: Box::new(body),
+        }
+    })
+}
+
+/// The channels declared at the top of the contract for `graph`.
+fn channels(graph: &Graph) -> Vec<String> {
+    let vertices = Node::Graph(graph).preorder().filter_map(|node| match node {
+        Node::Vertex(vertex) => match &vertex.name {
+            Name::VVar { value } => Some(value.clone()),
+            _ => None,
+        },
+        _ => None,
+    });
+
+    vertices
+        .chain(graph.free_vars())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// The Rholang processes the translation produces.
+enum Process {
+    Nil,
+    Send(String),
+    New {
+        names: Vec<String>,
+        body: Box<Process>,
+    },
+    Contract {
+        name: String,
+        body: Box<Process>,
+    },
+    Par(VecDeque<Process>),
+}
+
+impl Process {
+    /// Composes `processes` in parallel, leaving out `Nil`s.
+    ///
+    /// Nested compositions are merged into the largest of them, so a long
+    /// chain of vertices is composed in linear time.
+    fn par(processes: impl IntoIterator<Item = Process>) -> Self {
+        let processes: Vec<_> = processes.into_iter().collect();
+        let mut merged = VecDeque::new();
+
+        for mut process in processes.into_iter().rev() {
+            match &mut process {
+                Process::Nil => {}
+                Process::Par(nested) if nested.len() >= merged.len() => {
+                    let mut nested = std::mem::take(nested);
+                    nested.append(&mut merged);
+                    merged = nested;
+                }
+                Process::Par(nested) => {
+                    while let Some(process) = nested.pop_back() {
+                        merged.push_front(process);
+                    }
+                }
+                _ => merged.push_front(process),
+            }
+        }
+
+        match merged.len() {
+            0 => Process::Nil,
+            1 => merged.pop_front().expect("length checked"),
+            _ => Process::Par(merged),
+        }
+    }
+
+    /// Writes this process, assuming the current line is already indented
+    /// to `depth`.
+    ///
+    /// The processes left to write are kept on an explicit stack, so deeply
+    /// nested processes cannot overflow the call stack.
+    fn render(&self, out: &mut String, depth: usize) {
+        let mut steps = vec![Step::Render(self, depth)];
+
+        while let Some(step) = steps.pop() {
+            match step {
+                Step::Render(process, depth) => match process {
+                    Process::Nil => out.push_str("Nil"),
+                    Process::Send(send) => out.push_str(send),
+                    Process::New { names, body } => {
+                        out.push_str(&format!("new {} in ", names.join(", ")));
+                        Step::open(out, &mut steps, body, depth);
+                    }
+                    Process::Contract { name, body } => {
+                        out.push_str(&format!("contract {name}() = "));
+                        Step::open(out, &mut steps, body, depth);
+                    }
+                    Process::Par(processes) => {
+                        for (i, process) in processes.iter().enumerate().rev() {
+                            steps.push(Step::Render(process, depth));
+                            if i > 0 {
+                                steps.push(Step::Separator(depth));
+                            }
+                        }
+                    }
+                },
+                Step::Separator(depth) => {
+                    out.push_str(" |\n");
+                    indent(out, depth);
+                }
+                Step::Close(depth) => {
+                    out.push('\n');
+                    indent(out, depth);
+                    out.push('}');
+                }
+            }
+        }
+    }
+}
+
+/// Drops the nested processes one at a time, for the same reason
+/// [`render`](Process::render) does not recurse.
+impl Drop for Process {
+    fn drop(&mut self) {
+        let mut stack = Vec::new();
+        self.detach(&mut stack);
+        while let Some(mut process) = stack.pop() {
+            process.detach(&mut stack);
+        }
+    }
+}
+
+impl Process {
+    /// Moves the nested processes onto `stack`, leaving `Nil`s behind.
+    fn detach(&mut self, stack: &mut Vec<Process>) {
+        match self {
+            Process::New { body, .. } | Process::Contract { body, .. } => {
+                if !matches!(**body, Process::Nil) {
+                    stack.push(std::mem::replace(body, Process::Nil));
+                }
+            }
+            Process::Par(processes) => stack.extend(processes.drain(..)),
+            Process::Nil | Process::Send(_) => {}
+        }
+    }
+}
+
+/// What is left to write of a process, see [`Process::render`].
+enum Step<'p> {
+    /// A process, on a line indented to the given depth.
+    Render(&'p Process, usize),
+    /// The ` |` between two parallel processes at the given depth.
+    Separator(usize),
+    /// The brace closing a block opened at the given depth.
+    Close(usize),
+}
+
+impl<'p> Step<'p> {
+    /// Opens a block for `body` one level deeper than `depth`, leaving its
+    /// contents and the closing brace to `steps`.
+    fn open(out: &mut String, steps: &mut Vec<Step<'p>>, body: &'p Process, depth: usize) {
+        out.push_str("{\n");
+        indent(out, depth + 1);
+        steps.push(Step::Close(depth));
+        steps.push(Step::Render(body, depth + 1));
+    }
+}
+
+/// Writes `body` in braces, one level deeper than `depth`.
+fn block(out: &mut String, body: &Process, depth: usize) {
+    out.push_str("{\n");
+    indent(out, depth + 1);
+    body.render(out, depth + 1);
+    out.push('\n');
+    indent(out, depth);
+    out.push('}');
+}
+
+fn indent(out: &mut String, depth: usize) {
+    out.extend(std::iter::repeat_n("  ", depth));
+}
+
+/// A construct whose process is waiting for the processes of its children.
+///
+/// The sends and the name of an edge are taken from the strategy when the
+/// construct is opened, so finishing it needs nothing but its children.
+enum Pending {
+    Vertex(Option<String>),
+    Var(String),
+    Binding { var: String, send: Option<String> },
+    Edge(Option<String>),
+    Subgraph(String),
+    Tensor,
+    Context,
+}
+
+impl Pending {
+    /// The number of child processes the construct is made of.
+    fn arity(&self) -> usize {
+        match self {
+            Pending::Edge(_) | Pending::Subgraph(_) | Pending::Tensor => 2,
+            _ => 1,
+        }
+    }
+
+    fn finish(self, mut children: Vec<Process>) -> Process {
+        let last = children.pop().expect("arity is at least one");
+        match self {
+            Pending::Vertex(send) => {
+                Process::par(send.map(Process::Send).into_iter().chain([last]))
+            }
+            Pending::Var(send) => Process::par([Process::Send(send), last]),
+            Pending::Binding { var, send } => Process::New {
+                names: vec![var],
+                body: Box::new(Process::par(
+                    send.map(Process::Send).into_iter().chain([last]),
+                )),
+            },
+            Pending::Edge(name) => {
+                let body = Process::par(children.into_iter().chain([last]));
+                match name {
+                    Some(name) => Process::New {
+                        names: vec![name],
+                        body: Box::new(body),
+                    },
+                    None => body,
+                }
+            }
+            Pending::Subgraph(var) => Process::New {
+                names: vec![var.clone()],
+                body: Box::new(Process::par([
+                    Process::Contract {
+                        name: var,
+                        body: Box::new(children.pop().expect("arity is two")),
+                    },
+                    last,
+                ])),
+            },
+            Pending::Tensor => Process::par(children.into_iter().chain([last])),
+            Pending::Context => last,
+        }
+    }
+}
+
+/// The accumulator of [`generate`].
+///
+/// The walker reports a node before its children, so every construct stays
+/// open on `open` until as many processes as its [`arity`](Pending::arity)
+/// have been finished beneath it.
+#[derive(Default)]
+struct Assembly {
+    open: Vec<(Pending, Vec<Process>)>,
+    /// The process of the whole graph, once it is finished.
+    process: Option<Process>,
+}
+
+impl Assembly {
+    fn open(mut self, pending: Pending) -> Self {
+        self.open.push((pending, Vec::new()));
+        self
+    }
+
+    /// Hands a finished process to the innermost open construct, finishing
+    /// every construct that becomes complete.
+    fn close(mut self, mut process: Process) -> Self {
+        while let Some((pending, children)) = self.open.last_mut() {
+            children.push(process);
+            if children.len() < pending.arity() {
+                return self;
+            }
+            let (pending, children) = self.open.pop().expect("checked above");
+            process = pending.finish(children);
+        }
+        self.process = Some(process);
+        self
+    }
+}
+
+struct Generator<'s, S> {
+    strategy: &'s S,
+}
+
+impl<'a, S: RholangStrategy> Visitor<'a, Assembly, Error> for Generator<'_, S> {
+    fn visit_nil(&self, acc: Assembly) -> Result<Assembly, Error> {
+        Ok(acc.close(Process::Nil))
+    }
+
+    fn visit_vertex(&self, acc: Assembly, vertex: &'a GVertex) -> Result<Assembly, Error> {
+        let send = channel(&vertex.vertex.name)?.map(|channel| self.strategy.vertex(&channel));
+        Ok(acc.open(Pending::Vertex(send)))
+    }
+
+    fn visit_var(&self, acc: Assembly, var: &'a GVar) -> Result<Assembly, Error> {
+        Ok(acc.open(Pending::Var(self.strategy.variable(&var.var))))
+    }
+
+    fn visit_nominate(&self, acc: Assembly, binding: &'a Binding) -> Result<Assembly, Error> {
+        let send = channel(&binding.vertex.name)?
+            .map(|vertex| self.strategy.nomination(&binding.var, &vertex));
+
+        Ok(acc.open(Pending::Binding {
+            var: binding.var.clone(),
+            send,
+        }))
+    }
+
+    fn visit_edge_anon(&self, acc: Assembly, _: &'a GEdgeAnon) -> Result<Assembly, Error> {
+        Ok(acc.open(Pending::Edge(None)))
+    }
+
+    fn visit_edge_named(&self, acc: Assembly, edge: &'a GEdgeNamed) -> Result<Assembly, Error> {
+        let name = channel(&edge.name)?.and_then(|name| self.strategy.edge(&name));
+        Ok(acc.open(Pending::Edge(name)))
+    }
+
+    fn visit_rule_anon(&self, _: Assembly, _: &'a GRuleAnon) -> Result<Assembly, Error> {
+        Err(Error::Unsupported {
+            context: "rule".into(),
+        })
+    }
+
+    fn visit_rule_named(&self, _: Assembly, _: &'a GRuleNamed) -> Result<Assembly, Error> {
+        Err(Error::Unsupported {
+            context: "rule".into(),
+        })
+    }
+
+    fn visit_subgraph(&self, acc: Assembly, subgraph: &'a GraphBinding) -> Result<Assembly, Error> {
+        Ok(acc.open(Pending::Subgraph(subgraph.var.clone())))
+    }
+
+    fn visit_tensor(&self, acc: Assembly, _: &'a GTensor) -> Result<Assembly, Error> {
+        Ok(acc.open(Pending::Tensor))
+    }
+
+    fn visit_context(&self, acc: Assembly, _: &'a GContext) -> Result<Assembly, Error> {
+        Ok(acc.open(Pending::Context))
+    }
+}
+
+/// The channel a name stands for; the wildcard stands for none.
+fn channel(name: &Name) -> Result<Option<String>, Error> {
+    match name {
+        Name::Wildcard => Ok(None),
+        Name::VVar { value } | Name::GVar { value } => Ok(Some(value.clone())),
+        Name::QuoteGraph { .. } | Name::QuoteVertex { .. } => Err(Error::Unsupported {
+            context: "quoted name".into(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        DefaultStrategy,
+        Diagnostic,
+        RholangPlan,
+        RholangStrategy,
+        Untranslatable,
+        check_translatable,
+        compile,
+        from_graph,
+        plan,
+        to_rholang,
+    };
+    use crate::ast::{Error, GraphBuilder, NodeKind};
+    use crate::parse_to_ast;
+
+    #[test]
+    fn test_compile_vertex() {
+        let result = compile("<a> | 0", "Main").unwrap();
+
+        assert_eq!(result.ast, parse_to_ast("<a> | 0".into()).unwrap());
+        assert_eq!(
+            result.rholang,
+            [
+                r#"contract @"Main"() = {"#,
+                "  new a in {",
+                "    a!(Nil)",
+                "  }",
+                "}",
+            ]
+            .join("\n")
+        );
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_compile_reports_unbound_variables() {
+        let result = compile("(let x = <a> in y | 0, let y = <_> in 0)", "Main").unwrap();
+
+        assert_eq!(
+            result.rholang,
+            [
+                r#"contract @"Main"() = {"#,
+                "  new a, y in {",
+                "    new x in {",
+                "      x!(*a) |",
+                "      y!(Nil)",
+                "    } |",
+                "    new y in {",
+                "      Nil",
+                "    }",
+                "  }",
+                "}",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            result.diagnostics,
+            [Diagnostic::UnboundVariable { var: "y".into() }]
+        );
+    }
+
+    #[test]
+    fn test_from_graph_vertex() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+
+        assert_eq!(from_graph(&graph).unwrap(), "new a in {\n  a!(Nil)\n}");
+        assert_eq!(from_graph(&crate::ast::Graph::Nil).unwrap(), "Nil");
+    }
+
+    #[test]
+    fn test_from_graph_matches_contract_body() {
+        for code in [
+            "<a> | x | 0 * let y = <b> in y | 0",
+            "e(let x = <a> in y | 0, let y = <_> in <c> | 0) * (let u = <u> in 0, let v = <v> in 0)",
+            "let G = <g> | 0 in { context \"c\" for f in <G> | 0 }",
+        ] {
+            let graph = parse_to_ast(code.into()).unwrap();
+            let body = from_graph(&graph).unwrap().replace('\n', "\n  ");
+
+            assert_eq!(
+                to_rholang(&graph, "Main").unwrap(),
+                format!("contract @\"Main\"() = {{\n  {body}\n}}"),
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_graph_rejects_rules_and_quoted_names() {
+        for code in ["<a> | [= <a> | 0 <b> | 0]", "r [= 0 0] * 0", "<@<c>> | 0"] {
+            let graph = parse_to_ast(code.into()).unwrap();
+
+            assert!(
+                matches!(from_graph(&graph), Err(Error::Unsupported { .. })),
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let graph = parse_to_ast("<a> | 0".into()).unwrap();
+
+        assert_eq!(
+            plan(&graph),
+            RholangPlan {
+                channels: vec!["a".into()],
+                arguments: vec![],
+                sends: vec!["a!(Nil)".into()],
+            }
+        );
+
+        let graph = parse_to_ast("e(let x = <a> in y | 0, let y = <b> in <c> | 0)".into()).unwrap();
+        assert_eq!(
+            plan(&graph),
+            RholangPlan {
+                channels: vec!["a".into(), "b".into(), "c".into(), "y".into()],
+                arguments: vec!["e".into(), "x".into(), "y".into()],
+                sends: vec![
+                    "x!(*a)".into(),
+                    "y!(Nil)".into(),
+                    "y!(*b)".into(),
+                    "c!(Nil)".into(),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_translate_deep_chains() {
+        let vertices = (0..10_000).fold(GraphBuilder::nil(), |graph, _| graph.prefix_vertex("a"));
+        let rholang = to_rholang(&vertices.build(), "Main").unwrap();
+
+        assert_eq!(rholang.matches("a!(Nil)").count(), 10_000);
+
+        // Every binding nests a `new`, so the output grows with the square of
+        // the depth through indentation alone.
+        let bindings = (0..3_000).fold(GraphBuilder::nil(), |graph, _| {
+            graph.prefix_nominate("x", "a")
+        });
+        let rholang = to_rholang(&bindings.build(), "Main").unwrap();
+
+        assert_eq!(rholang.matches("new x in {").count(), 3_000);
+        assert_eq!(rholang.matches('}').count(), 3_002);
+    }
+
+    #[test]
+    fn test_rules_are_unsupported() {
+        let graph = parse_to_ast("[= <a> | 0 <b> | 0]".into()).unwrap();
+
+        assert!(matches!(
+            to_rholang(&graph, "Main"),
+            Err(Error::Unsupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_translatable_reports_rule_path() {
+        let graph =
+            parse_to_ast("<a> | 0 * { x | [= <@{<b> | 0}> | 0 0] * let y = <@<c>> in 0 }".into())
+                .unwrap();
+
+        assert_eq!(
+            check_translatable(&graph),
+            Err(vec![
+                Untranslatable {
+                    path: vec![1, 0, 0],
+                    kind: NodeKind::RuleAnon,
+                    context: "rule".into(),
+                },
+                Untranslatable {
+                    path: vec![1, 1],
+                    kind: NodeKind::Nominate,
+                    context: "quoted name".into(),
+                },
+            ])
+        );
+        assert!(to_rholang(&graph, "Main").is_err());
+
+        let graph = parse_to_ast(r#"context "c" for @{0} in <a> | 0"#.into()).unwrap();
+        assert_eq!(check_translatable(&graph), Ok(()));
+        assert!(to_rholang(&graph, "Main").is_ok());
+    }
+
+    struct Annotated;
+
+    impl RholangStrategy for Annotated {
+        fn vertex(&self, channel: &str) -> String {
+            format!("/* <{channel}> */ {}", DefaultStrategy.vertex(channel))
+        }
+    }
+
+    #[test]
+    fn test_custom_strategy_comments_vertex_sends() {
+        let graph = parse_to_ast("<a> | x | 0 * let y = <b> in 0".into()).unwrap();
+
+        assert_eq!(
+            graph.to_rholang_with("Main", &DefaultStrategy).unwrap(),
+            to_rholang(&graph, "Main").unwrap()
+        );
+        assert_eq!(
+            graph.to_rholang_with("Main", &Annotated).unwrap(),
+            [
+                r#"contract @"Main"() = {"#,
+                "  new a, b, x in {",
+                "    /* <a> */ a!(Nil) |",
+                "    x!(Nil) |",
+                "    new y in {",
+                "      y!(*b)",
+                "    }",
+                "  }",
+                "}",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -55,6 +55,27 @@ enum WalkingStep<'a> {
     Graph(&'a Graph),
     /// A binding node to be processed
     Binding(&'a Binding),
+    /// A deferred `visit_nominate` call, used with [`BindingOrder::After`]
+    Nominate(&'a Binding),
+}
+
+/// Controls when `visit_nominate` fires relative to a binding's continuation.
+///
+/// A binding `let x = <v> in g` is reported through
+/// [`Visitor::visit_nominate`]. With the default [`BindingOrder::Before`] the
+/// visitor sees the binding first and then every node of `g`; with
+/// [`BindingOrder::After`] the nodes of `g` are visited first, so the visitor
+/// already knows what the binding feeds into when it is reported.
+///
+/// The option applies to nominated bindings (`Graph::Nominate`) and to the
+/// two bindings of every edge alike.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BindingOrder {
+    /// Visit the binding, then its continuation (pre-order)
+    #[default]
+    Before,
+    /// Visit the continuation, then the binding (post-order)
+    After,
 }
 
 /// A graph walker that traverses AST nodes using the visitor pattern.
@@ -98,6 +119,10 @@ enum WalkingStep<'a> {
 /// ```
 pub struct Walker<'graph> {
     graph: &'graph Graph,
+    binding_order: BindingOrder,
+    /// Work list kept between [`Walker::visit_mut`] runs so that its
+    /// allocation can be reused.
+    stack: Vec<WalkingStep<'graph>>,
 }
 
 impl<'graph> Walker<'graph> {
@@ -132,7 +157,26 @@ impl<'graph> Walker<'graph> {
     /// This constructor has O(1) time complexity as it only stores a reference
     /// to the graph without performing any preprocessing or validation.
     pub fn new(graph: &'graph Graph) -> Self {
-        Self { graph }
+        Self {
+            graph,
+            binding_order: BindingOrder::default(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Sets when bindings are reported relative to their continuation.
+    ///
+    /// See [`BindingOrder`] for the resulting visiting sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let walker = Walker::new(&my_graph).with_binding_order(BindingOrder::After);
+    /// let result = walker.visit(initial_accumulator, my_visitor);
+    /// ```
+    pub fn with_binding_order(mut self, binding_order: BindingOrder) -> Self {
+        self.binding_order = binding_order;
+        self
     }
 
     /// Performs the graph traversal, visiting each node with the provided visitor.
@@ -168,6 +212,9 @@ impl<'graph> Walker<'graph> {
     /// - Each node type delegates to the appropriate visitor method
     /// - Binding nodes are treated uniformly with graph nodes for consistent processing
     ///
+    /// Bindings are reported before their continuation unless another
+    /// [`BindingOrder`] was chosen with [`Walker::with_binding_order`].
+    ///
     /// # Visitor Method Mapping
     ///
     /// Each graph node type maps to a specific visitor method:
@@ -187,12 +234,75 @@ impl<'graph> Walker<'graph> {
             .unwrap_or_else(|e| match e {})
     }
 
+    /// Performs the graph traversal with a fallible visitor.
+    ///
+    /// The traversal order and visitor method mapping are the same as for
+    /// [`Walker::visit`]. The error type `E` is chosen by the visitor and is
+    /// returned to the caller exactly as produced, without boxing or
+    /// conversion, so any user-defined type can be used.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `A` - The accumulator type that will be threaded through the traversal
+    /// * `E` - The error type produced by the visitor
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error produced by a visitor method. No further nodes
+    /// are visited once an error has been returned.
     pub fn try_visit<A, E>(
         &self,
+        accumulator: A,
+        visitor: impl Visitor<'graph, A, E>,
+    ) -> Result<A, E> {
+        self.walk(&mut Vec::new(), accumulator, visitor)
+    }
+
+    /// Performs the same traversal as [`Walker::visit`], reusing the stack
+    /// buffer of earlier `visit_mut` and `try_visit_mut` calls.
+    ///
+    /// Tools that run many analyses over the same graph can keep one walker
+    /// and call this repeatedly; after the first run the stack has grown to
+    /// the size the graph needs and no longer reallocates.
+    pub fn visit_mut<A>(
+        &mut self,
+        accumulator: A,
+        visitor: impl Visitor<'graph, A, Infallible>,
+    ) -> A {
+        self.try_visit_mut(accumulator, visitor)
+            .unwrap_or_else(|e| match e {})
+    }
+
+    /// Performs the same traversal as [`Walker::try_visit`], reusing the
+    /// stack buffer of earlier `visit_mut` and `try_visit_mut` calls.
+    ///
+    /// Each run starts from the root, even if the previous one stopped at an
+    /// error: the work left over from that run is dropped, but the buffer
+    /// keeps its capacity, so there is nothing to reset between runs.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error produced by a visitor method. No further nodes
+    /// are visited once an error has been returned.
+    pub fn try_visit_mut<A, E>(
+        &mut self,
+        accumulator: A,
+        visitor: impl Visitor<'graph, A, E>,
+    ) -> Result<A, E> {
+        let mut stack = std::mem::take(&mut self.stack);
+        let result = self.walk(&mut stack, accumulator, visitor);
+        self.stack = stack;
+        result
+    }
+
+    fn walk<A, E>(
+        &self,
+        stack: &mut Vec<WalkingStep<'graph>>,
         mut accumulator: A,
         visitor: impl Visitor<'graph, A, E>,
     ) -> Result<A, E> {
-        let mut stack = vec![WalkingStep::Graph(self.graph)];
+        stack.clear();
+        stack.push(WalkingStep::Graph(self.graph));
 
         while let Some(el) = stack.pop() {
             accumulator = match el {
@@ -205,15 +315,18 @@ impl<'graph> Walker<'graph> {
                     stack.push(WalkingStep::Graph(graph));
                     visitor.visit_var(accumulator, var)?
                 }
-                WalkingStep::Graph(Graph::Nominate(
-                    binding @ Binding {
-                        graph,
-                        var: _,
-                        vertex: _,
-                    },
-                )) => {
-                    stack.push(WalkingStep::Graph(graph));
-                    visitor.visit_nominate(accumulator, binding)?
+                WalkingStep::Graph(Graph::Nominate(binding)) | WalkingStep::Binding(binding) => {
+                    match self.binding_order {
+                        BindingOrder::Before => {
+                            stack.push(WalkingStep::Graph(&binding.graph));
+                            visitor.visit_nominate(accumulator, binding)?
+                        }
+                        BindingOrder::After => {
+                            stack.push(WalkingStep::Nominate(binding));
+                            stack.push(WalkingStep::Graph(&binding.graph));
+                            accumulator
+                        }
+                    }
                 }
                 WalkingStep::Graph(Graph::EdgeAnon(
                     edge @ GEdgeAnon {
@@ -278,16 +391,7 @@ impl<'graph> Walker<'graph> {
                     stack.push(WalkingStep::Graph(graph));
                     visitor.visit_context(accumulator, context)?
                 }
-                WalkingStep::Binding(
-                    binding @ Binding {
-                        graph,
-                        var: _,
-                        vertex: _,
-                    },
-                ) => {
-                    stack.push(WalkingStep::Graph(graph));
-                    visitor.visit_nominate(accumulator, binding)?
-                }
+                WalkingStep::Nominate(binding) => visitor.visit_nominate(accumulator, binding)?,
             };
         }
 
@@ -297,6 +401,7 @@ impl<'graph> Walker<'graph> {
 
 #[cfg(test)]
 mod test {
+    use std::cell::Cell;
     use std::convert::Infallible;
     use std::fmt::Display;
 
@@ -313,11 +418,12 @@ mod test {
         Graph,
         GraphBinding,
         Name,
+        THREE_EDGE_GRAPH,
     };
     use crate::bindings::psGraph;
     use crate::parse_to_ast;
     use crate::visitor::Visitor;
-    use crate::walker::Walker;
+    use crate::walker::{BindingOrder, Walker};
 
     /// Test visitor implementation that generates XML-like output for graph nodes.
     ///
@@ -667,26 +773,7 @@ mod test {
     /// This comprehensive test validates complex traversal scenarios.
     #[test]
     fn test_linear_graph_with_3_edges() {
-        let graph: Graph = parse_to_ast(
-            "{
-                    (
-                      let n2 = <notification> in {
-                        (
-                          let e2 = <encryption> in {
-                            (
-                              let e1 = <encryption> in <encryption> | 0,
-                              let s = <store> in <store> | 0
-                            )
-                          } ,
-                          let n1 = <notification> in <notification> | 0
-                        )
-                      },
-                      let e3 = <encryption> in e1 | 0
-                    )
-                  }"
-            .into(),
-        )
-        .unwrap();
+        let graph: Graph = parse_to_ast(THREE_EDGE_GRAPH.into()).unwrap();
         let visitor = create_visitor();
         let walker = Walker::new(&graph);
         let accumulator = walker.visit(create_accumulator(), visitor);
@@ -727,6 +814,179 @@ mod test {
         );
     }
 
+    #[derive(Debug, PartialEq, Eq)]
+    enum MyErr {
+        TensorFound { operands: usize },
+        UnexpectedVar { var: String },
+    }
+
+    struct TensorRejectingVisitor;
+
+    impl<'a> Visitor<'a, usize, MyErr> for TensorRejectingVisitor {
+        fn visit_vertex(&self, acc: usize, _vertex: &'a GVertex) -> Result<usize, MyErr> {
+            Ok(acc + 1)
+        }
+
+        fn visit_tensor(&self, _acc: usize, _tensor: &'a GTensor) -> Result<usize, MyErr> {
+            Err(MyErr::TensorFound { operands: 2 })
+        }
+    }
+
+    /// Visitor rejecting variables outside `allowed`, counting the variables
+    /// and vertices it is shown.
+    struct VarCheckingVisitor<'v> {
+        allowed: &'static [&'static str],
+        visits: &'v Cell<usize>,
+    }
+
+    impl<'a> Visitor<'a, usize, MyErr> for VarCheckingVisitor<'_> {
+        fn visit_vertex(&self, acc: usize, _vertex: &'a GVertex) -> Result<usize, MyErr> {
+            self.visits.set(self.visits.get() + 1);
+            Ok(acc)
+        }
+
+        fn visit_var(&self, acc: usize, var: &'a GVar) -> Result<usize, MyErr> {
+            self.visits.set(self.visits.get() + 1);
+            if self.allowed.contains(&var.var.as_str()) {
+                Ok(acc + 1)
+            } else {
+                Err(MyErr::UnexpectedVar {
+                    var: var.var.clone(),
+                })
+            }
+        }
+    }
+
+    /// Tests that a user-defined error type is propagated from the visitor
+    /// to the caller unchanged.
+    #[test]
+    fn test_custom_error_propagates_from_visit_tensor() {
+        let graph = parse_to_ast("<a> | 0 * <b> | 0".into()).unwrap();
+        let walker = Walker::new(&graph);
+
+        let result: Result<usize, MyErr> = walker.try_visit(0, TensorRejectingVisitor);
+
+        assert_eq!(result, Err(MyErr::TensorFound { operands: 2 }));
+    }
+
+    /// Tests that a fallible visitor which never fails yields its accumulator.
+    #[test]
+    fn test_custom_error_visitor_without_errors() {
+        let graph = parse_to_ast("<a> | <b> | 0".into()).unwrap();
+        let walker = Walker::new(&graph);
+
+        assert_eq!(walker.try_visit(0, TensorRejectingVisitor), Ok(2));
+    }
+
+    /// Visitor recording the order in which bindings and vertices are reported.
+    struct OrderVisitor;
+
+    impl<'a> Visitor<'a, Vec<String>, Infallible> for OrderVisitor {
+        fn visit_nil(&self, mut acc: Vec<String>) -> Result<Vec<String>, Infallible> {
+            acc.push("nil".into());
+            Ok(acc)
+        }
+
+        fn visit_vertex(
+            &self,
+            mut acc: Vec<String>,
+            vertex: &'a GVertex,
+        ) -> Result<Vec<String>, Infallible> {
+            if let Name::VVar { value } = &vertex.vertex.name {
+                acc.push(format!("vertex {value}"));
+            }
+            Ok(acc)
+        }
+
+        fn visit_nominate(
+            &self,
+            mut acc: Vec<String>,
+            binding: &'a Binding,
+        ) -> Result<Vec<String>, Infallible> {
+            acc.push(format!("nominate {}", binding.var));
+            Ok(acc)
+        }
+    }
+
+    #[test]
+    fn test_binding_order_before() {
+        let graph = parse_to_ast("let a = <a> in <b> | 0".into()).unwrap();
+
+        let order = Walker::new(&graph)
+            .with_binding_order(BindingOrder::Before)
+            .visit(Vec::new(), OrderVisitor);
+
+        assert_eq!(order, ["nominate a", "vertex b", "nil"]);
+    }
+
+    #[test]
+    fn test_binding_order_after() {
+        let graph = parse_to_ast("let a = <a> in <b> | 0".into()).unwrap();
+
+        let order = Walker::new(&graph)
+            .with_binding_order(BindingOrder::After)
+            .visit(Vec::new(), OrderVisitor);
+
+        assert_eq!(order, ["vertex b", "nil", "nominate a"]);
+    }
+
+    #[test]
+    fn test_binding_order_after_in_edge() {
+        let graph = parse_to_ast("(let a = <a> in <c> | 0, let b = <b> in 0)".into()).unwrap();
+
+        let order = Walker::new(&graph)
+            .with_binding_order(BindingOrder::After)
+            .visit(Vec::new(), OrderVisitor);
+
+        assert_eq!(
+            order,
+            ["vertex c", "nil", "nominate a", "nil", "nominate b"]
+        );
+    }
+
+    #[test]
+    fn test_try_visit_stops_at_unexpected_var() {
+        let graph = parse_to_ast("x | y | z | 0 * <a> | w | 0".into()).unwrap();
+        let mut walker = Walker::new(&graph);
+        let visits = Cell::new(0);
+        let visitor = |allowed| VarCheckingVisitor {
+            allowed,
+            visits: &visits,
+        };
+
+        assert_eq!(
+            walker.try_visit_mut(0, visitor(&["x", "z", "w"])),
+            Err(MyErr::UnexpectedVar { var: "y".into() })
+        );
+        assert_eq!(visits.replace(0), 2);
+
+        assert_eq!(
+            walker.try_visit_mut(0, visitor(&["x", "y", "z", "w"])),
+            Ok(4)
+        );
+        assert_eq!(visits.get(), 5);
+    }
+
+    #[test]
+    fn test_visit_mut_restarts_after_error() {
+        let graph = parse_to_ast("let a = <a> in { <b> | 0 * <c> | 0 }".into()).unwrap();
+        let mut walker = Walker::new(&graph);
+
+        assert!(walker.try_visit_mut(0, TensorRejectingVisitor).is_err());
+        assert!(!walker.stack.is_empty());
+        assert_eq!(
+            walker.visit_mut(Vec::new(), OrderVisitor),
+            ["nominate a", "vertex b", "nil", "vertex c", "nil"]
+        );
+
+        let capacity = walker.stack.capacity();
+        assert_eq!(
+            walker.visit_mut(Vec::new(), OrderVisitor),
+            walker.visit(Vec::new(), OrderVisitor)
+        );
+        assert_eq!(walker.stack.capacity(), capacity);
+    }
+
     /// Tests walker behavior with a context node.
     ///
     /// Verifies that the walker correctly processes a context node that provides